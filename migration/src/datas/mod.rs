@@ -5,3 +5,8 @@ pub mod m20241024_034305_insert_sys_role_menu;
 pub mod m20241024_034526_insert_sys_role;
 pub mod m20241024_034744_insert_sys_menu;
 pub mod m20241024_082926_insert_casbin_rule;
+pub mod m20241201_000003_insert_casbin_rule_token;
+pub mod m20241201_000007_insert_casbin_rule_session;
+pub mod m20241201_000020_insert_casbin_rule_access_key;
+pub mod m20241201_000023_insert_casbin_rule_access_key_stats;
+pub mod m20241201_000024_insert_casbin_rule_access_key_scopes;
@@ -30,6 +30,34 @@ impl MigratorTrait for Migrator {
             Box::new(datas::m20241024_033933_insert_sys_user_role::Migration),
             Box::new(datas::m20241024_034305_insert_sys_role_menu::Migration),
             Box::new(datas::m20241024_082926_insert_casbin_rule::Migration),
+            // 增量架构迁移
+            Box::new(schemas::m20241201_000001_alter_sys_tokens_add_family_id::Migration),
+            Box::new(schemas::m20241201_000002_alter_sys_tokens_add_jti::Migration),
+            Box::new(datas::m20241201_000003_insert_casbin_rule_token::Migration),
+            Box::new(schemas::m20241201_000004_alter_sys_login_log_add_status::Migration),
+            Box::new(schemas::m20241201_000005_create_sys_user_totp::Migration),
+            Box::new(schemas::m20241201_000006_alter_sys_domain_add_mfa_required_roles::Migration),
+            Box::new(datas::m20241201_000007_insert_casbin_rule_session::Migration),
+            Box::new(
+                schemas::m20241201_000008_alter_sys_domain_add_login_identifier_types::Migration,
+            ),
+            Box::new(schemas::m20241201_000009_alter_sys_login_log_add_identifier_type::Migration),
+            Box::new(schemas::m20241201_000010_alter_sys_user_add_password_fields::Migration),
+            Box::new(schemas::m20241201_000011_create_sys_user_password_history::Migration),
+            Box::new(schemas::m20241201_000012_alter_sys_domain_add_password_policy::Migration),
+            Box::new(schemas::m20241201_000013_create_sys_user_identity::Migration),
+            Box::new(schemas::m20241201_000014_alter_sys_operation_log_add_actor_id::Migration),
+            Box::new(schemas::m20241201_000015_alter_sys_tokens_add_audience::Migration),
+            Box::new(schemas::m20241201_000016_create_sys_user_login_location::Migration),
+            Box::new(schemas::m20241201_000017_alter_sys_domain_add_login_risk_policy::Migration),
+            Box::new(schemas::m20241201_000018_create_sys_user_credential::Migration),
+            Box::new(schemas::m20241201_000019_alter_sys_access_key_add_previous_secret::Migration),
+            Box::new(datas::m20241201_000020_insert_casbin_rule_access_key::Migration),
+            Box::new(schemas::m20241201_000021_alter_sys_access_key_add_usage_fields::Migration),
+            Box::new(schemas::m20241201_000022_create_sys_access_key_usage::Migration),
+            Box::new(datas::m20241201_000023_insert_casbin_rule_access_key_stats::Migration),
+            Box::new(datas::m20241201_000024_insert_casbin_rule_access_key_scopes::Migration),
+            Box::new(schemas::m20241201_000025_alter_sys_tokens_add_actor_id::Migration),
         ]
     }
 }
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysLoginLog::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysLoginLog::Status)
+                            .string()
+                            .not_null()
+                            .default("SUCCESS"),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysLoginLog::FailureReason).string().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysLoginLog::Table)
+                    .drop_column(SysLoginLog::FailureReason)
+                    .drop_column(SysLoginLog::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysLoginLog {
+    Table,
+    Status,
+    FailureReason,
+}
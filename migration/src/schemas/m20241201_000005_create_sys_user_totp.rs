@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysUserTotp::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysUserTotp::UserId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SysUserTotp::Secret).string().not_null())
+                    .col(
+                        ColumnDef::new(SysUserTotp::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserTotp::RecoveryCodes)
+                            .json_binary()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(SysUserTotp::ConfirmedAt).timestamp().null())
+                    .col(
+                        ColumnDef::new(SysUserTotp::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(SysUserTotp::UpdatedAt).timestamp().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysUserTotp::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysUserTotp {
+    Table,
+    UserId,
+    Secret,
+    Enabled,
+    RecoveryCodes,
+    ConfirmedAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysUserIdentity::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysUserIdentity::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SysUserIdentity::UserId).string().not_null())
+                    .col(
+                        ColumnDef::new(SysUserIdentity::Provider)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SysUserIdentity::Subject).string().not_null())
+                    .col(ColumnDef::new(SysUserIdentity::Email).string().null())
+                    .col(
+                        ColumnDef::new(SysUserIdentity::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserIdentity::LastLoginAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(SysUserIdentity::Table)
+                    .name("idx_sys_user_identity_provider_subject")
+                    .col(SysUserIdentity::Provider)
+                    .col(SysUserIdentity::Subject)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(SysUserIdentity::Table)
+                    .name("idx_sys_user_identity_user_id")
+                    .col(SysUserIdentity::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysUserIdentity::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysUserIdentity {
+    Table,
+    Id,
+    UserId,
+    Provider,
+    Subject,
+    Email,
+    CreatedAt,
+    LastLoginAt,
+}
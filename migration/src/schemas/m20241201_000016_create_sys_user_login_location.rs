@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysUserLoginLocation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysUserLoginLocation::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserLoginLocation::UserId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SysUserLoginLocation::Ip).string().not_null())
+                    .col(
+                        ColumnDef::new(SysUserLoginLocation::Country)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserLoginLocation::Province)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserLoginLocation::City)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserLoginLocation::UserAgentFamily)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserLoginLocation::RiskSignals)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserLoginLocation::LoginAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(SysUserLoginLocation::Table)
+                    .name("idx_sys_user_login_location_user_id_login_at")
+                    .col(SysUserLoginLocation::UserId)
+                    .col(SysUserLoginLocation::LoginAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysUserLoginLocation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysUserLoginLocation {
+    Table,
+    Id,
+    UserId,
+    Ip,
+    Country,
+    Province,
+    City,
+    UserAgentFamily,
+    RiskSignals,
+    LoginAt,
+}
@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysUserCredential::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysUserCredential::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserCredential::UserId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserCredential::CredentialId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserCredential::Passkey)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SysUserCredential::Aaguid).string().not_null())
+                    .col(
+                        ColumnDef::new(SysUserCredential::Transports)
+                            .string()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(SysUserCredential::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(SysUserCredential::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserCredential::LastUsedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(SysUserCredential::Table)
+                    .name("idx_sys_user_credential_credential_id")
+                    .col(SysUserCredential::CredentialId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(SysUserCredential::Table)
+                    .name("idx_sys_user_credential_user_id")
+                    .col(SysUserCredential::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysUserCredential::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysUserCredential {
+    Table,
+    Id,
+    UserId,
+    CredentialId,
+    Passkey,
+    Aaguid,
+    Transports,
+    Name,
+    CreatedAt,
+    LastUsedAt,
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysAccessKey::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysAccessKey::PreviousAccessKeySecret)
+                            .string()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysAccessKey::PreviousSecretExpiresAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysAccessKey::RotatedAt).timestamp().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysAccessKey::Table)
+                    .drop_column(SysAccessKey::PreviousAccessKeySecret)
+                    .drop_column(SysAccessKey::PreviousSecretExpiresAt)
+                    .drop_column(SysAccessKey::RotatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysAccessKey {
+    Table,
+    PreviousAccessKeySecret,
+    PreviousSecretExpiresAt,
+    RotatedAt,
+}
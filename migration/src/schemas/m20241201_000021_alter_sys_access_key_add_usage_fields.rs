@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysAccessKey::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysAccessKey::ExpiresAt).timestamp().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysAccessKey::LastUsedAt).timestamp().null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysAccessKey::LastUsedIp).string().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysAccessKey::Table)
+                    .drop_column(SysAccessKey::ExpiresAt)
+                    .drop_column(SysAccessKey::LastUsedAt)
+                    .drop_column(SysAccessKey::LastUsedIp)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysAccessKey {
+    Table,
+    ExpiresAt,
+    LastUsedAt,
+    LastUsedIp,
+}
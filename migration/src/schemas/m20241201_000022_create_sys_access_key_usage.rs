@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysAccessKeyUsage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysAccessKeyUsage::AccessKeyId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysAccessKeyUsage::UsageDate)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysAccessKeyUsage::RequestCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SysAccessKeyUsage::SuccessCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SysAccessKeyUsage::BadSignatureCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SysAccessKeyUsage::ReplayedNonceCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SysAccessKeyUsage::ExpiredTimestampCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SysAccessKeyUsage::ExpiredKeyCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(SysAccessKeyUsage::AccessKeyId)
+                            .col(SysAccessKeyUsage::UsageDate),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysAccessKeyUsage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysAccessKeyUsage {
+    Table,
+    AccessKeyId,
+    UsageDate,
+    RequestCount,
+    SuccessCount,
+    BadSignatureCount,
+    ReplayedNonceCount,
+    ExpiredTimestampCount,
+    ExpiredKeyCount,
+}
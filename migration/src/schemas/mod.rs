@@ -11,3 +11,23 @@ pub mod m20241023_091155_create_sys_organization;
 pub mod m20241023_091159_create_sys_role_menu;
 pub mod m20241023_091204_create_sys_tokens;
 pub mod m20241023_091210_create_sys_user_role;
+pub mod m20241201_000001_alter_sys_tokens_add_family_id;
+pub mod m20241201_000002_alter_sys_tokens_add_jti;
+pub mod m20241201_000004_alter_sys_login_log_add_status;
+pub mod m20241201_000005_create_sys_user_totp;
+pub mod m20241201_000006_alter_sys_domain_add_mfa_required_roles;
+pub mod m20241201_000008_alter_sys_domain_add_login_identifier_types;
+pub mod m20241201_000009_alter_sys_login_log_add_identifier_type;
+pub mod m20241201_000010_alter_sys_user_add_password_fields;
+pub mod m20241201_000011_create_sys_user_password_history;
+pub mod m20241201_000012_alter_sys_domain_add_password_policy;
+pub mod m20241201_000013_create_sys_user_identity;
+pub mod m20241201_000014_alter_sys_operation_log_add_actor_id;
+pub mod m20241201_000015_alter_sys_tokens_add_audience;
+pub mod m20241201_000016_create_sys_user_login_location;
+pub mod m20241201_000017_alter_sys_domain_add_login_risk_policy;
+pub mod m20241201_000018_create_sys_user_credential;
+pub mod m20241201_000019_alter_sys_access_key_add_previous_secret;
+pub mod m20241201_000021_alter_sys_access_key_add_usage_fields;
+pub mod m20241201_000022_create_sys_access_key_usage;
+pub mod m20241201_000025_alter_sys_tokens_add_actor_id;
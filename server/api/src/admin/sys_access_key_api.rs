@@ -4,19 +4,44 @@ use axum::{
     extract::{Path, Query},
     Extension,
 };
+use axum_casbin::{casbin::MgmtApi, CasbinAxumLayer};
 use server_core::web::{error::AppError, page::PaginatedData, res::Res, validator::ValidatedForm};
 use server_service::admin::{
-    AccessKeyPageRequest, CreateAccessKeyInput, SysAccessKeyModel, SysAccessKeyService,
-    TAccessKeyService,
+    access_key_policies_changed, AccessKeyOutput, AccessKeyPageRequest, AccessKeyPolicyOutput,
+    AccessKeyScopeInput, AccessKeyUsageRequest, CreateAccessKeyInput, SysAccessKeyModel,
+    SysAccessKeyService, SysAccessKeyUsageModel, TAccessKeyService, UpdateAccessKeyInput,
 };
 
+fn casbin_error(err: impl std::fmt::Display) -> AppError {
+    AppError {
+        code: 500,
+        message: err.to_string(),
+    }
+}
+
+/// 删除以访问密钥 ID 为主体的全部策略，不限所属域，并通知其他实例重新加载
+async fn remove_key_policies(
+    cache_enforcer: &mut CasbinAxumLayer,
+    access_key_id: &str,
+) -> Result<(), AppError> {
+    let enforcer = cache_enforcer.get_enforcer();
+    enforcer
+        .write()
+        .await
+        .remove_filtered_policy(0, vec![access_key_id.to_string()])
+        .await
+        .map_err(casbin_error)?;
+    access_key_policies_changed().await;
+    Ok(())
+}
+
 pub struct SysAccessKeyApi;
 
 impl SysAccessKeyApi {
     pub async fn get_paginated_access_keys(
         Query(params): Query<AccessKeyPageRequest>,
         Extension(service): Extension<Arc<SysAccessKeyService>>,
-    ) -> Result<Res<PaginatedData<SysAccessKeyModel>>, AppError> {
+    ) -> Result<Res<PaginatedData<AccessKeyOutput>>, AppError> {
         service
             .find_paginated_access_keys(params)
             .await
@@ -30,10 +55,120 @@ impl SysAccessKeyApi {
         service.create_access_key(input).await.map(Res::new_data)
     }
 
+    pub async fn update_access_key(
+        Extension(service): Extension<Arc<SysAccessKeyService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        ValidatedForm(input): ValidatedForm<UpdateAccessKeyInput>,
+    ) -> Result<Res<SysAccessKeyModel>, AppError> {
+        let previous = service.get_access_key(&input.id).await?;
+        let updated = service.update_access_key(input).await?;
+        // 授权范围属于原来的域，换域后需要重新授权
+        if previous.domain != updated.domain {
+            remove_key_policies(&mut cache_enforcer, &updated.access_key_id).await?;
+        }
+        Ok(Res::new_data(updated))
+    }
+
     pub async fn delete_access_key(
         Path(id): Path<String>,
         Extension(service): Extension<Arc<SysAccessKeyService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
     ) -> Result<Res<()>, AppError> {
-        service.delete_access_key(&id).await.map(Res::new_data)
+        let access_key = service.get_access_key(&id).await?;
+        service.delete_access_key(&id).await?;
+        remove_key_policies(&mut cache_enforcer, &access_key.access_key_id).await?;
+        Ok(Res::new_data(()))
+    }
+
+    pub async fn get_access_key_scopes(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysAccessKeyService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+    ) -> Result<Res<Vec<AccessKeyPolicyOutput>>, AppError> {
+        let access_key = service.get_access_key(&id).await?;
+        let enforcer = cache_enforcer.get_enforcer();
+        let enforcer_read = enforcer.read().await;
+
+        let mut policies: Vec<AccessKeyPolicyOutput> = enforcer_read
+            .get_filtered_policy(0, vec![access_key.access_key_id, access_key.domain])
+            .into_iter()
+            .map(|policy| {
+                let mut values = policy.into_iter().skip(2);
+                AccessKeyPolicyOutput {
+                    path: values.next().unwrap_or_default(),
+                    method: values.next().unwrap_or_default(),
+                }
+            })
+            .collect();
+        policies.sort();
+
+        Ok(Res::new_data(policies))
+    }
+
+    /// 以给定的授权范围整体替换访问密钥在所属域内的策略
+    pub async fn update_access_key_scopes(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysAccessKeyService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        ValidatedForm(input): ValidatedForm<AccessKeyScopeInput>,
+    ) -> Result<Res<Vec<AccessKeyPolicyOutput>>, AppError> {
+        let (access_key, policies) = service.resolve_access_key_policies(&id, input).await?;
+        let rules: Vec<Vec<String>> = policies
+            .iter()
+            .map(|policy| {
+                vec![
+                    access_key.access_key_id.clone(),
+                    access_key.domain.clone(),
+                    policy.path.clone(),
+                    policy.method.clone(),
+                ]
+            })
+            .collect();
+
+        // 在同一把写锁内先删后加，避免请求看到授权为空的中间状态
+        let enforcer = cache_enforcer.get_enforcer();
+        let mut enforcer_write = enforcer.write().await;
+        enforcer_write
+            .remove_filtered_policy(0, vec![access_key.access_key_id.clone()])
+            .await
+            .map_err(casbin_error)?;
+        if !rules.is_empty() {
+            enforcer_write
+                .add_policies(rules)
+                .await
+                .map_err(casbin_error)?;
+        }
+        drop(enforcer_write);
+        access_key_policies_changed().await;
+
+        Ok(Res::new_data(policies))
+    }
+
+    pub async fn rotate_access_key_secret(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysAccessKeyService>>,
+    ) -> Result<Res<SysAccessKeyModel>, AppError> {
+        service
+            .rotate_access_key_secret(&id)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn retire_previous_secret(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysAccessKeyService>>,
+    ) -> Result<Res<SysAccessKeyModel>, AppError> {
+        service.retire_previous_secret(&id).await.map(Res::new_data)
+    }
+
+    pub async fn get_access_key_usage(
+        Path(id): Path<String>,
+        Query(params): Query<AccessKeyUsageRequest>,
+        Extension(service): Extension<Arc<SysAccessKeyService>>,
+    ) -> Result<Res<Vec<SysAccessKeyUsageModel>>, AppError> {
+        service
+            .find_access_key_usage(&id, params)
+            .await
+            .map(Res::new_data)
     }
 }
@@ -1,18 +1,36 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, str::FromStr, sync::Arc};
 
-use axum::{extract::ConnectInfo, http::HeaderMap, Extension};
+use axum::{
+    extract::{ConnectInfo, Path},
+    http::HeaderMap,
+    response::IntoResponse,
+    Extension, Json,
+};
 use axum_extra::{headers::UserAgent, TypedHeader};
 use server_core::web::{
-    auth::User, error::AppError, res::Res, util::ClientIp, validator::ValidatedForm, RequestId,
+    auth::User, error::AppError, jwt::JwtUtils, res::Res, util::ClientIp, validator::ValidatedForm,
+    RequestId,
 };
 use server_service::{
     admin::{
-        dto::sys_auth_dto::LoginContext, AuthOutput, LoginInput, SysAuthService, TAuthService,
-        UserInfoOutput, UserRoute,
+        dto::sys_auth_dto::LoginContext, AuthOutput, LoginInput, LoginOutput, MfaEnrollInput,
+        MfaVerifyInput, OidcAuthorizeOutput, OidcCallbackInput, PasskeyLoginInput,
+        PasskeyLoginOptionsInput, PasskeyMfaOptionsInput, PasskeyMfaVerifyInput,
+        PasskeyRequestOptionsOutput, RefreshTokenInput, SysAuthService, TAuthService,
+        TotpEnrollOutput, UserInfoOutput, UserRoute,
     },
     Audience,
 };
 
+const DOMAIN_HEADER: &str = "x-domain";
+const AUDIENCE_HEADER: &str = "x-audience";
+const LOGIN_TYPE_HEADER: &str = "x-login-type";
+const MAX_HEADER_VALUE_LENGTH: usize = 50;
+const DEFAULT_DOMAIN: &str = "built-in";
+const DEFAULT_LOGIN_TYPE: &str = "PC";
+/// 客户端可以声明的登录类型，模拟登录等由服务端设置的类型不在其中
+const CLIENT_LOGIN_TYPES: &[&str] = &["PC", "H5", "APP", "MINI_PROGRAM"];
+
 pub struct SysAuthenticationApi;
 
 impl SysAuthenticationApi {
@@ -23,9 +41,205 @@ impl SysAuthenticationApi {
         Extension(request_id): Extension<RequestId>,
         Extension(service): Extension<Arc<SysAuthService>>,
         ValidatedForm(input): ValidatedForm<LoginInput>,
+    ) -> Result<Res<LoginOutput>, AppError> {
+        let mut login_context =
+            Self::build_login_context(addr, &headers, &user_agent, &request_id)?;
+        // 请求体中的参数优先于请求头
+        if let Some(domain) = &input.domain {
+            login_context.domain = domain.clone();
+        }
+        if let Some(audience) = &input.audience {
+            login_context.audience = Self::parse_audience(audience)?;
+        }
+        if let Some(login_type) = &input.login_type {
+            login_context.login_type = Self::parse_login_type(login_type)?;
+        }
+
+        service
+            .pwd_login(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn verify_mfa_handler(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<MfaVerifyInput>,
+    ) -> Result<Res<AuthOutput>, AppError> {
+        let login_context = Self::build_login_context(addr, &headers, &user_agent, &request_id)?;
+
+        service
+            .verify_mfa(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn oidc_authorize_handler(
+        Path(provider): Path<String>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+    ) -> Result<Res<OidcAuthorizeOutput>, AppError> {
+        service.oidc_authorize(&provider).await.map(Res::new_data)
+    }
+
+    pub async fn oidc_callback_handler(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        Path(provider): Path<String>,
+        ValidatedForm(input): ValidatedForm<OidcCallbackInput>,
+    ) -> Result<Res<LoginOutput>, AppError> {
+        let login_context = Self::build_login_context(addr, &headers, &user_agent, &request_id)?;
+
+        service
+            .oidc_login(&provider, input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn enroll_mfa_handler(
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<MfaEnrollInput>,
+    ) -> Result<Res<TotpEnrollOutput>, AppError> {
+        service.enroll_mfa(input).await.map(Res::new_data)
+    }
+
+    pub async fn passkey_login_options_handler(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<PasskeyLoginOptionsInput>,
+    ) -> Result<Res<PasskeyRequestOptionsOutput>, AppError> {
+        let mut login_context =
+            Self::build_login_context(addr, &headers, &user_agent, &request_id)?;
+        if let Some(domain) = &input.domain {
+            login_context.domain = domain.clone();
+        }
+
+        service
+            .passkey_login_options(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn passkey_login_handler(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<PasskeyLoginInput>,
+    ) -> Result<Res<AuthOutput>, AppError> {
+        let mut login_context =
+            Self::build_login_context(addr, &headers, &user_agent, &request_id)?;
+        // 请求体中的参数优先于请求头
+        if let Some(domain) = &input.domain {
+            login_context.domain = domain.clone();
+        }
+        if let Some(audience) = &input.audience {
+            login_context.audience = Self::parse_audience(audience)?;
+        }
+
+        service
+            .passkey_login(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn passkey_mfa_options_handler(
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<PasskeyMfaOptionsInput>,
+    ) -> Result<Res<PasskeyRequestOptionsOutput>, AppError> {
+        service.passkey_mfa_options(input).await.map(Res::new_data)
+    }
+
+    pub async fn verify_passkey_mfa_handler(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<PasskeyMfaVerifyInput>,
+    ) -> Result<Res<AuthOutput>, AppError> {
+        let login_context = Self::build_login_context(addr, &headers, &user_agent, &request_id)?;
+
+        service
+            .verify_passkey_mfa(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn refresh_token_handler(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<RefreshTokenInput>,
+    ) -> Result<Res<AuthOutput>, AppError> {
+        let login_context = Self::build_login_context(addr, &headers, &user_agent, &request_id)?;
+
+        service
+            .refresh_token(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn logout(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+    ) -> Result<Res<()>, AppError> {
+        match user.jti() {
+            Some(jti) => service.logout(&jti).await.map(Res::new_data),
+            None => Ok(Res::new_data(())),
+        }
+    }
+
+    /// 管理员以目标用户身份登录，返回的令牌携带管理员 ID
+    pub async fn impersonate(
+        Path(user_id): Path<String>,
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysAuthService>>,
     ) -> Result<Res<AuthOutput>, AppError> {
+        let login_context = Self::build_login_context(addr, &headers, &user_agent, &request_id)?;
+
+        service
+            .impersonate(&user, &user_id, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn stop_impersonation(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+    ) -> Result<Res<()>, AppError> {
+        service.stop_impersonation(&user).await.map(Res::new_data)
+    }
+
+    /// 公布 JWT 验证公钥（RFC 7517），按标准格式返回，不包装为统一响应
+    pub async fn jwks() -> Result<impl IntoResponse, AppError> {
+        JwtUtils::jwks().await.map(Json).map_err(AppError::from)
+    }
+
+    /// 构建登录上下文，域、受众与登录类型从请求头解析，未提供时使用默认值
+    fn build_login_context(
+        addr: SocketAddr,
+        headers: &HeaderMap,
+        user_agent: &UserAgent,
+        request_id: &RequestId,
+    ) -> Result<LoginContext, AppError> {
         let client_ip = {
-            let header_ip = ClientIp::get_real_ip(&headers);
+            let header_ip = ClientIp::get_real_ip(headers);
             if header_ip == "unknown" {
                 addr.ip().to_string()
             } else {
@@ -36,33 +250,64 @@ impl SysAuthenticationApi {
         let address = xdb::searcher::search_by_ip(client_ip.as_str())
             .unwrap_or_else(|_| "Unknown Location".to_string());
 
-        let login_context = LoginContext {
+        let audience = match Self::header_value(headers, AUDIENCE_HEADER) {
+            Some(audience) => Self::parse_audience(&audience)?,
+            None => Audience::ManagementPlatform,
+        };
+        let login_type = match Self::header_value(headers, LOGIN_TYPE_HEADER) {
+            Some(login_type) => Self::parse_login_type(&login_type)?,
+            None => DEFAULT_LOGIN_TYPE.to_string(),
+        };
+
+        Ok(LoginContext {
             client_ip,
             client_port: Some(addr.port() as i32),
             address,
             user_agent: user_agent.as_str().to_string(),
             request_id: request_id.to_string(),
-            audience: Audience::ManagementPlatform,
-            login_type: "PC".to_string(),
-            domain: "built-in".to_string(),
-        };
+            audience,
+            login_type,
+            domain: Self::header_domain(headers),
+        })
+    }
 
-        service
-            .pwd_login(input, login_context)
-            .await
-            .map(Res::new_data)
+    /// 请求头中的登录域，未指定时为内置域
+    pub(crate) fn header_domain(headers: &HeaderMap) -> String {
+        Self::header_value(headers, DOMAIN_HEADER).unwrap_or_else(|| DEFAULT_DOMAIN.to_string())
+    }
+
+    fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty() && value.len() <= MAX_HEADER_VALUE_LENGTH)
+            .map(ToString::to_string)
+    }
+
+    fn parse_audience(audience: &str) -> Result<Audience, AppError> {
+        Audience::from_str(audience).map_err(|_| AppError {
+            code: 400,
+            message: format!("Invalid audience: {}", audience),
+        })
+    }
+
+    fn parse_login_type(login_type: &str) -> Result<String, AppError> {
+        CLIENT_LOGIN_TYPES
+            .iter()
+            .find(|allowed| allowed.eq_ignore_ascii_case(login_type))
+            .map(|allowed| allowed.to_string())
+            .ok_or_else(|| AppError {
+                code: 400,
+                message: format!("Invalid login type: {}", login_type),
+            })
     }
 
     pub async fn get_user_info(
         Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysAuthService>>,
     ) -> Result<Res<UserInfoOutput>, AppError> {
-        let user_info = UserInfoOutput {
-            user_id: user.user_id(),
-            user_name: user.username(),
-            roles: user.subject(),
-        };
-
-        Ok(Res::new_data(user_info))
+        service.get_user_info(&user).await.map(Res::new_data)
     }
 
     pub async fn get_user_routes(
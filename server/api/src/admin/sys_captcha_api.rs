@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use axum::Extension;
+use server_core::web::{error::AppError, res::Res};
+use server_service::admin::{CaptchaOutput, SysCaptchaService, TCaptchaService};
+
+pub struct SysCaptchaApi;
+
+impl SysCaptchaApi {
+    pub async fn generate(
+        Extension(service): Extension<Arc<SysCaptchaService>>,
+    ) -> Result<Res<CaptchaOutput>, AppError> {
+        service.generate().await.map(Res::new_data)
+    }
+}
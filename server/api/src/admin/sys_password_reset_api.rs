@@ -0,0 +1,42 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{extract::ConnectInfo, http::HeaderMap, Extension};
+use server_core::web::{error::AppError, res::Res, util::ClientIp, validator::ValidatedForm};
+use server_service::admin::{
+    ForgotPasswordInput, ResetPasswordInput, SysPasswordResetService, TPasswordResetService,
+};
+
+use super::sys_authentication_api::SysAuthenticationApi;
+
+pub struct SysPasswordResetApi;
+
+impl SysPasswordResetApi {
+    pub async fn forgot_password(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        Extension(service): Extension<Arc<SysPasswordResetService>>,
+        ValidatedForm(input): ValidatedForm<ForgotPasswordInput>,
+    ) -> Result<Res<()>, AppError> {
+        let client_ip = match ClientIp::get_real_ip(&headers) {
+            ip if ip == "unknown" => addr.ip().to_string(),
+            ip => ip,
+        };
+        // 与登录一致，请求体中的登录域优先于请求头
+        let domain = input
+            .domain
+            .clone()
+            .unwrap_or_else(|| SysAuthenticationApi::header_domain(&headers));
+
+        service
+            .forgot_password(input, &domain, &client_ip)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn reset_password(
+        Extension(service): Extension<Arc<SysPasswordResetService>>,
+        ValidatedForm(input): ValidatedForm<ResetPasswordInput>,
+    ) -> Result<Res<()>, AppError> {
+        service.reset_password(input).await.map(Res::new_data)
+    }
+}
@@ -10,4 +10,8 @@ impl SysSandboxApi {
     pub async fn test_complex_api_key() -> Result<Res<String>, AppError> {
         Ok(Res::new_data("ComplexApiKey".to_string()))
     }
+
+    pub async fn test_signed_api_key() -> Result<Res<String>, AppError> {
+        Ok(Res::new_data("SignedApiKey".to_string()))
+    }
 }
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    Extension,
+};
+use server_core::web::{auth::User, error::AppError, page::PaginatedData, res::Res};
+use server_service::admin::{SessionOutput, SessionPageRequest, SysTokenService, TTokenService};
+
+pub struct SysTokenApi;
+
+impl SysTokenApi {
+    pub async fn revoke_user_tokens(
+        Path(user_id): Path<String>,
+        Extension(service): Extension<Arc<SysTokenService>>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .revoke_user_tokens(&user_id)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn get_paginated_sessions(
+        Query(params): Query<SessionPageRequest>,
+        Extension(service): Extension<Arc<SysTokenService>>,
+    ) -> Result<Res<PaginatedData<SessionOutput>>, AppError> {
+        service
+            .find_paginated_sessions(params)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn revoke_session(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysTokenService>>,
+    ) -> Result<Res<()>, AppError> {
+        service.revoke_session(&id).await.map(Res::new_data)
+    }
+
+    pub async fn get_my_sessions(
+        Query(params): Query<SessionPageRequest>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysTokenService>>,
+    ) -> Result<Res<PaginatedData<SessionOutput>>, AppError> {
+        service
+            .find_user_sessions(&user.user_id(), user.jti().as_deref(), params)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn revoke_my_session(
+        Path(id): Path<String>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysTokenService>>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .revoke_user_session(&user.user_id(), &id)
+            .await
+            .map(Res::new_data)
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::Extension;
+use server_core::web::{auth::User, error::AppError, res::Res, validator::ValidatedForm};
+use server_service::admin::{
+    SysTotpService, TTotpService, TotpCodeInput, TotpEnrollOutput, TotpRecoveryCodesOutput,
+};
+
+pub struct SysTotpApi;
+
+impl SysTotpApi {
+    pub async fn enroll(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysTotpService>>,
+    ) -> Result<Res<TotpEnrollOutput>, AppError> {
+        service
+            .enroll(&user.user_id(), &user.username())
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn confirm(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysTotpService>>,
+        ValidatedForm(input): ValidatedForm<TotpCodeInput>,
+    ) -> Result<Res<TotpRecoveryCodesOutput>, AppError> {
+        service
+            .confirm(&user.user_id(), input)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn disable(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysTotpService>>,
+        ValidatedForm(input): ValidatedForm<TotpCodeInput>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .disable(&user.user_id(), &user.domain(), &user.subject(), input)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn regenerate_recovery_codes(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysTotpService>>,
+        ValidatedForm(input): ValidatedForm<TotpCodeInput>,
+    ) -> Result<Res<TotpRecoveryCodesOutput>, AppError> {
+        service
+            .regenerate_recovery_codes(&user.user_id(), input)
+            .await
+            .map(Res::new_data)
+    }
+}
@@ -9,8 +9,8 @@ use server_core::web::{
     auth::User, error::AppError, page::PaginatedData, res::Res, validator::ValidatedForm,
 };
 use server_service::admin::{
-    CreateUserInput, SysUserService, TUserService, UpdateUserInput, UserPageRequest,
-    UserWithoutPassword,
+    ChangePasswordInput, CreateUserInput, SysUserService, TUserService, UpdateProfileInput,
+    UpdateUserInput, UserPageRequest, UserWithoutPassword,
 };
 
 pub struct SysUserApi;
@@ -91,4 +91,33 @@ impl SysUserApi {
     ) -> Result<Res<()>, AppError> {
         service.delete_user(&id).await.map(Res::new_data)
     }
+
+    pub async fn get_profile(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysUserService>>,
+    ) -> Result<Res<UserWithoutPassword>, AppError> {
+        service.get_profile(&user).await.map(Res::new_data)
+    }
+
+    pub async fn update_profile(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysUserService>>,
+        ValidatedForm(input): ValidatedForm<UpdateProfileInput>,
+    ) -> Result<Res<UserWithoutPassword>, AppError> {
+        service
+            .update_profile(&user, input)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn change_password(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysUserService>>,
+        ValidatedForm(input): ValidatedForm<ChangePasswordInput>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .change_password(&user, input)
+            .await
+            .map(Res::new_data)
+    }
 }
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension};
+use server_core::web::{auth::User, error::AppError, res::Res, validator::ValidatedForm};
+use server_service::admin::{
+    PasskeyCreationOptionsOutput, PasskeyCredentialOutput, PasskeyRegisterInput,
+    RenamePasskeyInput, SysWebAuthnService, TWebAuthnService,
+};
+
+pub struct SysWebAuthnApi;
+
+impl SysWebAuthnApi {
+    pub async fn registration_options(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysWebAuthnService>>,
+    ) -> Result<Res<PasskeyCreationOptionsOutput>, AppError> {
+        service.registration_options(&user).await.map(Res::new_data)
+    }
+
+    pub async fn register(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysWebAuthnService>>,
+        ValidatedForm(input): ValidatedForm<PasskeyRegisterInput>,
+    ) -> Result<Res<PasskeyCredentialOutput>, AppError> {
+        service.register(&user, input).await.map(Res::new_data)
+    }
+
+    pub async fn get_credentials(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysWebAuthnService>>,
+    ) -> Result<Res<Vec<PasskeyCredentialOutput>>, AppError> {
+        service
+            .find_credentials(&user.user_id())
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn rename_credential(
+        Path(id): Path<String>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysWebAuthnService>>,
+        ValidatedForm(input): ValidatedForm<RenamePasskeyInput>,
+    ) -> Result<Res<PasskeyCredentialOutput>, AppError> {
+        service
+            .rename_credential(&user.user_id(), &id, input)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn delete_credential(
+        Path(id): Path<String>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysWebAuthnService>>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .delete_credential(&user, &id)
+            .await
+            .map(Res::new_data)
+    }
+}
@@ -21,6 +21,8 @@ async fn main() {
     server_initialize::init_redis_pools().await;
     server_initialize::init_primary_mongo().await;
     server_initialize::init_mongo_pools().await;
+    server_initialize::initialize_token_revocation().await;
+    server_initialize::initialize_token_cleanup().await;
 
     // build our application with a route
     let app = server_initialize::initialize_admin_router().await;
@@ -5,8 +5,10 @@ use tokio::fs;
 
 use crate::{
     model::{Config, OptionalConfigs},
-    project_error, project_info, DatabaseConfig, JwtConfig, MongoConfig, MongoInstancesConfig,
-    RedisConfig, RedisInstancesConfig, ServerConfig,
+    project_error, project_info, AccessKeyConfig, CaptchaConfig, DatabaseConfig, JwtConfig,
+    LdapConfig, LoginRiskConfig, LoginSecurityConfig, MailConfig, MfaConfig, MongoConfig,
+    MongoInstancesConfig, OidcConfig, PasswordPolicyConfig, PasswordResetConfig, RedisConfig,
+    RedisInstancesConfig, ServerConfig, WebAuthnConfig,
 };
 
 #[derive(Debug, Error)]
@@ -66,6 +68,18 @@ pub async fn init_from_file(file_path: &str) -> Result<(), ConfigError> {
     global::init_config::<OptionalConfigs<MongoInstancesConfig>>(config.mongo_instances.into())
         .await;
 
+    global::init_config::<LoginSecurityConfig>(config.login_security.unwrap_or_default()).await;
+    global::init_config::<LoginRiskConfig>(config.login_risk.unwrap_or_default()).await;
+    global::init_config::<MfaConfig>(config.mfa.unwrap_or_default()).await;
+    global::init_config::<CaptchaConfig>(config.captcha.unwrap_or_default()).await;
+    global::init_config::<PasswordPolicyConfig>(config.password_policy.unwrap_or_default()).await;
+    global::init_config::<PasswordResetConfig>(config.password_reset.unwrap_or_default()).await;
+    global::init_config::<MailConfig>(config.mail.unwrap_or_default()).await;
+    global::init_config::<OidcConfig>(config.oidc.unwrap_or_default()).await;
+    global::init_config::<LdapConfig>(config.ldap.unwrap_or_default()).await;
+    global::init_config::<WebAuthnConfig>(config.webauthn.unwrap_or_default()).await;
+    global::init_config::<AccessKeyConfig>(config.access_key.unwrap_or_default()).await;
+
     project_info!("Configuration initialized successfully");
     Ok(())
 }
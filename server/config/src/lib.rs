@@ -1,7 +1,12 @@
 pub use config_init::init_from_file;
 pub use model::{
-    Config, DatabaseConfig, DatabasesInstancesConfig, JwtConfig, MongoConfig, MongoInstancesConfig,
-    OptionalConfigs, RedisConfig, RedisInstancesConfig, RedisMode, ServerConfig,
+    AccessKeyConfig, CaptchaConfig, CaptchaType, Config, DatabaseConfig, DatabasesInstancesConfig,
+    JwtAlgorithm, JwtAudienceConfig, JwtConfig, JwtKeyConfig, LdapConfig, LdapGroupRole,
+    LoginRiskAction, LoginRiskConfig, LoginSecurityConfig, LoginTimeWindow, MailConfig,
+    MailTransport, MfaConfig, MongoConfig, MongoInstancesConfig, OidcConfig, OidcProviderConfig,
+    OptionalConfigs, PasswordPolicyConfig, PasswordResetConfig, ProtectedRouteConfig,
+    ProtectedRoutesConfig, RedisConfig, RedisInstancesConfig, RedisMode, ReplayProtectionConfig,
+    ServerConfig, SessionLimitPolicy, SmtpConfig, SmtpSecurity, WebAuthnConfig,
 };
 pub use server_global::{project_error, project_info};
 
@@ -0,0 +1,89 @@
+use serde::Deserialize;
+
+/// 访问密钥配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AccessKeyConfig {
+    /// 轮换 secret 后旧 secret 继续有效的宽限期（秒）
+    pub rotation_grace_period: u64,
+    /// 查询参数签名的防重放配置
+    pub complex: ReplayProtectionConfig,
+    /// v2 请求签名的防重放配置
+    pub signed: ReplayProtectionConfig,
+    /// 各验证方式需要校验访问密钥的路由
+    pub protected_routes: ProtectedRoutesConfig,
+}
+
+impl Default for AccessKeyConfig {
+    fn default() -> Self {
+        Self {
+            rotation_grace_period: 86400,
+            complex: ReplayProtectionConfig::default(),
+            signed: ReplayProtectionConfig::default(),
+            protected_routes: ProtectedRoutesConfig::default(),
+        }
+    }
+}
+
+/// 签名请求的防重放配置
+///
+/// 时间戳在服务器时间前后 `timestamp_disparity_ms` 内均被接受，同一请求最长可在两倍偏差内重放，
+/// 因此 nonce 的保留时间不能短于两倍的时间偏差
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct ReplayProtectionConfig {
+    /// nonce 的保留时间（秒）
+    pub nonce_ttl_secs: u64,
+    /// 允许的客户端与服务器时间偏差（毫秒）
+    pub timestamp_disparity_ms: i64,
+}
+
+impl Default for ReplayProtectionConfig {
+    fn default() -> Self {
+        Self {
+            nonce_ttl_secs: 600,
+            timestamp_disparity_ms: 300_000,
+        }
+    }
+}
+
+/// 按验证方式分组的受保护路由，每组只由对应的验证中间件检查
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ProtectedRoutesConfig {
+    /// 只校验访问密钥的路由
+    pub simple: Vec<ProtectedRouteConfig>,
+    /// 校验查询参数签名的路由
+    pub complex: Vec<ProtectedRouteConfig>,
+    /// 校验 v2 请求签名的路由
+    pub signed: Vec<ProtectedRouteConfig>,
+}
+
+impl Default for ProtectedRoutesConfig {
+    fn default() -> Self {
+        Self {
+            simple: vec![ProtectedRouteConfig::new("/sandbox/simple-api-key")],
+            complex: vec![ProtectedRouteConfig::new("/sandbox/complex-api-key")],
+            signed: vec![ProtectedRouteConfig::new("/sandbox/signed-api-key")],
+        }
+    }
+}
+
+/// 受保护的路由
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProtectedRouteConfig {
+    /// 路径模式，支持 axum 风格的 `:param` 与 `*` / `*rest` 通配
+    pub path: String,
+    /// 限定的 HTTP 方法，为空表示所有方法
+    #[serde(default)]
+    pub methods: Vec<String>,
+}
+
+impl ProtectedRouteConfig {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            methods: Vec::new(),
+        }
+    }
+}
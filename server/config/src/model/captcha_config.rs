@@ -0,0 +1,39 @@
+use serde::Deserialize;
+
+/// 登录验证码配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CaptchaConfig {
+    /// 是否所有登录请求都需要验证码
+    pub enabled: bool,
+    /// 统计窗口内同一账号或 IP 失败次数达到该值后要求验证码，为 0 表示不自动开启
+    pub failure_threshold: u32,
+    /// 验证码有效期（秒）
+    pub expire: u64,
+    /// 内置验证码类型
+    pub captcha_type: CaptchaType,
+    /// 字符验证码的长度
+    pub length: usize,
+}
+
+/// 内置验证码类型
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptchaType {
+    /// 算术题，如 `3 + 5 = ?`
+    Arithmetic,
+    /// 随机字符
+    Text,
+}
+
+impl Default for CaptchaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            failure_threshold: 3,
+            expire: 120,
+            captcha_type: CaptchaType::Arithmetic,
+            length: 4,
+        }
+    }
+}
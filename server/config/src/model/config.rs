@@ -1,8 +1,10 @@
 use serde::Deserialize;
 
 use super::{
-    DatabaseConfig, DatabasesInstancesConfig, JwtConfig, MongoConfig, MongoInstancesConfig,
-    RedisConfig, RedisInstancesConfig, ServerConfig,
+    AccessKeyConfig, CaptchaConfig, DatabaseConfig, DatabasesInstancesConfig, JwtConfig,
+    LdapConfig, LoginRiskConfig, LoginSecurityConfig, MailConfig, MfaConfig, MongoConfig,
+    MongoInstancesConfig, OidcConfig, PasswordPolicyConfig, PasswordResetConfig, RedisConfig,
+    RedisInstancesConfig, ServerConfig, WebAuthnConfig,
 };
 
 /// 应用程序配置结构
@@ -34,11 +36,22 @@ use super::{
 /// - `database`: 主数据库配置，用于配置默认的数据库连接
 /// - `database_instances`: 可选的数据库连接池配置，用于配置多个命名的数据库连接
 /// - `server`: HTTP 服务器配置，包含监听地址和端口等
-/// - `jwt`: JWT 认证配置，包含密钥和过期时间等
+/// - `jwt`: JWT 认证配置，包含密钥、非对称签名密钥、过期时间和按受众的会话限制等
 /// - `redis`: 主 Redis 配置，用于配置默认的 Redis 连接
 /// - `redis_instances`: 可选的 Redis 连接池配置，用于配置多个命名的 Redis 连接
 /// - `mongo`: 主 MongoDB 配置，用于配置默认的 MongoDB 连接
 /// - `mongo_instances`: 可选的 MongoDB 连接池配置，用于配置多个命名的 MongoDB 连接
+/// - `login_security`: 可选的登录安全配置，包含失败锁定、IP 黑名单和登录时间段
+/// - `login_risk`: 可选的默认异常登录检测策略，登录域可单独配置
+/// - `mfa`: 可选的两步验证配置
+/// - `captcha`: 可选的登录验证码配置
+/// - `password_policy`: 可选的默认密码策略，登录域可单独配置
+/// - `password_reset`: 可选的找回密码配置，包含令牌有效期、重置链接与按账号、IP 的申请频率限制
+/// - `mail`: 可选的邮件发送配置，默认写入本地 outbox 目录
+/// - `oidc`: 可选的 OpenID Connect 登录配置
+/// - `ldap`: 可选的 LDAP / Active Directory 认证配置
+/// - `webauthn`: 可选的 WebAuthn / 通行密钥配置
+/// - `access_key`: 可选的访问密钥配置，包含 secret 轮换的宽限期、签名验证的防重放参数与各验证方式的受保护路由
 ///
 /// # 示例配置（YAML）
 /// ```yaml
@@ -57,6 +70,19 @@ use super::{
 /// jwt:
 ///   secret: "your-secret-key"
 ///   expire: 3600
+///   refresh_expire: 604800
+///   signing_kid: "2024-12"
+///   keys:
+///     - kid: "2024-12"
+///       algorithm: "ES256"
+///       private_key_path: "server/resources/keys/jwt-2024-12.key"
+///       public_key_path: "server/resources/keys/jwt-2024-12.pub"
+///   audiences:
+///     mobile_app:
+///       expire: 86400
+///       refresh_expire: 2592000
+///       max_sessions: 1
+///       session_limit_policy: "reject_new"
 ///
 /// redis:
 ///   mode: "single"
@@ -68,6 +94,85 @@ use super::{
 ///     urls:
 ///       - "redis://:password@localhost:6379"
 ///       - "redis://:password@localhost:6380"
+///
+/// login_security:
+///   max_failed_attempts: 5
+///   lockout_duration: 300
+///   ip_deny_list:
+///     - "10.0.0.0/8"
+///   allowed_login_windows:
+///     - start: "08:00"
+///       end: "20:00"
+///
+/// login_risk:
+///   history_size: 10
+///   impossible_travel_window: 3600
+///   action: "notify"
+///
+/// captcha:
+///   enabled: false
+///   failure_threshold: 3
+///   captcha_type: "arithmetic"
+///
+/// password_policy:
+///   min_length: 8
+///   require_digit: true
+///   history_count: 5
+///   max_age_days: 90
+///
+/// mail:
+///   transport: "smtp"
+///   from: "no-reply@example.com"
+///   smtp:
+///     host: "smtp.example.com"
+///     port: 587
+///     username: "no-reply@example.com"
+///     password: "password"
+///     security: "starttls"
+///
+/// oidc:
+///   providers:
+///     - name: "corp"
+///       issuer: "https://sso.example.com"
+///       client_id: "soybean-admin"
+///       client_secret: "secret"
+///       redirect_uri: "http://localhost:9527/#/oidc/callback/corp"
+///       auto_provision: true
+///       default_role: "R_USER"
+///
+/// ldap:
+///   enabled: true
+///   url: "ldaps://ad.example.com:636"
+///   bind_dn: "CN=svc-soybean,OU=Service,DC=example,DC=com"
+///   bind_password: "secret"
+///   base_dn: "DC=example,DC=com"
+///   user_filter: "(&(objectClass=user)(sAMAccountName={username}))"
+///   username_attribute: "sAMAccountName"
+///   group_roles:
+///     - domain: "built-in"
+///       group: "CN=Admins,OU=Groups,DC=example,DC=com"
+///       role: "R_ADMIN"
+///
+/// webauthn:
+///   rp_id: "admin.example.com"
+///   rp_name: "Soybean Admin"
+///   origins:
+///     - "https://admin.example.com"
+///
+/// access_key:
+///   rotation_grace_period: 86400
+///   complex:
+///     nonce_ttl_secs: 600
+///     timestamp_disparity_ms: 300000
+///   signed:
+///     nonce_ttl_secs: 600
+///     timestamp_disparity_ms: 300000
+///   protected_routes:
+///     simple:
+///       - path: "/sandbox/simple-api-key"
+///     signed:
+///       - path: "/open/orders/:id"
+///         methods: ["GET", "PUT"]
 /// ```
 #[derive(Deserialize, Debug, Clone)]
 pub struct Config {
@@ -97,4 +202,37 @@ pub struct Config {
     /// 可选的 MongoDB 连接池配置
     /// 用于配置多个命名的 MongoDB 连接
     pub mongo_instances: Option<Vec<MongoInstancesConfig>>,
+
+    /// 登录安全配置
+    pub login_security: Option<LoginSecurityConfig>,
+
+    /// 默认异常登录检测策略
+    pub login_risk: Option<LoginRiskConfig>,
+
+    /// 两步验证配置
+    pub mfa: Option<MfaConfig>,
+
+    /// 登录验证码配置
+    pub captcha: Option<CaptchaConfig>,
+
+    /// 默认密码策略
+    pub password_policy: Option<PasswordPolicyConfig>,
+
+    /// 找回密码配置
+    pub password_reset: Option<PasswordResetConfig>,
+
+    /// 邮件发送配置
+    pub mail: Option<MailConfig>,
+
+    /// OpenID Connect 登录配置
+    pub oidc: Option<OidcConfig>,
+
+    /// LDAP / Active Directory 认证配置
+    pub ldap: Option<LdapConfig>,
+
+    /// WebAuthn / 通行密钥配置
+    pub webauthn: Option<WebAuthnConfig>,
+
+    /// 访问密钥配置
+    pub access_key: Option<AccessKeyConfig>,
 }
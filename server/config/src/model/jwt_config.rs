@@ -1,8 +1,170 @@
+use std::collections::HashMap;
+
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct JwtConfig {
+    /// HMAC 共享密钥，未配置非对称密钥时用于签发令牌，配置后仍用于验证未携带 kid 的旧令牌
+    #[serde(default)]
     pub jwt_secret: String,
     pub issuer: String,
     pub expire: i64,
+    /// 刷新令牌有效期（秒），未配置时为 7 天
+    #[serde(default = "default_refresh_expire")]
+    pub refresh_expire: i64,
+    /// 非对称密钥列表，按 kid 区分，可同时保留多个用于验证的密钥
+    #[serde(default)]
+    pub keys: Vec<JwtKeyConfig>,
+    /// 签发令牌使用的密钥 kid，为空时使用第一个配置了私钥的密钥
+    #[serde(default)]
+    pub signing_kid: Option<String>,
+    /// 按受众（如 `management_platform`、`mobile_app`）覆盖令牌有效期与并发会话数
+    #[serde(default)]
+    pub audiences: HashMap<String, JwtAudienceConfig>,
+}
+
+fn default_refresh_expire() -> i64 {
+    604800
+}
+
+impl JwtConfig {
+    /// 访问令牌有效期（秒），受众未单独配置时使用全局值
+    pub fn expire_for(&self, audience: &str) -> i64 {
+        self.audiences
+            .get(audience)
+            .and_then(|config| config.expire)
+            .unwrap_or(self.expire)
+    }
+
+    /// 刷新令牌有效期（秒），受众未单独配置时使用全局值
+    pub fn refresh_expire_for(&self, audience: &str) -> i64 {
+        self.audiences
+            .get(audience)
+            .and_then(|config| config.refresh_expire)
+            .unwrap_or(self.refresh_expire)
+    }
+
+    /// 所有受众中最长的访问令牌与刷新令牌有效期（秒）
+    pub fn max_expire(&self) -> (i64, i64) {
+        self.audiences.keys().fold(
+            (self.expire, self.refresh_expire),
+            |(expire, refresh_expire), audience| {
+                (
+                    expire.max(self.expire_for(audience)),
+                    refresh_expire.max(self.refresh_expire_for(audience)),
+                )
+            },
+        )
+    }
+
+    /// 受众的并发会话限制，未配置或为 0 时不限制
+    pub fn session_limit_for(&self, audience: &str) -> Option<(u64, SessionLimitPolicy)> {
+        self.audiences
+            .get(audience)
+            .filter(|config| config.max_sessions > 0)
+            .map(|config| (config.max_sessions, config.session_limit_policy))
+    }
+}
+
+/// 单个受众的令牌配置，未配置的项沿用全局值
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct JwtAudienceConfig {
+    /// 访问令牌有效期（秒）
+    pub expire: Option<i64>,
+    /// 刷新令牌有效期（秒）
+    pub refresh_expire: Option<i64>,
+    /// 同一用户在该受众下允许的最大在线会话数，0 表示不限制
+    pub max_sessions: u64,
+    /// 超出会话数限制时的处理方式
+    pub session_limit_policy: SessionLimitPolicy,
+}
+
+/// 超出并发会话数限制时的处理方式
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionLimitPolicy {
+    /// 吊销最早登录的会话
+    #[default]
+    RevokeOldest,
+    /// 拒绝新的登录
+    RejectNew,
+}
+
+/// JWT 非对称签名算法
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    #[serde(rename = "RS256")]
+    Rs256,
+    #[serde(rename = "ES256")]
+    Es256,
+    #[serde(rename = "EdDSA")]
+    EdDsa,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct JwtKeyConfig {
+    pub kid: String,
+    pub algorithm: JwtAlgorithm,
+    /// 私钥 PEM 文件路径，只用于验证的旧密钥可以不配置
+    pub private_key_path: Option<String>,
+    /// 公钥 PEM 文件路径（SubjectPublicKeyInfo 格式）
+    pub public_key_path: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audience_overrides() {
+        let config: JwtConfig = serde_yaml::from_str(
+            r#"
+issuer: "soybean"
+expire: 7200
+refresh_expire: 604800
+audiences:
+    management_platform:
+        expire: 1800
+        max_sessions: 3
+    mobile_app:
+        refresh_expire: 2592000
+        max_sessions: 1
+        session_limit_policy: "reject_new"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.expire_for("management_platform"), 1800);
+        assert_eq!(config.refresh_expire_for("management_platform"), 604800);
+        assert_eq!(config.expire_for("mobile_app"), 7200);
+        assert_eq!(config.refresh_expire_for("mobile_app"), 2592000);
+        assert_eq!(config.expire_for("mini_program"), 7200);
+        assert_eq!(config.max_expire(), (7200, 2592000));
+
+        assert_eq!(
+            config.session_limit_for("management_platform"),
+            Some((3, SessionLimitPolicy::RevokeOldest))
+        );
+        assert_eq!(
+            config.session_limit_for("mobile_app"),
+            Some((1, SessionLimitPolicy::RejectNew))
+        );
+        assert_eq!(config.session_limit_for("official_website"), None);
+    }
+
+    #[test]
+    fn test_default_refresh_expire() {
+        let config: JwtConfig = serde_yaml::from_str(
+            r#"
+jwt_secret: "soybean-admin-rust"
+issuer: "soybean"
+expire: 7200
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.refresh_expire, 604800);
+        assert_eq!(config.refresh_expire_for("management_platform"), 604800);
+    }
 }
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+/// LDAP / Active Directory 认证配置
+///
+/// 先以服务账号绑定并按 `user_filter` 搜索用户，再以用户 DN 和密码绑定完成认证
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LdapConfig {
+    pub enabled: bool,
+    /// 目录地址，支持 `ldap://` 与 `ldaps://`
+    pub url: String,
+    /// 搜索用户的服务账号 DN，为空时匿名搜索
+    pub bind_dn: String,
+    pub bind_password: String,
+    /// 用户搜索的起始 DN
+    pub base_dn: String,
+    /// 用户搜索条件，`{username}` 会替换为登录用户名
+    pub user_filter: String,
+    pub username_attribute: String,
+    pub nick_name_attribute: String,
+    pub email_attribute: String,
+    /// 用户所属组的属性
+    pub group_attribute: String,
+    /// 启用目录认证的登录域
+    pub domains: Vec<String>,
+    /// 目录组与角色编码的映射
+    pub group_roles: Vec<LdapGroupRole>,
+    /// 单次认证的超时（秒）
+    pub timeout: u64,
+}
+
+/// 登录域内目录组到角色的映射
+#[derive(Deserialize, Debug, Clone)]
+pub struct LdapGroupRole {
+    pub domain: String,
+    /// 组的 DN，比较时忽略大小写
+    pub group: String,
+    pub role: String,
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "ldap://127.0.0.1:389".to_string(),
+            bind_dn: String::new(),
+            bind_password: String::new(),
+            base_dn: String::new(),
+            user_filter: "(uid={username})".to_string(),
+            username_attribute: "uid".to_string(),
+            nick_name_attribute: "cn".to_string(),
+            email_attribute: "mail".to_string(),
+            group_attribute: "memberOf".to_string(),
+            domains: vec!["built-in".to_string()],
+            group_roles: Vec::new(),
+            timeout: 10,
+        }
+    }
+}
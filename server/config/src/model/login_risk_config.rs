@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// 异常登录检测配置
+///
+/// 作为全局默认策略，登录域可配置自己的策略覆盖
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct LoginRiskConfig {
+    /// 是否启用异常登录检测
+    pub enabled: bool,
+    /// 每个用户保留的最近登录地点数量
+    pub history_size: u32,
+    /// 检测从未出现过的国家或省份
+    pub detect_new_location: bool,
+    /// 检测从未使用过的浏览器与操作系统组合
+    pub detect_new_device: bool,
+    /// 相邻两次登录的国家不同且间隔小于该值（秒）时视为不可能的移动，为 0 表示不检测
+    pub impossible_travel_window: u64,
+    /// 检测到异常时的处理方式
+    pub action: LoginRiskAction,
+}
+
+/// 检测到异常登录时的处理方式，安全事件总会被记录
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LoginRiskAction {
+    /// 只记录安全事件
+    Log,
+    /// 邮件通知用户
+    #[default]
+    Notify,
+    /// 要求输入验证码
+    Captcha,
+    /// 要求两步验证，未开启两步验证的用户改为要求验证码
+    Mfa,
+}
+
+impl Default for LoginRiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_size: 10,
+            detect_new_location: true,
+            detect_new_device: true,
+            impossible_travel_window: 3600,
+            action: LoginRiskAction::Notify,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+
+/// 登录安全配置
+///
+/// 未配置时使用默认值，默认不启用 IP 黑名单和登录时间段限制
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LoginSecurityConfig {
+    /// 统计窗口内同一账号允许的最大失败次数，达到后锁定账号
+    pub max_failed_attempts: u32,
+    /// 统计窗口内同一 IP 允许的最大失败次数，达到后拒绝该 IP 登录
+    pub max_ip_failed_attempts: u32,
+    /// 失败次数统计窗口（秒）
+    pub failure_window: u64,
+    /// 首次锁定时长（秒）
+    pub lockout_duration: u64,
+    /// 再次锁定时锁定时长的递增倍数
+    pub lockout_backoff_multiplier: u32,
+    /// 锁定时长上限（秒）
+    pub max_lockout_duration: u64,
+    /// IP 黑名单，支持单个 IP（如 `10.0.0.1`）和 CIDR（如 `10.0.0.0/8`）
+    pub ip_deny_list: Vec<String>,
+    /// 允许登录的时间段，为空表示不限制
+    pub allowed_login_windows: Vec<LoginTimeWindow>,
+}
+
+/// 允许登录的时间段，使用服务器本地时间，格式为 `HH:MM`
+///
+/// 开始时间晚于结束时间表示跨越午夜，如 `22:00` 至 `06:00`
+#[derive(Deserialize, Debug, Clone)]
+pub struct LoginTimeWindow {
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for LoginSecurityConfig {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 5,
+            max_ip_failed_attempts: 20,
+            failure_window: 900,
+            lockout_duration: 300,
+            lockout_backoff_multiplier: 2,
+            max_lockout_duration: 86400,
+            ip_deny_list: Vec::new(),
+            allowed_login_windows: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use serde::Deserialize;
+
+/// 邮件发送配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MailConfig {
+    /// 发送方式
+    pub transport: MailTransport,
+    /// 发件人地址
+    pub from: String,
+    /// `outbox` 方式下邮件的写入目录
+    pub outbox_dir: String,
+    /// `smtp` 方式下的 SMTP 服务器配置
+    pub smtp: SmtpConfig,
+}
+
+/// 邮件发送方式
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransport {
+    /// 通过 SMTP 服务器发送
+    Smtp,
+    /// 写入本地目录，用于开发和离线测试
+    Outbox,
+}
+
+/// SMTP 服务器配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// 连接加密方式
+    pub security: SmtpSecurity,
+    /// 单封邮件的发送超时（秒）
+    pub timeout: u64,
+}
+
+/// SMTP 连接加密方式
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// 明文连接
+    None,
+    /// 明文连接后通过 STARTTLS 升级，通常使用 587 端口
+    StartTls,
+    /// 直接建立 TLS 连接，通常使用 465 端口
+    Tls,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: MailTransport::Outbox,
+            from: "no-reply@soybean.local".to_string(),
+            outbox_dir: "logs/outbox".to_string(),
+            smtp: SmtpConfig::default(),
+        }
+    }
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 587,
+            username: None,
+            password: None,
+            security: SmtpSecurity::StartTls,
+            timeout: 30,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+/// 两步验证（TOTP）配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MfaConfig {
+    /// 显示在身份验证器应用中的签发方名称
+    pub issuer: String,
+    /// 登录时两步验证挑战的有效期（秒）
+    pub challenge_expire: u64,
+    /// 单个挑战允许的最大验证次数
+    pub max_verify_attempts: u32,
+    /// 每次生成的恢复码数量
+    pub recovery_code_count: usize,
+}
+
+impl Default for MfaConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "Soybean Admin".to_string(),
+            challenge_expire: 300,
+            max_verify_attempts: 5,
+            recovery_code_count: 10,
+        }
+    }
+}
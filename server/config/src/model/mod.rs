@@ -1,9 +1,24 @@
+pub use access_key_config::{
+    AccessKeyConfig, ProtectedRouteConfig, ProtectedRoutesConfig, ReplayProtectionConfig,
+};
+pub use captcha_config::{CaptchaConfig, CaptchaType};
 pub use config::Config;
 pub use database_config::{DatabaseConfig, DatabasesInstancesConfig};
-pub use jwt_config::JwtConfig;
+pub use jwt_config::{
+    JwtAlgorithm, JwtAudienceConfig, JwtConfig, JwtKeyConfig, SessionLimitPolicy,
+};
+pub use ldap_config::{LdapConfig, LdapGroupRole};
+pub use login_risk_config::{LoginRiskAction, LoginRiskConfig};
+pub use login_security_config::{LoginSecurityConfig, LoginTimeWindow};
+pub use mail_config::{MailConfig, MailTransport, SmtpConfig, SmtpSecurity};
+pub use mfa_config::MfaConfig;
 pub use mongo_config::{MongoConfig, MongoInstancesConfig};
+pub use oidc_config::{OidcConfig, OidcProviderConfig};
+pub use password_policy_config::PasswordPolicyConfig;
+pub use password_reset_config::PasswordResetConfig;
 pub use redis_config::{RedisConfig, RedisInstancesConfig, RedisMode};
 pub use server_config::ServerConfig;
+pub use webauthn_config::WebAuthnConfig;
 
 /// 可选配置集合的包装类
 #[allow(dead_code)]
@@ -18,9 +33,20 @@ impl<T> From<Option<Vec<T>>> for OptionalConfigs<T> {
     }
 }
 
+mod access_key_config;
+mod captcha_config;
 mod config;
 mod database_config;
 mod jwt_config;
+mod ldap_config;
+mod login_risk_config;
+mod login_security_config;
+mod mail_config;
+mod mfa_config;
 mod mongo_config;
+mod oidc_config;
+mod password_policy_config;
+mod password_reset_config;
 mod redis_config;
 mod server_config;
+mod webauthn_config;
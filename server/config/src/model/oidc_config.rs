@@ -0,0 +1,62 @@
+use serde::Deserialize;
+
+/// OpenID Connect 登录配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct OidcConfig {
+    /// 授权请求 state 的有效期（秒）
+    pub state_expire: u64,
+    /// 身份提供方列表
+    pub providers: Vec<OidcProviderConfig>,
+}
+
+/// OpenID Connect 身份提供方
+#[derive(Deserialize, Debug, Clone)]
+pub struct OidcProviderConfig {
+    /// 提供方名称，用于登录地址和登录类型
+    pub name: String,
+    /// 签发方地址，从 `{issuer}/.well-known/openid-configuration` 获取发现文档
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    /// 授权完成后的回调地址，需在身份提供方处登记
+    pub redirect_uri: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    /// 自动创建用户时作为用户名的声明
+    #[serde(default = "default_username_claim")]
+    pub username_claim: String,
+    /// 外部身份未关联用户时是否自动创建用户
+    #[serde(default)]
+    pub auto_provision: bool,
+    /// 用户所属的登录域
+    #[serde(default = "default_domain")]
+    pub domain: String,
+    /// 自动创建用户时分配的角色编码
+    pub default_role: Option<String>,
+}
+
+fn default_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+fn default_username_claim() -> String {
+    "preferred_username".to_string()
+}
+
+fn default_domain() -> String {
+    "built-in".to_string()
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            state_expire: 600,
+            providers: Vec::new(),
+        }
+    }
+}
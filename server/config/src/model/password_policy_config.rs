@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// 密码策略配置
+///
+/// 作为全局默认策略，登录域可配置自己的策略覆盖
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct PasswordPolicyConfig {
+    /// 最小长度
+    pub min_length: usize,
+    /// 必须包含大写字母
+    pub require_uppercase: bool,
+    /// 必须包含小写字母
+    pub require_lowercase: bool,
+    /// 必须包含数字
+    pub require_digit: bool,
+    /// 必须包含特殊字符
+    pub require_special: bool,
+    /// 最低密码强度（0-4）
+    pub min_strength: u8,
+    /// 禁止密码中包含用户名（不区分大小写）
+    pub forbid_username: bool,
+    /// 不允许与最近 N 次使用过的密码相同，为 0 表示不限制
+    pub history_count: u32,
+    /// 密码有效期（天），过期后下次登录要求修改，为 0 表示永不过期
+    pub max_age_days: u32,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            require_uppercase: false,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: false,
+            min_strength: 2,
+            forbid_username: true,
+            history_count: 5,
+            max_age_days: 0,
+        }
+    }
+}
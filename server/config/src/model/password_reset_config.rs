@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+/// 找回密码配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PasswordResetConfig {
+    /// 重置令牌的有效期（秒）
+    pub token_expire: u64,
+    /// 邮件中的重置链接，`{token}` 会被替换为重置令牌
+    pub reset_url: String,
+    /// 统计窗口内同一账号允许的最大申请次数，0 表示不限制
+    pub max_account_requests: u32,
+    /// 统计窗口内同一 IP 允许的最大申请次数，0 表示不限制
+    pub max_ip_requests: u32,
+    /// 申请次数统计窗口（秒）
+    pub request_window: u64,
+}
+
+impl Default for PasswordResetConfig {
+    fn default() -> Self {
+        Self {
+            token_expire: 1800,
+            reset_url: "http://localhost:9527/#/reset-password?token={token}".to_string(),
+            max_account_requests: 3,
+            max_ip_requests: 20,
+            request_window: 3600,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+
+/// WebAuthn / 通行密钥配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WebAuthnConfig {
+    /// 是否启用通行密钥
+    pub enabled: bool,
+    /// 依赖方 ID，通常为前端页面的域名，不含协议与端口
+    pub rp_id: String,
+    /// 显示在认证器中的依赖方名称
+    pub rp_name: String,
+    /// 允许发起认证的前端来源，需包含协议与端口
+    pub origins: Vec<String>,
+    /// 注册与认证挑战的有效期（秒）
+    pub challenge_expire: u64,
+}
+
+impl Default for WebAuthnConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rp_id: "localhost".to_string(),
+            rp_name: "Soybean Admin".to_string(),
+            origins: vec!["http://localhost:9527".to_string()],
+            challenge_expire: 300,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_webauthn_config() {
+        let yaml = r#"
+rp_id: "admin.example.com"
+origins:
+  - "https://admin.example.com"
+"#;
+        let config: WebAuthnConfig = serde_yaml::from_str(yaml).unwrap();
+
+        assert!(config.enabled);
+        assert_eq!(config.rp_id, "admin.example.com");
+        assert_eq!(config.rp_name, "Soybean Admin");
+        assert_eq!(config.origins, vec!["https://admin.example.com"]);
+    }
+}
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 /// Enum to represent different platforms or audiences for JWT authentication.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Audience {
@@ -22,3 +24,18 @@ impl Audience {
         }
     }
 }
+
+impl FromStr for Audience {
+    type Err = strum::ParseError;
+
+    /// Parses the audience string produced by [`Audience::as_str`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "official_website" => Ok(Audience::OfficialWebsite),
+            "management_platform" => Ok(Audience::ManagementPlatform),
+            "mobile_app" => Ok(Audience::MobileApp),
+            "mini_program" => Ok(Audience::MiniProgram),
+            _ => Err(strum::ParseError::VariantNotFound),
+        }
+    }
+}
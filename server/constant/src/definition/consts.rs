@@ -21,3 +21,91 @@ impl TokenStatus {
         matches!(self, TokenStatus::Active)
     }
 }
+
+/// 登录标识类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum LoginIdentifierType {
+    Username,
+    Email,
+    Phone,
+}
+
+impl LoginIdentifierType {
+    /// 按格式判断标识类型：包含 `@` 视为邮箱，纯数字（可带 `+` 前缀）视为手机号
+    pub fn detect(identifier: &str) -> Self {
+        let digits = identifier.strip_prefix('+').unwrap_or(identifier);
+        if identifier.contains('@') {
+            LoginIdentifierType::Email
+        } else if (6..=20).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit()) {
+            LoginIdentifierType::Phone
+        } else {
+            LoginIdentifierType::Username
+        }
+    }
+}
+
+/// 登录结果
+#[derive(Debug, Clone, PartialEq, Eq, AsRefStr, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum LoginStatus {
+    Success,
+    Failure,
+}
+
+/// 登录失败原因
+#[derive(Debug, Clone, PartialEq, Eq, AsRefStr, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum LoginFailureReason {
+    /// 用户不存在
+    UserNotFound,
+    /// 密码错误
+    WrongPassword,
+    /// 账号已被锁定
+    AccountLocked,
+    /// IP 在黑名单中
+    IpBlocked,
+    /// 同一 IP 失败次数过多
+    TooManyIpAttempts,
+    /// 不在允许登录的时间段内
+    OutsideLoginWindow,
+    /// 两步验证码错误
+    InvalidMfaCode,
+    /// 登录域不存在
+    DomainNotFound,
+    /// 登录域已禁用
+    DomainDisabled,
+    /// 需要验证码但未提交
+    CaptchaRequired,
+    /// 验证码错误或已过期
+    InvalidCaptcha,
+    /// 外部身份未关联用户
+    IdentityNotLinked,
+    /// 通行密钥校验失败
+    InvalidPasskey,
+}
+
+impl LoginFailureReason {
+    /// 是否计入失败次数，仅凭证错误会触发账号锁定
+    pub fn counts_toward_lockout(&self) -> bool {
+        matches!(
+            self,
+            LoginFailureReason::UserNotFound
+                | LoginFailureReason::WrongPassword
+                | LoginFailureReason::InvalidMfaCode
+                | LoginFailureReason::InvalidPasskey
+        )
+    }
+}
+
+/// 异常登录信号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, Display, EnumString)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum LoginRiskSignal {
+    /// 从未出现过的国家或省份
+    NewLocation,
+    /// 与上次登录的国家不同且间隔过短
+    ImpossibleTravel,
+    /// 从未使用过的浏览器与操作系统组合
+    NewDevice,
+}
@@ -1,12 +1,16 @@
+use chrono::{Local, NaiveDateTime};
 use md5::{Digest, Md5};
-use moka::sync::Cache;
 use parking_lot::RwLock;
 use ring::{digest, hmac};
 use std::{
-    borrow::Cow,
     collections::HashMap,
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+    nonce_store::{MemoryNonceStore, NonceStore},
+    signature_v2::verify_signature_v2,
 };
 
 /// Supported signature algorithms for API key validation.
@@ -40,6 +44,10 @@ impl Default for SignatureAlgorithm {
 pub struct ApiKeyConfig {
     /// The signature algorithm to use for request validation
     pub algorithm: SignatureAlgorithm,
+    /// How long a nonce is remembered, in seconds
+    pub nonce_ttl_secs: u64,
+    /// Maximum allowed clock difference between client and server, in milliseconds
+    pub timestamp_disparity_ms: i64,
 }
 
 impl Default for ApiKeyConfig {
@@ -47,71 +55,96 @@ impl Default for ApiKeyConfig {
     fn default() -> Self {
         Self {
             algorithm: SignatureAlgorithm::default(),
+            nonce_ttl_secs: NONCE_TTL_SECS,
+            timestamp_disparity_ms: TIMESTAMP_DISPARITY_MS,
+        }
+    }
+}
+
+impl ApiKeyConfig {
+    /// Checks that nonces are remembered for as long as a request can be replayed.
+    ///
+    /// A timestamp is accepted from `timestamp_disparity_ms` before to
+    /// `timestamp_disparity_ms` after the server clock, so a captured request
+    /// stays acceptable for up to twice the disparity after it is first seen.
+    /// A shorter nonce TTL would accept the replay once the nonce expires.
+    ///
+    /// # Returns
+    /// An error message describing the invalid setting, if any
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timestamp_disparity_ms <= 0 {
+            return Err(format!(
+                "timestamp_disparity_ms must be positive, got {}",
+                self.timestamp_disparity_ms
+            ));
         }
+        let replay_window_ms = 2 * self.timestamp_disparity_ms as u128;
+        if u128::from(self.nonce_ttl_secs) * 1000 < replay_window_ms {
+            return Err(format!(
+                "nonce_ttl_secs ({}s) must cover twice the timestamp disparity ({}ms)",
+                self.nonce_ttl_secs, self.timestamp_disparity_ms
+            ));
+        }
+        Ok(())
     }
 }
 
-/// Constants for validation timeouts and expiration.
+/// Default validation timeouts and expiration.
 pub const NONCE_TTL_SECS: u64 = 600; // 10 minutes
 pub const TIMESTAMP_DISPARITY_MS: i64 = 300_000; // 5 minutes
 
-/// Capacity hints for collections
-const DEFAULT_CAPACITY: usize = 32;
+/// Reasons for rejecting a request authenticated with an API key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyRejection {
+    /// The API key is not registered
+    UnknownKey,
+    /// The API key is past its expiry time
+    ExpiredKey,
+    /// The request timestamp is outside the allowed disparity window
+    ExpiredTimestamp,
+    /// The signature does not match any active secret
+    BadSignature,
+    /// The nonce has already been used
+    ReplayedNonce,
+}
 
-/// In-memory store for managing nonces with automatic expiration.
+/// Identity of an API key that passed validation.
 ///
-/// Uses moka cache to store nonces with a TTL of 10 minutes. Once a nonce
-/// expires, it can be reused. This helps prevent replay attacks while
-/// maintaining memory efficiency.
-#[derive(Clone)]
-pub struct MemoryNonceStore {
-    nonces: Cache<String, ()>,
+/// The middleware hands it to the authorization layers, with the access key
+/// ID as the casbin subject and the key's domain as the casbin domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyPrincipal {
+    /// The access key ID
+    pub access_key_id: String,
+    /// Domain the key belongs to, `None` for keys registered without one
+    pub domain: Option<String>,
 }
 
-impl Default for MemoryNonceStore {
-    #[inline]
-    fn default() -> Self {
-        Self::new()
-    }
+/// Expiry and domain of an API key, shared by both validators.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiKeyAttributes {
+    /// Local time after which the key is rejected, `None` for no expiry
+    pub expires_at: Option<NaiveDateTime>,
+    /// Domain the key belongs to
+    pub domain: Option<String>,
 }
 
-impl MemoryNonceStore {
-    /// Creates a new instance of MemoryNonceStore with a 10-minute TTL.
-    #[inline]
-    pub fn new() -> Self {
-        Self {
-            nonces: Cache::builder()
-                .time_to_live(std::time::Duration::from_secs(NONCE_TTL_SECS))
-                .build(),
-        }
-    }
-
-    /// Validates and stores a nonce.
-    ///
-    /// # Arguments
-    /// * `nonce` - The nonce string to validate and store
-    ///
-    /// # Returns
-    /// * `true` if the nonce is valid and not previously used
-    /// * `false` if the nonce is invalid or has been used before
-    #[inline]
-    pub fn check_and_set(&self, nonce: &str) -> bool {
-        if self.nonces.contains_key(nonce) {
-            false
-        } else {
-            self.nonces.insert(nonce.to_string(), ());
-            true
-        }
-    }
+/// Checks whether an optional expiry time has passed.
+#[inline]
+fn is_expired(expires_at: Option<NaiveDateTime>, now: NaiveDateTime) -> bool {
+    expires_at.is_some_and(|expires_at| now >= expires_at)
 }
 
+/// Capacity hints for collections
+const DEFAULT_CAPACITY: usize = 32;
+
 /// Simple API key validator that checks against a set of predefined keys.
 ///
 /// This validator provides basic API key validation by comparing against a set of valid keys.
 /// Keys are stored permanently and can only be modified through explicit API calls.
 #[derive(Clone)]
 pub struct SimpleApiKeyValidator {
-    keys: Arc<RwLock<HashMap<String, ()>>>,
+    keys: Arc<RwLock<HashMap<String, ApiKeyAttributes>>>,
 }
 
 impl SimpleApiKeyValidator {
@@ -133,7 +166,28 @@ impl SimpleApiKeyValidator {
     /// * `false` if the key is invalid
     #[inline]
     pub fn validate_key(&self, key: &str) -> bool {
-        self.keys.read().contains_key(key)
+        self.check_key(key).is_ok()
+    }
+
+    /// Checks an API key and reports why it was rejected.
+    ///
+    /// # Arguments
+    /// * `key` - The API key to check
+    ///
+    /// # Returns
+    /// * `Ok(ApiKeyPrincipal)` if the key is valid
+    /// * `Err(ApiKeyRejection)` with the reason otherwise
+    pub fn check_key(&self, key: &str) -> Result<ApiKeyPrincipal, ApiKeyRejection> {
+        match self.keys.read().get(key) {
+            None => Err(ApiKeyRejection::UnknownKey),
+            Some(attributes) if is_expired(attributes.expires_at, Local::now().naive_local()) => {
+                Err(ApiKeyRejection::ExpiredKey)
+            },
+            Some(attributes) => Ok(ApiKeyPrincipal {
+                access_key_id: key.to_string(),
+                domain: attributes.domain.clone(),
+            }),
+        }
     }
 
     /// Adds a new valid API key.
@@ -142,7 +196,17 @@ impl SimpleApiKeyValidator {
     /// * `key` - The API key to add
     #[inline]
     pub fn add_key(&self, key: String) {
-        self.keys.write().insert(key, ());
+        self.add_key_with_attributes(key, ApiKeyAttributes::default());
+    }
+
+    /// Adds a new valid API key with an expiry time and domain.
+    ///
+    /// # Arguments
+    /// * `key` - The API key to add
+    /// * `attributes` - Expiry and domain of the key
+    #[inline]
+    pub fn add_key_with_attributes(&self, key: String, attributes: ApiKeyAttributes) {
+        self.keys.write().insert(key, attributes);
     }
 
     /// Removes an API key from the set of valid keys.
@@ -153,6 +217,18 @@ impl SimpleApiKeyValidator {
     pub fn remove_key(&self, key: &str) {
         self.keys.write().remove(key);
     }
+
+    /// Replaces the whole set of valid keys in a single step.
+    ///
+    /// # Arguments
+    /// * `keys` - The new set of valid API keys and their attributes
+    pub fn replace_keys<I>(&self, keys: I)
+    where
+        I: IntoIterator<Item = (String, ApiKeyAttributes)>,
+    {
+        let keys = keys.into_iter().collect();
+        *self.keys.write() = keys;
+    }
 }
 
 impl Default for SimpleApiKeyValidator {
@@ -162,6 +238,75 @@ impl Default for SimpleApiKeyValidator {
     }
 }
 
+/// A secret accepted for an API key.
+///
+/// A key normally has a single secret without expiry. While a secret is being
+/// rotated, the previous secret stays valid until `expires_at`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeySecret {
+    /// The secret used to sign requests
+    pub secret: String,
+    /// Local time after which the secret is no longer accepted
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl ApiKeySecret {
+    /// Creates a secret that never expires.
+    #[inline]
+    pub fn new(secret: String) -> Self {
+        Self {
+            secret,
+            expires_at: None,
+        }
+    }
+
+    /// Creates a secret that is accepted until the given local time.
+    #[inline]
+    pub fn expiring(secret: String, expires_at: NaiveDateTime) -> Self {
+        Self {
+            secret,
+            expires_at: Some(expires_at),
+        }
+    }
+
+    /// Checks whether the secret is still accepted at `now`.
+    #[inline]
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
+/// Secrets, expiry and domain of an API key held by [`ComplexApiKeyValidator`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiKeyCredentials {
+    /// Secrets accepted for the key
+    pub secrets: Vec<ApiKeySecret>,
+    /// Local time after which the key is rejected, whatever the secret
+    pub expires_at: Option<NaiveDateTime>,
+    /// Domain the key belongs to
+    pub domain: Option<String>,
+}
+
+impl ApiKeyCredentials {
+    /// Creates credentials with a single secret, no expiry and no domain.
+    #[inline]
+    pub fn new(secret: String) -> Self {
+        Self {
+            secrets: vec![ApiKeySecret::new(secret)],
+            ..Default::default()
+        }
+    }
+
+    /// Returns the expiry and domain of the key.
+    #[inline]
+    pub fn attributes(&self) -> ApiKeyAttributes {
+        ApiKeyAttributes {
+            expires_at: self.expires_at,
+            domain: self.domain.clone(),
+        }
+    }
+}
+
 /// Complex API key validator that supports multiple signature algorithms and nonce validation.
 ///
 /// This validator provides advanced API key validation with features including:
@@ -171,11 +316,12 @@ impl Default for SimpleApiKeyValidator {
 /// - URL parameter signing
 ///
 /// API keys and their corresponding secrets are stored permanently and can only be
-/// modified through explicit API calls.
+/// modified through explicit API calls. A key may hold several secrets at once,
+/// so that clients can move to a rotated secret during a grace period.
 #[derive(Clone)]
 pub struct ComplexApiKeyValidator {
-    secrets: Arc<RwLock<HashMap<String, String>>>,
-    nonce_store: Arc<MemoryNonceStore>,
+    secrets: Arc<RwLock<HashMap<String, ApiKeyCredentials>>>,
+    nonce_store: Arc<dyn NonceStore>,
     config: ApiKeyConfig,
 }
 
@@ -186,21 +332,34 @@ impl ComplexApiKeyValidator {
     /// * `config` - Optional API key validation configuration. If None, uses default configuration.
     #[inline]
     pub fn new(config: Option<ApiKeyConfig>) -> Self {
+        Self::with_nonce_store(config, Arc::new(MemoryNonceStore::new()))
+    }
+
+    /// Creates a new ComplexApiKeyValidator backed by the given nonce store.
+    ///
+    /// # Arguments
+    /// * `config` - Optional API key validation configuration. If None, uses default configuration.
+    /// * `nonce_store` - Store used to reject replayed nonces, shared across instances if distributed
+    #[inline]
+    pub fn with_nonce_store(
+        config: Option<ApiKeyConfig>,
+        nonce_store: Arc<dyn NonceStore>,
+    ) -> Self {
         Self {
             secrets: Arc::new(RwLock::new(HashMap::with_capacity(DEFAULT_CAPACITY))),
-            nonce_store: Arc::new(MemoryNonceStore::new()),
+            nonce_store,
             config: config.unwrap_or_default(),
         }
     }
 
-    /// Validates if a timestamp is within the allowed 5-minute window.
+    /// Validates if a timestamp is within the configured disparity window.
     #[inline]
     fn validate_timestamp(&self, timestamp: i64) -> bool {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_millis() as i64;
-        (now - timestamp).abs() < TIMESTAMP_DISPARITY_MS
+        (now - timestamp).abs() < self.config.timestamp_disparity_ms
     }
 
     /// Calculates signature for a signing string using the configured algorithm.
@@ -250,7 +409,10 @@ impl ComplexApiKeyValidator {
     /// # Returns
     /// * `true` if the request is valid
     /// * `false` if any validation check fails
-    pub fn validate_signature(
+    ///
+    /// The nonce is only recorded once the signature is known to be valid, so
+    /// unsigned garbage cannot be used to fill the nonce store.
+    pub async fn validate_signature(
         &self,
         api_key: &str,
         params: &[(String, String)],
@@ -258,20 +420,81 @@ impl ComplexApiKeyValidator {
         timestamp: i64,
         nonce: &str,
     ) -> bool {
+        self.check_signature(api_key, params, signature, timestamp, nonce)
+            .await
+            .is_ok()
+    }
+
+    /// Validates a signed API request and reports why it was rejected.
+    ///
+    /// Takes the same arguments as [`Self::validate_signature`].
+    pub async fn check_signature(
+        &self,
+        api_key: &str,
+        params: &[(String, String)],
+        signature: &str,
+        timestamp: i64,
+        nonce: &str,
+    ) -> Result<ApiKeyPrincipal, ApiKeyRejection> {
+        let (principal, secrets) = self.active_secrets(api_key)?;
+
         if !self.validate_timestamp(timestamp) {
-            return false;
+            return Err(ApiKeyRejection::ExpiredTimestamp);
+        }
+
+        if !self.verify_params_signature(&secrets, params, signature) {
+            return Err(ApiKeyRejection::BadSignature);
         }
 
-        if !self.nonce_store.check_and_set(nonce) {
-            return false;
+        self.check_nonce(nonce).await?;
+        Ok(principal)
+    }
+
+    /// Returns the principal of an API key and its secrets that are currently accepted.
+    fn active_secrets(
+        &self,
+        api_key: &str,
+    ) -> Result<(ApiKeyPrincipal, Vec<String>), ApiKeyRejection> {
+        let now = Local::now().naive_local();
+        let guard = self.secrets.read();
+        let credentials = guard.get(api_key).ok_or(ApiKeyRejection::UnknownKey)?;
+        if is_expired(credentials.expires_at, now) {
+            return Err(ApiKeyRejection::ExpiredKey);
         }
 
-        let secrets_guard = self.secrets.read();
-        let secret = match secrets_guard.get(api_key) {
-            Some(s) => Cow::Borrowed(s),
-            None => return false,
+        let principal = ApiKeyPrincipal {
+            access_key_id: api_key.to_string(),
+            domain: credentials.domain.clone(),
         };
+        let secrets = credentials
+            .secrets
+            .iter()
+            .filter(|secret| secret.is_active(now))
+            .map(|secret| secret.secret.clone())
+            .collect();
+        Ok((principal, secrets))
+    }
 
+    /// Records the nonce, rejecting it if it has been used before.
+    async fn check_nonce(&self, nonce: &str) -> Result<(), ApiKeyRejection> {
+        if self
+            .nonce_store
+            .check_and_set(nonce, Duration::from_secs(self.config.nonce_ttl_secs))
+            .await
+        {
+            Ok(())
+        } else {
+            Err(ApiKeyRejection::ReplayedNonce)
+        }
+    }
+
+    /// Checks the signature over the sorted request parameters.
+    fn verify_params_signature(
+        &self,
+        secrets: &[String],
+        params: &[(String, String)],
+        signature: &str,
+    ) -> bool {
         // Pre-allocate with capacity to avoid reallocations
         let mut sorted_params: Vec<_> = Vec::with_capacity(params.len());
         sorted_params.extend_from_slice(params);
@@ -297,7 +520,65 @@ impl ComplexApiKeyValidator {
             }
         }
 
-        self.calculate_signature(&signing_string, &secret) == signature
+        secrets
+            .iter()
+            .any(|secret| self.calculate_signature(&signing_string, secret) == signature)
+    }
+
+    /// Validates a v2 signed request.
+    ///
+    /// The v2 signature is always HMAC-SHA256, independent of the configured
+    /// v1 algorithm. The nonce is only recorded once the signature is valid.
+    ///
+    /// # Arguments
+    /// * `api_key` - The API key to validate
+    /// * `canonical_request` - The canonical request built from the incoming request
+    /// * `signature` - The signature to validate
+    /// * `timestamp` - Request timestamp in milliseconds since UNIX epoch
+    /// * `nonce` - Unique request identifier to prevent replay attacks
+    ///
+    /// # Returns
+    /// * `true` if the request is valid
+    /// * `false` if any validation check fails
+    pub async fn validate_signature_v2(
+        &self,
+        api_key: &str,
+        canonical_request: &str,
+        signature: &str,
+        timestamp: i64,
+        nonce: &str,
+    ) -> bool {
+        self.check_signature_v2(api_key, canonical_request, signature, timestamp, nonce)
+            .await
+            .is_ok()
+    }
+
+    /// Validates a v2 signed request and reports why it was rejected.
+    ///
+    /// Takes the same arguments as [`Self::validate_signature_v2`].
+    pub async fn check_signature_v2(
+        &self,
+        api_key: &str,
+        canonical_request: &str,
+        signature: &str,
+        timestamp: i64,
+        nonce: &str,
+    ) -> Result<ApiKeyPrincipal, ApiKeyRejection> {
+        let (principal, secrets) = self.active_secrets(api_key)?;
+
+        if !self.validate_timestamp(timestamp) {
+            return Err(ApiKeyRejection::ExpiredTimestamp);
+        }
+
+        let verified = secrets.iter().any(|secret| {
+            verify_signature_v2(secret, timestamp, nonce, canonical_request, signature)
+        });
+        if !verified {
+            return Err(ApiKeyRejection::BadSignature);
+        }
+
+        self.check_nonce(nonce).await?;
+        Ok(principal)
     }
 
     /// Adds a new API key and its corresponding secret.
@@ -307,7 +588,17 @@ impl ComplexApiKeyValidator {
     /// * `secret` - The secret corresponding to the API key
     #[inline]
     pub fn add_key_secret(&self, key: String, secret: String) {
-        self.secrets.write().insert(key, secret);
+        self.set_key_credentials(key, ApiKeyCredentials::new(secret));
+    }
+
+    /// Sets the secrets, expiry and domain of an API key, replacing any existing ones.
+    ///
+    /// # Arguments
+    /// * `key` - The API key to update
+    /// * `credentials` - The secrets accepted for the API key, its expiry and domain
+    #[inline]
+    pub fn set_key_credentials(&self, key: String, credentials: ApiKeyCredentials) {
+        self.secrets.write().insert(key, credentials);
     }
 
     /// Removes an API key and its secret.
@@ -319,6 +610,18 @@ impl ComplexApiKeyValidator {
         self.secrets.write().remove(key);
     }
 
+    /// Replaces all API keys and secrets in a single step.
+    ///
+    /// # Arguments
+    /// * `key_secrets` - The new set of API keys and their credentials
+    pub fn replace_key_credentials<I>(&self, key_secrets: I)
+    where
+        I: IntoIterator<Item = (String, ApiKeyCredentials)>,
+    {
+        let secrets = key_secrets.into_iter().collect();
+        *self.secrets.write() = secrets;
+    }
+
     /// Updates the API key validation configuration.
     ///
     /// # Arguments
@@ -344,18 +647,78 @@ mod tests {
     }
 
     #[test]
-    fn test_nonce_store() {
-        let store = MemoryNonceStore::new();
-        assert!(store.check_and_set("nonce1"));
-        assert!(!store.check_and_set("nonce1"));
-        thread::sleep(std::time::Duration::from_secs(NONCE_TTL_SECS + 1));
-        assert!(store.check_and_set("nonce1"));
+    fn test_replace_keys() {
+        let simple = SimpleApiKeyValidator::new();
+        simple.add_key("stale-key".to_string());
+        simple.replace_keys(vec![("fresh-key".to_string(), ApiKeyAttributes::default())]);
+        assert!(simple.validate_key("fresh-key"));
+        assert!(!simple.validate_key("stale-key"));
+
+        let complex = ComplexApiKeyValidator::new(None);
+        complex.add_key_secret("stale-key".to_string(), "stale-secret".to_string());
+        complex.replace_key_credentials(vec![(
+            "fresh-key".to_string(),
+            ApiKeyCredentials::new("fresh-secret".to_string()),
+        )]);
+        assert_eq!(
+            complex
+                .active_secrets("fresh-key")
+                .map(|(_, secrets)| secrets),
+            Ok(vec!["fresh-secret".to_string()])
+        );
+        assert!(!complex.secrets.read().contains_key("stale-key"));
     }
 
-    #[test]
-    fn test_complex_validator() {
+    #[tokio::test]
+    async fn test_rotated_secrets() {
+        let validator = ComplexApiKeyValidator::new(None);
+        let now = Local::now().naive_local();
+        validator.set_key_credentials(
+            "test-key".to_string(),
+            ApiKeyCredentials {
+                secrets: vec![
+                    ApiKeySecret::new("new-secret".to_string()),
+                    ApiKeySecret::expiring(
+                        "old-secret".to_string(),
+                        now + chrono::Duration::hours(1),
+                    ),
+                    ApiKeySecret::expiring(
+                        "retired-secret".to_string(),
+                        now - chrono::Duration::seconds(1),
+                    ),
+                ],
+                ..Default::default()
+            },
+        );
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let params = vec![("data".to_string(), "test-data".to_string())];
+
+        for (secret, nonce, accepted) in [
+            ("new-secret", "nonce-new", true),
+            ("old-secret", "nonce-old", true),
+            ("retired-secret", "nonce-retired", false),
+        ] {
+            let signature = validator.calculate_signature("data=test-data", secret);
+            assert_eq!(
+                validator
+                    .validate_signature("test-key", &params, &signature, timestamp, nonce)
+                    .await,
+                accepted,
+                "{}",
+                secret
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_complex_validator() {
         let validator = ComplexApiKeyValidator::new(Some(ApiKeyConfig {
             algorithm: SignatureAlgorithm::Md5,
+            ..Default::default()
         }));
 
         validator.add_key_secret("test-key".to_string(), "test-secret".to_string());
@@ -374,7 +737,140 @@ mod tests {
         let signing_string = format!("data=test-data&nonce=test-nonce&timestamp={}", now);
         let signature = validator.calculate_signature(&signing_string, "test-secret");
 
-        assert!(validator.validate_signature("test-key", &params, &signature, now, "test-nonce"));
+        assert!(
+            validator
+                .validate_signature("test-key", &params, &signature, now, "test-nonce")
+                .await
+        );
+        // Replaying the same nonce is rejected
+        assert!(
+            !validator
+                .validate_signature("test-key", &params, &signature, now, "test-nonce")
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejection_reasons() {
+        let now = Local::now().naive_local();
+        let simple = SimpleApiKeyValidator::new();
+        simple.add_key_with_attributes(
+            "live-key".to_string(),
+            ApiKeyAttributes {
+                expires_at: Some(now + chrono::Duration::hours(1)),
+                domain: Some("built-in".to_string()),
+            },
+        );
+        simple.add_key_with_attributes(
+            "dead-key".to_string(),
+            ApiKeyAttributes {
+                expires_at: Some(now),
+                domain: None,
+            },
+        );
+        assert_eq!(
+            simple.check_key("live-key"),
+            Ok(ApiKeyPrincipal {
+                access_key_id: "live-key".to_string(),
+                domain: Some("built-in".to_string()),
+            })
+        );
+        assert_eq!(
+            simple.check_key("dead-key"),
+            Err(ApiKeyRejection::ExpiredKey)
+        );
+        assert_eq!(simple.check_key("no-key"), Err(ApiKeyRejection::UnknownKey));
+
+        let complex = ComplexApiKeyValidator::new(None);
+        complex.add_key_secret("test-key".to_string(), "test-secret".to_string());
+        complex.set_key_credentials(
+            "dead-key".to_string(),
+            ApiKeyCredentials {
+                expires_at: Some(now),
+                ..ApiKeyCredentials::new("test-secret".to_string())
+            },
+        );
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let params = vec![("data".to_string(), "test-data".to_string())];
+        let signature = complex.calculate_signature("data=test-data", "test-secret");
+
+        assert_eq!(
+            complex
+                .check_signature("dead-key", &params, &signature, timestamp, "n1")
+                .await,
+            Err(ApiKeyRejection::ExpiredKey)
+        );
+        assert_eq!(
+            complex
+                .check_signature("test-key", &params, &signature, 0, "n1")
+                .await,
+            Err(ApiKeyRejection::ExpiredTimestamp)
+        );
+        assert_eq!(
+            complex
+                .check_signature("test-key", &params, "bad", timestamp, "n1")
+                .await,
+            Err(ApiKeyRejection::BadSignature)
+        );
+        assert_eq!(
+            complex
+                .check_signature("test-key", &params, &signature, timestamp, "n1")
+                .await
+                .map(|principal| principal.access_key_id),
+            Ok("test-key".to_string())
+        );
+        assert_eq!(
+            complex
+                .check_signature("test-key", &params, &signature, timestamp, "n1")
+                .await,
+            Err(ApiKeyRejection::ReplayedNonce)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timestamp_disparity_config() {
+        let validator = ComplexApiKeyValidator::new(Some(ApiKeyConfig {
+            timestamp_disparity_ms: 1_000,
+            ..Default::default()
+        }));
+        validator.add_key_secret("test-key".to_string(), "test-secret".to_string());
+
+        let stale = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64
+            - 5_000;
+        let params = vec![("data".to_string(), "test-data".to_string())];
+        let signature = validator.calculate_signature("data=test-data", "test-secret");
+
+        assert!(
+            !validator
+                .validate_signature("test-key", &params, &signature, stale, "stale-nonce")
+                .await
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(ApiKeyConfig::default().validate().is_ok());
+        // TTL shorter than the replay window would accept a replay after the nonce expires
+        assert!(ApiKeyConfig {
+            nonce_ttl_secs: 300,
+            timestamp_disparity_ms: 300_000,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(ApiKeyConfig {
+            timestamp_disparity_ms: 0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
     }
 
     #[test]
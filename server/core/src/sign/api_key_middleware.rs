@@ -1,21 +1,43 @@
 use axum::{
-    body::Body,
-    extract::Request,
-    http::{HeaderMap, StatusCode, Uri},
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Request},
+    http::{request::Parts, HeaderMap, StatusCode, Uri},
     middleware::Next,
     response::IntoResponse,
 };
-use once_cell::sync::Lazy;
-use std::{collections::HashSet, sync::RwLock};
+use axum_casbin::CasbinVals;
+use chrono::{Local, NaiveDateTime};
+use server_global::global;
+use std::net::SocketAddr;
 
-use crate::web::res::Res;
+use crate::web::{res::Res, util::ClientIp};
 
-use super::{ComplexApiKeyValidator, SimpleApiKeyValidator};
+use super::{
+    signature_v2::{canonical_request, SignatureV2Authorization},
+    ApiKeyPrincipal, ApiKeyRejection, ComplexApiKeyValidator, RouteMatcher, SimpleApiKeyValidator,
+};
+
+/// Name of the dynamic event carrying [`AccessKeyUsageEvent`]s.
+pub const ACCESS_KEY_USAGE_EVENT: &str = "access_key_usage";
 
-/// Global set of protected paths.
+/// Usage of a known API key, sent through the event channel after validation.
 ///
-/// This set stores the paths that require API key validation.
-static PROTECTED_PATHS: Lazy<RwLock<HashSet<String>>> = Lazy::new(|| RwLock::new(HashSet::new()));
+/// Recording usage off the request path keeps database writes out of the
+/// latency of every API call.
+#[derive(Debug, Clone)]
+pub struct AccessKeyUsageEvent {
+    /// The access key ID presented by the client.
+    pub access_key_id: String,
+    /// Client IP address.
+    pub ip: String,
+    /// Validation outcome.
+    pub outcome: Result<(), ApiKeyRejection>,
+    /// Local time the request was validated.
+    pub occurred_at: NaiveDateTime,
+}
+
+/// Access key ID presented by a request and the outcome of validating it.
+type KeyValidation = (String, Result<ApiKeyPrincipal, ApiKeyRejection>);
 
 /// Source location for API key.
 ///
@@ -37,6 +59,8 @@ pub struct SimpleApiKeyConfig {
     pub source: ApiKeySource,
     /// Name of API key parameter.
     pub key_name: String,
+    /// Routes requiring API key validation.
+    pub routes: RouteMatcher,
 }
 
 impl Default for SimpleApiKeyConfig {
@@ -44,6 +68,7 @@ impl Default for SimpleApiKeyConfig {
         Self {
             source: ApiKeySource::Header,
             key_name: "x-api-key".to_string(),
+            routes: RouteMatcher::default(),
         }
     }
 }
@@ -61,6 +86,8 @@ pub struct ComplexApiKeyConfig {
     pub nonce_name: String,
     /// Signature parameter name.
     pub signature_name: String,
+    /// Routes requiring API key validation.
+    pub routes: RouteMatcher,
 }
 
 impl Default for ComplexApiKeyConfig {
@@ -70,6 +97,37 @@ impl Default for ComplexApiKeyConfig {
             timestamp_name: "timestamp".to_string(),
             nonce_name: "nonce".to_string(),
             signature_name: "signature".to_string(),
+            routes: RouteMatcher::default(),
+        }
+    }
+}
+
+/// Configuration for v2 canonical request signing.
+///
+/// This struct holds the configuration for v2 signed requests, whose
+/// credentials are carried in the `Authorization` header.
+#[derive(Clone)]
+pub struct SignedApiKeyConfig {
+    /// Header carrying the access key ID, signed headers and signature.
+    pub authorization_header: String,
+    /// Header carrying the request timestamp in milliseconds.
+    pub timestamp_header: String,
+    /// Header carrying the request nonce.
+    pub nonce_header: String,
+    /// Maximum request body size buffered for hashing, in bytes.
+    pub max_body_bytes: usize,
+    /// Routes requiring API key validation.
+    pub routes: RouteMatcher,
+}
+
+impl Default for SignedApiKeyConfig {
+    fn default() -> Self {
+        Self {
+            authorization_header: "authorization".to_string(),
+            timestamp_header: "x-soybean-date".to_string(),
+            nonce_header: "x-soybean-nonce".to_string(),
+            max_body_bytes: 1024 * 1024,
+            routes: RouteMatcher::default(),
         }
     }
 }
@@ -83,55 +141,117 @@ pub enum ApiKeyValidation {
     Simple(SimpleApiKeyValidator, SimpleApiKeyConfig),
     /// Complex API key validation with signature.
     Complex(ComplexApiKeyValidator, ComplexApiKeyConfig),
+    /// Complex API key validation with v2 canonical request signature.
+    Signed(ComplexApiKeyValidator, SignedApiKeyConfig),
 }
 
-/// Add a path to protected routes requiring API key validation.
-///
-/// This function adds a path to the set of protected paths.
-#[allow(dead_code)]
-pub fn protect_route(path: &str) {
-    if let Ok(mut paths) = PROTECTED_PATHS.write() {
-        paths.insert(path.to_string());
-    }
-}
-
-/// Check if URI path requires API key validation.
-///
-/// This function checks if the given URI path is in the set of protected paths.
-#[inline]
-fn is_protected_path(uri: &Uri) -> bool {
-    if let Ok(paths) = PROTECTED_PATHS.read() {
-        let path = uri.path();
-        paths.contains(path.strip_suffix('/').unwrap_or(path))
-    } else {
-        false
+impl ApiKeyValidation {
+    /// Routes this validation applies to.
+    #[inline]
+    pub fn routes(&self) -> &RouteMatcher {
+        match self {
+            ApiKeyValidation::Simple(_, config) => &config.routes,
+            ApiKeyValidation::Complex(_, config) => &config.routes,
+            ApiKeyValidation::Signed(_, config) => &config.routes,
+        }
     }
 }
 
 /// API key validation middleware.
 ///
 /// This middleware checks if the API key is valid for the given request.
+/// On success the [`ApiKeyPrincipal`] is added to the request extensions,
+/// and for keys bound to a domain so are [`CasbinVals`] with the access key
+/// ID as subject. A `CasbinAxumLayer` applied inside this middleware then
+/// only lets the key through to paths granted to it by casbin policies.
 #[inline]
 pub async fn api_key_middleware(
     validator: ApiKeyValidation,
     req: Request<Body>,
     next: Next,
 ) -> impl IntoResponse {
-    if !is_protected_path(req.uri()) {
+    if !validator.routes().matches(req.method(), req.uri().path()) {
         return next.run(req).await.into_response();
     }
 
-    match validate_request(&validator, &req) {
-        Ok(true) => next.run(req).await.into_response(),
-        Ok(false) => Res::<()>::new_error(
-            StatusCode::UNAUTHORIZED.as_u16(),
-            "Invalid API key or signature",
-        )
-        .into_response(),
+    let (mut req, result) = match &validator {
+        ApiKeyValidation::Signed(validator, config) => {
+            // 签名覆盖请求体，需要先读取完整的请求体再放回请求中
+            let (parts, body) = req.into_parts();
+            let body = match to_bytes(body, config.max_body_bytes).await {
+                Ok(body) => body,
+                Err(_) => {
+                    return Res::<()>::new_error(
+                        StatusCode::PAYLOAD_TOO_LARGE.as_u16(),
+                        "Request body too large",
+                    )
+                    .into_response()
+                },
+            };
+            let result = validate_signed_request(validator, config, &parts, &body).await;
+            (Request::from_parts(parts, Body::from(body)), result)
+        },
+        _ => {
+            let result = validate_request(&validator, req.headers(), req.uri()).await;
+            (req, result)
+        },
+    };
+
+    match result {
+        Ok((access_key_id, outcome)) => {
+            record_usage(
+                &req,
+                access_key_id,
+                outcome.as_ref().map(|_| ()).map_err(|rejection| *rejection),
+            );
+            match outcome {
+                Ok(principal) => {
+                    if let Some(domain) = &principal.domain {
+                        req.extensions_mut().insert(CasbinVals {
+                            subject: vec![principal.access_key_id.clone()],
+                            domain: Some(domain.clone()),
+                        });
+                    }
+                    req.extensions_mut().insert(principal);
+                    next.run(req).await.into_response()
+                },
+                Err(_) => Res::<()>::new_error(
+                    StatusCode::UNAUTHORIZED.as_u16(),
+                    "Invalid API key or signature",
+                )
+                .into_response(),
+            }
+        },
         Err(e) => Res::<()>::new_error(StatusCode::BAD_REQUEST.as_u16(), e).into_response(),
     }
 }
 
+/// Send the usage of a known API key to the event channel.
+///
+/// Unknown keys are not recorded, so arbitrary IDs sent by clients cannot
+/// grow the usage statistics.
+fn record_usage(req: &Request<Body>, access_key_id: String, outcome: Result<(), ApiKeyRejection>) {
+    if outcome == Err(ApiKeyRejection::UnknownKey) {
+        return;
+    }
+
+    let ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| ClientIp::get_real_ip(req.headers()));
+
+    global::send_dyn_event(
+        ACCESS_KEY_USAGE_EVENT,
+        Box::new(AccessKeyUsageEvent {
+            access_key_id,
+            ip,
+            outcome,
+            occurred_at: Local::now().naive_local(),
+        }),
+    );
+}
+
 /// Get value from request headers.
 ///
 /// This function retrieves the value of a header from the request headers.
@@ -154,13 +274,12 @@ fn get_query_value<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a
 /// Validate API key in request.
 ///
 /// This function validates the API key in the given request.
-#[inline]
-fn validate_request(
+async fn validate_request(
     validator: &ApiKeyValidation,
-    req: &Request<Body>,
-) -> Result<bool, &'static str> {
-    let headers = req.headers();
-    let query = req.uri().query().unwrap_or("");
+    headers: &HeaderMap,
+    uri: &Uri,
+) -> Result<KeyValidation, &'static str> {
+    let query = uri.query().unwrap_or("");
     let params = if !query.is_empty() {
         parse_query(query)
     } else {
@@ -175,7 +294,7 @@ fn validate_request(
             }
             .ok_or("Missing API key")?;
 
-            Ok(validator.validate_key(api_key))
+            Ok((api_key.to_string(), validator.check_key(api_key)))
         },
         ApiKeyValidation::Complex(validator, config) => {
             let api_key =
@@ -197,15 +316,64 @@ fn validate_request(
                 .map(|(k, v)| (k.clone(), v.clone()))
                 .collect();
 
-            Ok(validator.validate_signature(
-                api_key,
-                &params_for_signing,
-                signature,
-                timestamp,
-                nonce,
-            ))
+            let outcome = validator
+                .check_signature(api_key, &params_for_signing, signature, timestamp, nonce)
+                .await;
+            Ok((api_key.to_string(), outcome))
         },
+        // 签名覆盖请求体，由 validate_signed_request 处理
+        ApiKeyValidation::Signed(..) => Err("Signed request requires the request body"),
+    }
+}
+
+/// Validate v2 signed request.
+///
+/// This function validates the `Authorization` header of the given request
+/// against its canonical form. The timestamp and nonce headers must be part
+/// of the signed headers so they cannot be swapped on a captured request.
+async fn validate_signed_request(
+    validator: &ComplexApiKeyValidator,
+    config: &SignedApiKeyConfig,
+    parts: &Parts,
+    body: &[u8],
+) -> Result<KeyValidation, &'static str> {
+    let authorization = get_header_value(&parts.headers, &config.authorization_header)
+        .ok_or("Missing Authorization")?;
+    let authorization = SignatureV2Authorization::parse(authorization)?;
+
+    let timestamp_header = config.timestamp_header.to_ascii_lowercase();
+    let nonce_header = config.nonce_header.to_ascii_lowercase();
+    if !authorization.signed_headers.contains(&timestamp_header)
+        || !authorization.signed_headers.contains(&nonce_header)
+    {
+        return Err("Timestamp and nonce headers must be signed");
     }
+
+    let timestamp = get_header_value(&parts.headers, &timestamp_header)
+        .ok_or("Missing timestamp")?
+        .parse::<i64>()
+        .map_err(|_| "Invalid timestamp")?;
+    let nonce = get_header_value(&parts.headers, &nonce_header).ok_or("Missing nonce")?;
+
+    let canonical = canonical_request(
+        &parts.method,
+        parts.uri.path(),
+        parts.uri.query().unwrap_or(""),
+        &parts.headers,
+        &authorization.signed_headers,
+        body,
+    )?;
+
+    let outcome = validator
+        .check_signature_v2(
+            &authorization.access_key_id,
+            &canonical,
+            &authorization.signature,
+            timestamp,
+            nonce.trim(),
+        )
+        .await;
+    Ok((authorization.access_key_id, outcome))
 }
 
 /// Parse query string into key-value pairs.
@@ -230,6 +398,7 @@ fn parse_query(query: &str) -> Vec<(String, String)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sign::calculate_signature_v2;
     use std::time::{SystemTime, UNIX_EPOCH};
 
     #[test]
@@ -266,4 +435,135 @@ mod tests {
             signing_string, signature
         );
     }
+
+    #[tokio::test]
+    async fn test_principal_injected() {
+        use axum::{routing::get, Extension, Router};
+        use tower::ServiceExt;
+
+        use crate::sign::ApiKeyAttributes;
+
+        let validator = SimpleApiKeyValidator::new();
+        validator.add_key_with_attributes(
+            "scoped-key".to_string(),
+            ApiKeyAttributes {
+                expires_at: None,
+                domain: Some("built-in".to_string()),
+            },
+        );
+        let validation = ApiKeyValidation::Simple(
+            validator,
+            SimpleApiKeyConfig {
+                routes: RouteMatcher::new().route("/principal").unwrap(),
+                ..Default::default()
+            },
+        );
+
+        let app = Router::new()
+            .route(
+                "/principal",
+                get(|Extension(vals): Extension<CasbinVals>| async move {
+                    format!(
+                        "{}@{}",
+                        vals.subject.join(","),
+                        vals.domain.unwrap_or_default()
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn(move |req, next| {
+                api_key_middleware(validation.clone(), req, next)
+            }));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/principal")
+                    .header("x-api-key", "scoped-key")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"scoped-key@built-in");
+    }
+
+    #[tokio::test]
+    async fn test_signed_request_v2() {
+        let validator = ComplexApiKeyValidator::new(None);
+        validator.add_key_secret("test-access-key".to_string(), "test-secret-key".to_string());
+        let config = SignedApiKeyConfig::default();
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let nonce = format!("nonce_{}", timestamp);
+        let body = br#"{"amount":1}"#;
+
+        let build = |signature: &str, path: &str| {
+            let signed_headers = "host;x-soybean-date;x-soybean-nonce";
+            Request::builder()
+                .method("POST")
+                .uri(format!("{}?b=2&a=1", path))
+                .header("host", "example.com")
+                .header("x-soybean-date", timestamp.to_string())
+                .header("x-soybean-nonce", nonce.as_str())
+                .header(
+                    "authorization",
+                    format!(
+                        "SOYBEAN-HMAC-SHA256 Credential=test-access-key, SignedHeaders={}, \
+                         Signature={}",
+                        signed_headers, signature
+                    ),
+                )
+                .body(())
+                .unwrap()
+                .into_parts()
+                .0
+        };
+
+        let unsigned = build("", "/orders");
+        let canonical = canonical_request(
+            &unsigned.method,
+            unsigned.uri.path(),
+            unsigned.uri.query().unwrap(),
+            &unsigned.headers,
+            &[
+                "host".to_string(),
+                "x-soybean-date".to_string(),
+                "x-soybean-nonce".to_string(),
+            ],
+            body,
+        )
+        .unwrap();
+        let signature = calculate_signature_v2("test-secret-key", timestamp, &nonce, &canonical);
+
+        // A different path or body with the same signature is rejected
+        let outcome = |result: Result<KeyValidation, &'static str>| result.unwrap().1;
+        let other_path = build(&signature, "/refunds");
+        assert_eq!(
+            outcome(validate_signed_request(&validator, &config, &other_path, body).await),
+            Err(ApiKeyRejection::BadSignature)
+        );
+        let parts = build(&signature, "/orders");
+        assert_eq!(
+            outcome(validate_signed_request(&validator, &config, &parts, b"{}").await),
+            Err(ApiKeyRejection::BadSignature)
+        );
+
+        assert_eq!(
+            outcome(validate_signed_request(&validator, &config, &parts, body).await),
+            Ok(ApiKeyPrincipal {
+                access_key_id: "test-access-key".to_string(),
+                domain: None,
+            })
+        );
+        // Replays are rejected by the nonce store
+        assert_eq!(
+            outcome(validate_signed_request(&validator, &config, &parts, body).await),
+            Err(ApiKeyRejection::ReplayedNonce)
+        );
+    }
 }
@@ -1,51 +1,83 @@
 mod api_key;
 mod api_key_middleware;
+mod nonce_store;
+mod route_matcher;
+mod signature_v2;
 
 pub use api_key::{
-    ApiKeyConfig, ComplexApiKeyValidator, MemoryNonceStore, SignatureAlgorithm,
-    SimpleApiKeyValidator,
+    ApiKeyAttributes, ApiKeyConfig, ApiKeyCredentials, ApiKeyPrincipal, ApiKeyRejection,
+    ApiKeySecret, ComplexApiKeyValidator, SignatureAlgorithm, SimpleApiKeyValidator,
+    NONCE_TTL_SECS, TIMESTAMP_DISPARITY_MS,
 };
 pub use api_key_middleware::{
-    api_key_middleware, protect_route, ApiKeySource, ApiKeyValidation, ComplexApiKeyConfig,
-    SimpleApiKeyConfig,
+    api_key_middleware, AccessKeyUsageEvent, ApiKeySource, ApiKeyValidation, ComplexApiKeyConfig,
+    SignedApiKeyConfig, SimpleApiKeyConfig, ACCESS_KEY_USAGE_EVENT,
+};
+pub use nonce_store::{MemoryNonceStore, NonceStore, RedisNonceStore};
+pub use route_matcher::RouteMatcher;
+pub use signature_v2::{
+    calculate_signature_v2, canonical_request, sha256_hex, SignatureV2Authorization,
+    SIGNATURE_V2_ALGORITHM,
 };
 
 use once_cell::sync::Lazy;
+use server_global::global::GLOBAL_PRIMARY_REDIS;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 pub enum ValidatorType {
     Simple,
     Complex,
+    Signed,
+}
+
+/// 各验证方式使用的验证器，签名验证与 v2 签名验证各自持有配置与密钥
+struct ApiKeyValidators {
+    simple: RwLock<SimpleApiKeyValidator>,
+    complex: RwLock<ComplexApiKeyValidator>,
+    signed: RwLock<ComplexApiKeyValidator>,
 }
 
-static API_KEY_VALIDATORS: Lazy<(
-    Arc<RwLock<SimpleApiKeyValidator>>,
-    Arc<RwLock<ComplexApiKeyValidator>>,
-)> = Lazy::new(|| {
-    (
-        Arc::new(RwLock::new(SimpleApiKeyValidator::new())),
-        Arc::new(RwLock::new(ComplexApiKeyValidator::new(None))),
-    )
+static API_KEY_VALIDATORS: Lazy<ApiKeyValidators> = Lazy::new(|| ApiKeyValidators {
+    simple: RwLock::new(SimpleApiKeyValidator::new()),
+    complex: RwLock::new(ComplexApiKeyValidator::new(None)),
+    signed: RwLock::new(ComplexApiKeyValidator::new(None)),
 });
 
 pub async fn get_simple_validator() -> SimpleApiKeyValidator {
-    API_KEY_VALIDATORS.0.read().await.clone()
+    API_KEY_VALIDATORS.simple.read().await.clone()
 }
 
 pub async fn get_complex_validator() -> ComplexApiKeyValidator {
-    API_KEY_VALIDATORS.1.read().await.clone()
+    API_KEY_VALIDATORS.complex.read().await.clone()
 }
 
-pub async fn add_key(validator_type: ValidatorType, key: &str, secret: Option<&str>) {
+pub async fn get_signed_validator() -> ComplexApiKeyValidator {
+    API_KEY_VALIDATORS.signed.read().await.clone()
+}
+
+fn signature_validator(
+    validator_type: ValidatorType,
+) -> Option<&'static RwLock<ComplexApiKeyValidator>> {
     match validator_type {
-        ValidatorType::Simple => {
-            API_KEY_VALIDATORS.0.write().await.add_key(key.to_string());
+        ValidatorType::Simple => None,
+        ValidatorType::Complex => Some(&API_KEY_VALIDATORS.complex),
+        ValidatorType::Signed => Some(&API_KEY_VALIDATORS.signed),
+    }
+}
+
+pub async fn add_key(validator_type: ValidatorType, key: &str, secret: Option<&str>) {
+    match signature_validator(validator_type) {
+        None => {
+            API_KEY_VALIDATORS
+                .simple
+                .write()
+                .await
+                .add_key(key.to_string());
         },
-        ValidatorType::Complex => {
+        Some(validator) => {
             if let Some(secret) = secret {
-                API_KEY_VALIDATORS
-                    .1
+                validator
                     .write()
                     .await
                     .add_key_secret(key.to_string(), secret.to_string());
@@ -55,17 +87,76 @@ pub async fn add_key(validator_type: ValidatorType, key: &str, secret: Option<&s
 }
 
 pub async fn remove_key(validator_type: ValidatorType, key: &str) {
-    match validator_type {
-        ValidatorType::Simple => {
-            API_KEY_VALIDATORS.0.write().await.remove_key(key);
+    match signature_validator(validator_type) {
+        None => {
+            API_KEY_VALIDATORS.simple.write().await.remove_key(key);
         },
-        ValidatorType::Complex => {
-            API_KEY_VALIDATORS.1.write().await.remove_key(key);
+        Some(validator) => {
+            validator.write().await.remove_key(key);
         },
     }
 }
 
-pub async fn init_validators(config: Option<ApiKeyConfig>) {
-    let complex_validator = ComplexApiKeyValidator::new(config);
-    *API_KEY_VALIDATORS.1.write().await = complex_validator;
+/// 设置某个密钥当前接受的全部 secret、过期时间及所属域，轮换期间新旧 secret 同时有效
+///
+/// 简单验证器只同步过期时间与所属域，两个签名验证器同步全部信息。
+pub async fn set_key_credentials(key: &str, credentials: ApiKeyCredentials) {
+    API_KEY_VALIDATORS
+        .simple
+        .read()
+        .await
+        .add_key_with_attributes(key.to_string(), credentials.attributes());
+    API_KEY_VALIDATORS
+        .complex
+        .read()
+        .await
+        .set_key_credentials(key.to_string(), credentials.clone());
+    API_KEY_VALIDATORS
+        .signed
+        .read()
+        .await
+        .set_key_credentials(key.to_string(), credentials);
+}
+
+/// 以给定的 `(key, credentials)` 列表整体替换各验证器中的密钥
+pub async fn replace_keys(key_credentials: &[(String, ApiKeyCredentials)]) {
+    API_KEY_VALIDATORS.simple.read().await.replace_keys(
+        key_credentials
+            .iter()
+            .map(|(key, credentials)| (key.clone(), credentials.attributes())),
+    );
+    API_KEY_VALIDATORS
+        .complex
+        .read()
+        .await
+        .replace_key_credentials(key_credentials.iter().cloned());
+    API_KEY_VALIDATORS
+        .signed
+        .read()
+        .await
+        .replace_key_credentials(key_credentials.iter().cloned());
+}
+
+/// 初始化签名验证器
+///
+/// 签名验证与 v2 签名验证分别使用各自的防重放配置，配置无效时返回错误。
+/// 配置了主 Redis 时使用 Redis 记录 nonce，使多个实例共享防重放状态，
+/// 否则退回进程内的 moka 缓存。需在主 Redis 初始化之后、加载密钥之前调用。
+pub async fn init_validators(complex: ApiKeyConfig, signed: ApiKeyConfig) -> Result<(), String> {
+    complex
+        .validate()
+        .map_err(|e| format!("Invalid complex API key config: {}", e))?;
+    signed
+        .validate()
+        .map_err(|e| format!("Invalid signed API key config: {}", e))?;
+
+    let nonce_store: Arc<dyn NonceStore> = match GLOBAL_PRIMARY_REDIS.read().await.clone() {
+        Some(connection) => Arc::new(RedisNonceStore::new(connection)),
+        None => Arc::new(MemoryNonceStore::new()),
+    };
+    *API_KEY_VALIDATORS.complex.write().await =
+        ComplexApiKeyValidator::with_nonce_store(Some(complex), nonce_store.clone());
+    *API_KEY_VALIDATORS.signed.write().await =
+        ComplexApiKeyValidator::with_nonce_store(Some(signed), nonce_store);
+    Ok(())
 }
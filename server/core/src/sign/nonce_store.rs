@@ -0,0 +1,254 @@
+use axum::async_trait;
+use moka::{sync::Cache, Expiry};
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+use server_global::global::RedisConnection;
+use std::time::{Duration, Instant};
+
+/// Key prefix for nonces stored in Redis.
+const NONCE_KEY_PREFIX: &str = "soybean:sign:nonce:";
+
+/// Store for request nonces used to reject replayed signed requests.
+///
+/// Implementations must perform the check and the insert as a single atomic
+/// step, so that two concurrent requests carrying the same nonce cannot both
+/// be accepted.
+#[async_trait]
+pub trait NonceStore: Send + Sync {
+    /// Records a nonce if it has not been seen before.
+    ///
+    /// # Arguments
+    /// * `nonce` - The nonce string to validate and store
+    /// * `ttl` - How long the nonce is remembered
+    ///
+    /// # Returns
+    /// * `true` if the nonce is valid and not previously used
+    /// * `false` if the nonce has been used before or could not be recorded
+    async fn check_and_set(&self, nonce: &str, ttl: Duration) -> bool;
+}
+
+/// Expires each nonce after the TTL it was stored with.
+struct NonceExpiry;
+
+impl Expiry<String, Duration> for NonceExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        value: &Duration,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(*value)
+    }
+}
+
+/// In-memory store for managing nonces with automatic expiration.
+///
+/// Uses moka cache to store nonces with a per-entry TTL. Once a nonce
+/// expires, it can be reused. Nonces are only visible to the current process,
+/// so this store is suitable for single-instance deployments.
+#[derive(Clone)]
+pub struct MemoryNonceStore {
+    nonces: Cache<String, Duration>,
+}
+
+impl Default for MemoryNonceStore {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryNonceStore {
+    /// Creates a new, empty MemoryNonceStore.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            nonces: Cache::builder().expire_after(NonceExpiry).build(),
+        }
+    }
+}
+
+#[async_trait]
+impl NonceStore for MemoryNonceStore {
+    async fn check_and_set(&self, nonce: &str, ttl: Duration) -> bool {
+        self.nonces
+            .entry(nonce.to_string())
+            .or_insert(ttl)
+            .is_fresh()
+    }
+}
+
+/// Redis-backed nonce store shared by all server instances.
+///
+/// Each nonce is written with `SET NX EX`, so a nonce accepted by one
+/// instance is rejected by every other instance until it expires.
+#[derive(Clone)]
+pub struct RedisNonceStore {
+    connection: RedisConnection,
+}
+
+impl RedisNonceStore {
+    /// Creates a new RedisNonceStore on top of the given connection.
+    ///
+    /// # Arguments
+    /// * `connection` - A single-node or cluster Redis connection
+    #[inline]
+    pub fn new(connection: RedisConnection) -> Self {
+        Self { connection }
+    }
+
+    async fn set_nx_ex(&self, key: String, seconds: u64) -> redis::RedisResult<bool> {
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(seconds));
+
+        // SET NX replies with nil when the key already exists
+        let reply: Option<String> = match &self.connection {
+            RedisConnection::Single(client) => {
+                let mut conn = client.get_multiplexed_async_connection().await?;
+                conn.set_options(key, 1, options).await?
+            },
+            RedisConnection::Cluster(client) => {
+                let mut conn = client.get_async_connection().await?;
+                conn.set_options(key, 1, options).await?
+            },
+        };
+
+        Ok(reply.is_some())
+    }
+}
+
+#[async_trait]
+impl NonceStore for RedisNonceStore {
+    async fn check_and_set(&self, nonce: &str, ttl: Duration) -> bool {
+        let key = format!("{}{}", NONCE_KEY_PREFIX, nonce);
+        // Redis expiry is in whole seconds, round up so nonces never expire early
+        let seconds = (ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0)).max(1);
+
+        match self.set_nx_ex(key, seconds).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                // Fail closed: an unreachable store must not let replays through
+                tracing::error!("Failed to record nonce in Redis: {}", e);
+                false
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::{collections::HashSet, sync::Arc};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        net::{TcpListener, TcpStream},
+    };
+
+    type Commands = Arc<Mutex<Vec<Vec<String>>>>;
+
+    /// Reads one RESP array command, `None` once the client disconnects.
+    async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.ok()? == 0 {
+            return None;
+        }
+        let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).await.ok()?;
+            let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+            let mut arg = vec![0; len + 2];
+            reader.read_exact(&mut arg).await.ok()?;
+            arg.truncate(len);
+            args.push(String::from_utf8(arg).ok()?);
+        }
+        Some(args)
+    }
+
+    /// Starts a minimal Redis stand-in that implements `SET ... NX` and
+    /// records every `SET` it receives.
+    async fn fake_redis() -> (String, Commands) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let commands: Commands = Arc::default();
+        let keys = Arc::new(Mutex::new(HashSet::new()));
+
+        let recorded = commands.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (commands, keys) = (recorded.clone(), keys.clone());
+                tokio::spawn(async move {
+                    let mut reader = BufReader::new(stream);
+                    while let Some(args) = read_command(&mut reader).await {
+                        let reply: &[u8] = if args[0].eq_ignore_ascii_case("SET") {
+                            let nx = args.iter().any(|arg| arg.eq_ignore_ascii_case("NX"));
+                            let inserted = keys.lock().insert(args[1].clone());
+                            commands.lock().push(args);
+                            if nx && !inserted {
+                                b"$-1\r\n"
+                            } else {
+                                b"+OK\r\n"
+                            }
+                        } else {
+                            b"+OK\r\n"
+                        };
+                        if reader.get_mut().write_all(reply).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        (url, commands)
+    }
+
+    fn redis_store(url: &str) -> RedisNonceStore {
+        RedisNonceStore::new(RedisConnection::Single(Arc::new(
+            redis::Client::open(url).unwrap(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_redis_nonce_store() {
+        let (url, commands) = fake_redis().await;
+        let store = redis_store(&url);
+        let ttl = Duration::from_millis(1500);
+
+        assert!(store.check_and_set("nonce1", ttl).await);
+        assert!(!store.check_and_set("nonce1", ttl).await);
+        assert!(store.check_and_set("nonce2", ttl).await);
+
+        let commands = commands.lock();
+        assert_eq!(commands.len(), 3);
+        let set = &commands[0];
+        assert_eq!(set[..3], ["SET", "soybean:sign:nonce:nonce1", "1"]);
+        assert!(set.iter().any(|arg| arg == "NX"));
+        // the TTL is rounded up to whole seconds
+        let ex = set.iter().position(|arg| arg == "EX").unwrap();
+        assert_eq!(set[ex + 1], "2");
+    }
+
+    #[tokio::test]
+    async fn test_redis_nonce_store_fails_closed() {
+        // Reserve a port and release it so that nothing is listening on it
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let store = redis_store(&url);
+        assert!(!store.check_and_set("nonce1", Duration::from_secs(1)).await);
+    }
+
+    #[tokio::test]
+    async fn test_memory_nonce_store() {
+        let store = MemoryNonceStore::new();
+        let ttl = Duration::from_secs(1);
+        assert!(store.check_and_set("nonce1", ttl).await);
+        assert!(!store.check_and_set("nonce1", ttl).await);
+        tokio::time::sleep(ttl + Duration::from_millis(100)).await;
+        assert!(store.check_and_set("nonce1", ttl).await);
+    }
+}
@@ -0,0 +1,204 @@
+//! Matching of requests against the routes protected by an API key layer.
+//!
+//! Patterns use axum-style syntax: `:name` matches exactly one path segment
+//! and a trailing `*` or `*name` matches the rest of the path, including
+//! nothing at all. Empty segments and trailing slashes are ignored on both
+//! sides, so `/orders/` and `/orders` are the same route.
+use http::Method;
+use server_config::ProtectedRouteConfig;
+
+/// A single segment of a route pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// Matches the segment literally.
+    Literal(String),
+    /// `:name`, matches any single segment.
+    Param,
+    /// `*` or `*name`, matches all remaining segments.
+    Wildcard,
+}
+
+/// A protected route pattern with optional method filter.
+#[derive(Debug, Clone)]
+struct ProtectedRoute {
+    segments: Vec<Segment>,
+    /// Methods the route applies to, empty for all methods.
+    methods: Vec<Method>,
+}
+
+impl ProtectedRoute {
+    fn parse(pattern: &str, methods: Vec<Method>) -> Result<Self, String> {
+        let raw: Vec<&str> = path_segments(pattern).collect();
+        let mut segments = Vec::with_capacity(raw.len());
+
+        for (i, segment) in raw.iter().enumerate() {
+            let segment = if segment.starts_with('*') {
+                if i + 1 != raw.len() {
+                    return Err(format!(
+                        "Wildcard must be the last segment in route pattern '{}'",
+                        pattern
+                    ));
+                }
+                Segment::Wildcard
+            } else if segment.len() > 1 && segment.starts_with(':') {
+                Segment::Param
+            } else {
+                Segment::Literal(segment.to_string())
+            };
+            segments.push(segment);
+        }
+
+        Ok(Self { segments, methods })
+    }
+
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        if !self.methods.is_empty() && !self.methods.contains(method) {
+            return false;
+        }
+
+        let mut path = path_segments(path);
+        for segment in &self.segments {
+            match segment {
+                Segment::Wildcard => return true,
+                Segment::Param => {
+                    if path.next().is_none() {
+                        return false;
+                    }
+                },
+                Segment::Literal(literal) => {
+                    if path.next() != Some(literal.as_str()) {
+                        return false;
+                    }
+                },
+            }
+        }
+
+        path.next().is_none()
+    }
+}
+
+/// Splits a path into its non-empty segments.
+#[inline]
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+/// Set of routes that require API key validation.
+///
+/// Each [`ApiKeyValidation`](super::ApiKeyValidation) carries its own matcher,
+/// so routers using different validators can protect overlapping paths
+/// independently. An empty matcher protects nothing.
+#[derive(Debug, Clone, Default)]
+pub struct RouteMatcher {
+    routes: Vec<ProtectedRoute>,
+}
+
+impl RouteMatcher {
+    /// Creates an empty RouteMatcher.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a matcher from configured routes.
+    ///
+    /// # Arguments
+    /// * `routes` - Route patterns and their HTTP method filters
+    ///
+    /// # Returns
+    /// The matcher, or an error message if a pattern or method is invalid
+    pub fn from_config(routes: &[ProtectedRouteConfig]) -> Result<Self, String> {
+        routes.iter().try_fold(Self::new(), |matcher, route| {
+            let methods = route
+                .methods
+                .iter()
+                .map(|method| {
+                    Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                        .map_err(|_| format!("Invalid HTTP method '{}'", method))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            matcher.route_with_methods(&route.path, methods)
+        })
+    }
+
+    /// Protects a route pattern for all HTTP methods.
+    ///
+    /// # Arguments
+    /// * `pattern` - Route pattern, e.g. `/open/orders/:id`
+    pub fn route(self, pattern: &str) -> Result<Self, String> {
+        self.route_with_methods(pattern, Vec::new())
+    }
+
+    /// Protects a route pattern for the given HTTP methods only.
+    ///
+    /// # Arguments
+    /// * `pattern` - Route pattern, e.g. `/open/orders/:id`
+    /// * `methods` - Methods to protect, empty for all methods
+    pub fn route_with_methods(
+        mut self,
+        pattern: &str,
+        methods: Vec<Method>,
+    ) -> Result<Self, String> {
+        self.routes.push(ProtectedRoute::parse(pattern, methods)?);
+        Ok(self)
+    }
+
+    /// Checks whether a request requires API key validation.
+    ///
+    /// # Arguments
+    /// * `method` - HTTP method of the request
+    /// * `path` - Request path
+    pub fn matches(&self, method: &Method, path: &str) -> bool {
+        self.routes.iter().any(|route| route.matches(method, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_patterns() {
+        let matcher = RouteMatcher::new()
+            .route("/sandbox/simple-api-key")
+            .unwrap()
+            .route("/open/orders/:id")
+            .unwrap()
+            .route("/files/*path")
+            .unwrap();
+
+        assert!(matcher.matches(&Method::GET, "/sandbox/simple-api-key"));
+        assert!(matcher.matches(&Method::GET, "/sandbox/simple-api-key/"));
+        assert!(!matcher.matches(&Method::GET, "/sandbox/simple-api-key/x"));
+
+        assert!(matcher.matches(&Method::GET, "/open/orders/42"));
+        assert!(!matcher.matches(&Method::GET, "/open/orders"));
+        assert!(!matcher.matches(&Method::GET, "/open/orders/42/items"));
+
+        assert!(matcher.matches(&Method::GET, "/files"));
+        assert!(matcher.matches(&Method::GET, "/files/a/b.txt"));
+        assert!(!matcher.matches(&Method::GET, "/filesystem"));
+
+        assert!(RouteMatcher::new().route("/files/*path/more").is_err());
+        assert!(!RouteMatcher::new().matches(&Method::GET, "/"));
+    }
+
+    #[test]
+    fn test_method_filters() {
+        let matcher = RouteMatcher::from_config(&[ProtectedRouteConfig {
+            path: "/open/orders/:id".to_string(),
+            methods: vec!["put".to_string(), "DELETE".to_string()],
+        }])
+        .unwrap();
+
+        assert!(matcher.matches(&Method::PUT, "/open/orders/42"));
+        assert!(matcher.matches(&Method::DELETE, "/open/orders/42"));
+        assert!(!matcher.matches(&Method::GET, "/open/orders/42"));
+
+        assert!(RouteMatcher::from_config(&[ProtectedRouteConfig {
+            path: "/orders".to_string(),
+            methods: vec!["NOT A METHOD".to_string()],
+        }])
+        .is_err());
+    }
+}
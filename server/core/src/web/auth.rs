@@ -7,6 +7,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::web::res::Res;
 
+/// 代表用户操作的实际执行者（RFC 8693 `act` 声明），用于管理员模拟登录
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Actor {
+    pub sub: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     sub: String,
@@ -22,6 +28,9 @@ pub struct Claims {
     role: Vec<String>,
     domain: String,
     org: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    act: Option<Actor>,
 }
 
 impl Claims {
@@ -45,6 +54,7 @@ impl Claims {
             role,
             domain,
             org,
+            act: None,
         }
     }
 
@@ -67,6 +77,22 @@ impl Claims {
     pub fn set_jti(&mut self, jti: String) {
         self.jti = Some(jti);
     }
+
+    pub fn jti(&self) -> Option<&str> {
+        self.jti.as_deref()
+    }
+
+    pub fn aud(&self) -> &str {
+        &self.aud
+    }
+
+    pub fn set_act(&mut self, act: Actor) {
+        self.act = Some(act);
+    }
+
+    pub fn act(&self) -> Option<&Actor> {
+        self.act.as_ref()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -76,6 +102,8 @@ pub struct User {
     role: Vec<String>,
     domain: String,
     org: Option<String>,
+    jti: Option<String>,
+    act: Option<Actor>,
 }
 
 impl User {
@@ -94,6 +122,19 @@ impl User {
     pub fn domain(&self) -> String {
         self.domain.to_string()
     }
+
+    pub fn jti(&self) -> Option<String> {
+        self.jti.clone()
+    }
+
+    pub fn organization(&self) -> Option<String> {
+        self.org.clone()
+    }
+
+    /// 模拟登录时发起模拟的管理员 ID
+    pub fn actor_id(&self) -> Option<String> {
+        self.act.as_ref().map(|act| act.sub.clone())
+    }
 }
 
 impl From<Claims> for User {
@@ -104,6 +145,8 @@ impl From<Claims> for User {
             role: claims.role,
             domain: claims.domain,
             org: claims.org,
+            jti: claims.jti,
+            act: claims.act,
         }
     }
 }
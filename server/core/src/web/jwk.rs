@@ -0,0 +1,168 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+        EllipticCurveKeyType, Jwk, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType,
+        PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm,
+};
+use simple_asn1::{from_der, ASN1Block, OID};
+
+use crate::web::jwt::JwtError;
+
+const RSA_ENCRYPTION_OID: &[u64] = &[1, 2, 840, 113549, 1, 1, 1];
+const EC_PUBLIC_KEY_OID: &[u64] = &[1, 2, 840, 10045, 2, 1];
+const PRIME256V1_OID: &[u64] = &[1, 2, 840, 10045, 3, 1, 7];
+const ED25519_OID: &[u64] = &[1, 3, 101, 112];
+
+/// 将 PEM 格式（SubjectPublicKeyInfo）的公钥转换为 JWK
+pub fn public_key_to_jwk(kid: &str, algorithm: Algorithm, pem: &[u8]) -> Result<Jwk, JwtError> {
+    let pem = pem::parse(pem).map_err(|e| JwtError::InvalidKey(e.to_string()))?;
+    let (oids, key) = parse_subject_public_key_info(pem.contents())?;
+
+    let (key_algorithm, parameters) = match algorithm {
+        Algorithm::RS256 if oids.first().map(Vec::as_slice) == Some(RSA_ENCRYPTION_OID) => {
+            let (n, e) = parse_rsa_public_key(&key)?;
+            (
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(n),
+                    e: URL_SAFE_NO_PAD.encode(e),
+                }),
+            )
+        },
+        Algorithm::ES256
+            if oids.first().map(Vec::as_slice) == Some(EC_PUBLIC_KEY_OID)
+                && oids.get(1).map(Vec::as_slice) == Some(PRIME256V1_OID) =>
+        {
+            // 未压缩的椭圆曲线点：0x04 || X || Y
+            if key.len() != 65 || key[0] != 0x04 {
+                return Err(JwtError::InvalidKey(
+                    "Unsupported P-256 public key encoding".to_string(),
+                ));
+            }
+            (
+                KeyAlgorithm::ES256,
+                AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                    key_type: EllipticCurveKeyType::EC,
+                    curve: EllipticCurve::P256,
+                    x: URL_SAFE_NO_PAD.encode(&key[1..33]),
+                    y: URL_SAFE_NO_PAD.encode(&key[33..]),
+                }),
+            )
+        },
+        Algorithm::EdDSA if oids.first().map(Vec::as_slice) == Some(ED25519_OID) => (
+            KeyAlgorithm::EdDSA,
+            AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(&key),
+            }),
+        ),
+        _ => {
+            return Err(JwtError::InvalidKey(format!(
+                "Public key does not match algorithm {:?}",
+                algorithm
+            )))
+        },
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
+
+/// 解析 SubjectPublicKeyInfo，返回算法标识中的 OID 列表和公钥内容
+fn parse_subject_public_key_info(der: &[u8]) -> Result<(Vec<Vec<u64>>, Vec<u8>), JwtError> {
+    let invalid = || JwtError::InvalidKey("Invalid SubjectPublicKeyInfo".to_string());
+
+    let blocks = from_der(der).map_err(|e| JwtError::InvalidKey(e.to_string()))?;
+    let Some(ASN1Block::Sequence(_, fields)) = blocks.first() else {
+        return Err(invalid());
+    };
+    let [ASN1Block::Sequence(_, algorithm), ASN1Block::BitString(_, _, key)] = fields.as_slice()
+    else {
+        return Err(invalid());
+    };
+
+    let oids = algorithm
+        .iter()
+        .filter_map(|block| match block {
+            ASN1Block::ObjectIdentifier(_, oid) => oid_to_vec(oid),
+            _ => None,
+        })
+        .collect();
+
+    Ok((oids, key.clone()))
+}
+
+/// 解析 PKCS#1 RSAPublicKey，返回大端序的模数与指数
+fn parse_rsa_public_key(der: &[u8]) -> Result<(Vec<u8>, Vec<u8>), JwtError> {
+    let blocks = from_der(der).map_err(|e| JwtError::InvalidKey(e.to_string()))?;
+    match blocks.first() {
+        Some(ASN1Block::Sequence(_, fields)) => match fields.as_slice() {
+            [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] => {
+                Ok((n.to_bytes_be().1, e.to_bytes_be().1))
+            },
+            _ => Err(JwtError::InvalidKey("Invalid RSA public key".to_string())),
+        },
+        _ => Err(JwtError::InvalidKey("Invalid RSA public key".to_string())),
+    }
+}
+
+fn oid_to_vec(oid: &OID) -> Option<Vec<u64>> {
+    oid.as_vec::<u64>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::DecodingKey;
+
+    use super::*;
+
+    const ED25519_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VwAyEAoNxkyz1omtXVCYCRI43Uks1T8OZbhDyj9Mq3WjZU1Gg=
+-----END PUBLIC KEY-----";
+
+    const P256_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE/G51m49If/B2zuE7hjA/73QdasAq
+0f8NpdXSs/9wDC2Bzzbg3CPZJRBEsQJixw5ZR+EWopuY+syDaKQTjsLiHA==
+-----END PUBLIC KEY-----";
+
+    #[test]
+    fn test_ed25519_public_key_to_jwk() {
+        let jwk = public_key_to_jwk("ed", Algorithm::EdDSA, ED25519_PUBLIC_KEY.as_bytes()).unwrap();
+        assert_eq!(jwk.common.key_id.as_deref(), Some("ed"));
+        assert!(matches!(
+            jwk.algorithm,
+            AlgorithmParameters::OctetKeyPair(_)
+        ));
+        assert!(DecodingKey::from_jwk(&jwk).is_ok());
+    }
+
+    #[test]
+    fn test_p256_public_key_to_jwk() {
+        let jwk = public_key_to_jwk("ec", Algorithm::ES256, P256_PUBLIC_KEY.as_bytes()).unwrap();
+        match &jwk.algorithm {
+            AlgorithmParameters::EllipticCurve(params) => {
+                assert_eq!(params.curve, EllipticCurve::P256);
+                assert_eq!(params.x.len(), 43);
+                assert_eq!(params.y.len(), 43);
+            },
+            _ => panic!("expected EC parameters"),
+        }
+    }
+
+    #[test]
+    fn test_public_key_algorithm_mismatch() {
+        assert!(public_key_to_jwk("ed", Algorithm::RS256, ED25519_PUBLIC_KEY.as_bytes()).is_err());
+    }
+}
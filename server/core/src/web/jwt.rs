@@ -1,7 +1,7 @@
 use std::{error::Error, fmt};
 
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, Header, TokenData};
+use jsonwebtoken::{decode, decode_header, encode, jwk::JwkSet, Algorithm, Header, TokenData};
 use server_config::JwtConfig;
 use server_global::global;
 use ulid::Ulid;
@@ -29,6 +29,7 @@ pub enum JwtError {
     ValidationNotInitialized,
     TokenCreationError(String),
     TokenValidationError(String),
+    InvalidKey(String),
 }
 
 impl fmt::Display for JwtError {
@@ -38,6 +39,7 @@ impl fmt::Display for JwtError {
             JwtError::ValidationNotInitialized => write!(f, "Validation not initialized"),
             JwtError::TokenCreationError(err) => write!(f, "Token creation error: {}", err),
             JwtError::TokenValidationError(err) => write!(f, "Token validation error: {}", err),
+            JwtError::InvalidKey(err) => write!(f, "Invalid key: {}", err),
         }
     }
 }
@@ -57,13 +59,20 @@ impl JwtUtils {
         let now = Utc::now();
         let timestamp = now.timestamp() as usize;
         let jwt_config = global::get_config::<JwtConfig>().await.unwrap();
-        claims_clone.set_exp((now + Duration::seconds(jwt_config.expire)).timestamp() as usize);
+        let expire = jwt_config.expire_for(claims.aud());
+        claims_clone.set_exp((now + Duration::seconds(expire)).timestamp() as usize);
         claims_clone.set_iss(jwt_config.issuer.to_string());
         claims_clone.set_iat(timestamp);
         claims_clone.set_nbf(timestamp);
-        claims_clone.set_jti(Ulid::new().to_string());
+        // 调用方可预先指定 jti（如需与 sys_tokens 记录关联），否则自动生成
+        if claims_clone.jti().is_none() {
+            claims_clone.set_jti(Ulid::new().to_string());
+        }
+
+        let mut header = Header::new(keys.algorithm);
+        header.kid = keys.kid.clone();
 
-        let token = encode(&Header::default(), &claims_clone, &keys.encoding)
+        let token = encode(&header, &claims_clone, &keys.encoding)
             .map_err(|e| JwtError::TokenCreationError(e.to_string()));
 
         if let Ok(ref tok) = token {
@@ -87,7 +96,34 @@ impl JwtUtils {
 
         let mut validation_clone = validation.clone();
         validation_clone.set_audience(&[audience.to_string()]);
-        decode::<Claims>(token, &keys.decoding, &validation_clone)
+
+        // 按头部的 kid 选择验证密钥，并只接受该密钥对应的算法
+        let header =
+            decode_header(token).map_err(|e| JwtError::TokenValidationError(e.to_string()))?;
+        let decoding = match header.kid {
+            Some(kid) => {
+                let key = keys.verification_keys.get(&kid).ok_or_else(|| {
+                    JwtError::TokenValidationError(format!("Unknown key id: {}", kid))
+                })?;
+                validation_clone.algorithms = vec![key.algorithm];
+                &key.decoding
+            },
+            None => {
+                validation_clone.algorithms = vec![Algorithm::HS256];
+                keys.decoding
+                    .as_ref()
+                    .ok_or_else(|| JwtError::TokenValidationError("Missing key id".to_string()))?
+            },
+        };
+
+        decode::<Claims>(token, decoding, &validation_clone)
             .map_err(|e| JwtError::TokenValidationError(e.to_string()))
     }
+
+    /// 对外公布的验证公钥集合
+    pub async fn jwks() -> Result<JwkSet, JwtError> {
+        let keys_arc = global::KEYS.get().ok_or(JwtError::KeysNotInitialized)?;
+        let keys = keys_arc.lock().await;
+        Ok(keys.jwks.clone())
+    }
 }
@@ -1,8 +1,10 @@
 pub mod auth;
 pub mod error;
+pub mod jwk;
 pub mod jwt;
 pub mod page;
 pub mod res;
+pub mod token_revocation;
 pub mod util;
 pub mod validator;
 
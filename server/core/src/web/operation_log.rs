@@ -25,15 +25,34 @@ use super::{auth::User, RequestId};
 const USER_AGENT_HEADER: &str = "user-agent";
 const UNKNOWN_REQUEST_ID: &str = "unknown";
 const DEFAULT_BODY_CAPACITY: usize = 1024 * 16; // 16KB 默认缓冲区大小
+const REDACTED_VALUE: &str = "******";
+
+type UserInfo = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
 
 #[derive(Clone)]
 pub struct OperationLogLayer {
     pub enabled: bool,
+    /// 记录日志前从请求体和响应体中隐去的字段，例如密钥
+    pub redacted_fields: &'static [&'static str],
 }
 
 impl OperationLogLayer {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            redacted_fields: &[],
+        }
+    }
+
+    /// 设置需要隐去的字段名，在任意层级的 JSON 对象中匹配
+    pub fn with_redacted_fields(mut self, fields: &'static [&'static str]) -> Self {
+        self.redacted_fields = fields;
+        self
     }
 }
 
@@ -50,6 +69,7 @@ where
         OperationLogMiddleware {
             inner: service,
             enabled: self.enabled,
+            redacted_fields: self.redacted_fields,
         }
     }
 }
@@ -58,6 +78,7 @@ where
 pub struct OperationLogMiddleware<S> {
     inner: S,
     enabled: bool,
+    redacted_fields: &'static [&'static str],
 }
 
 impl<S> Service<Request<Body>> for OperationLogMiddleware<S>
@@ -83,13 +104,14 @@ where
         }
 
         let mut inner = self.inner.clone();
+        let redacted_fields = self.redacted_fields;
         Box::pin(async move {
             let start_time = Local::now().naive_local();
             let (parts, body) = req.into_parts();
             let headers = &parts.headers;
             let extensions = &parts.extensions;
 
-            let (user_id, username, domain) = get_user_info(extensions);
+            let (user_id, username, domain, actor_id) = get_user_info(extensions);
 
             let request_id = extensions
                 .get::<RequestId>()
@@ -118,6 +140,7 @@ where
                     user_id,
                     username,
                     domain,
+                    actor_id,
                     module_name: "TODO".to_string(),
                     description: "TODO".to_string(),
                     request_id,
@@ -128,8 +151,11 @@ where
                     params,
                     body: (!bytes.is_empty())
                         .then(|| serde_json::from_slice(&bytes).ok())
-                        .flatten(),
-                    response: serde_json::from_slice(&response_bytes).ok(),
+                        .flatten()
+                        .map(|value| redact_fields(value, redacted_fields)),
+                    response: serde_json::from_slice(&response_bytes)
+                        .ok()
+                        .map(|value| redact_fields(value, redacted_fields)),
                     start_time,
                     end_time,
                     duration,
@@ -224,10 +250,9 @@ fn get_client_ip(extensions: &Extensions, headers: &HeaderMap) -> String {
 /// * `extensions` - 请求扩展
 ///
 /// # 返回值
-/// * `(Option<String>, Option<String>, Option<String>)` - (用户ID, 用户名,
-///   域名) 的元组
+/// * `UserInfo` - (用户ID, 用户名, 域名, 模拟登录的管理员ID) 的元组
 #[inline(always)]
-fn get_user_info(extensions: &Extensions) -> (Option<String>, Option<String>, Option<String>) {
+fn get_user_info(extensions: &Extensions) -> UserInfo {
     let Some(user) = extensions.get::<User>() else {
         return Default::default();
     };
@@ -236,9 +261,43 @@ fn get_user_info(extensions: &Extensions) -> (Option<String>, Option<String>, Op
         Some(user.user_id()),
         Some(user.username()),
         Some(user.domain()),
+        user.actor_id(),
     )
 }
 
+/// 隐去 JSON 值中指定名称的字段
+///
+/// # 参数
+/// * `value` - 请求体或响应体解析出的 JSON 值
+/// * `fields` - 需要隐去的字段名
+///
+/// # 返回值
+/// * `Value` - 匹配字段的值被替换为占位符后的 JSON 值
+fn redact_fields(mut value: Value, fields: &[&str]) -> Value {
+    fn redact(value: &mut Value, fields: &[&str]) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if fields.contains(&key.as_str()) {
+                        if !value.is_null() {
+                            *value = Value::String(REDACTED_VALUE.to_string());
+                        }
+                    } else {
+                        redact(value, fields);
+                    }
+                }
+            },
+            Value::Array(items) => items.iter_mut().for_each(|item| redact(item, fields)),
+            _ => {},
+        }
+    }
+
+    if !fields.is_empty() {
+        redact(&mut value, fields);
+    }
+    value
+}
+
 /// 解析 URI 查询参数为 JSON 值
 ///
 /// # 参数
@@ -329,6 +388,7 @@ mod tests {
         assert_eq!(ctx.user_agent, Some("test-agent".to_string()));
         assert_eq!(ctx.ip, "192.168.1.1");
         assert_eq!(ctx.user_id, Some("test-user".to_string()));
+        assert_eq!(ctx.actor_id, None);
     }
 
     #[tokio::test]
@@ -395,6 +455,7 @@ mod tests {
             let mut middleware = OperationLogMiddleware {
                 inner: service.clone(),
                 enabled: true,
+                redacted_fields: &[],
             };
 
             let request = create_request(method.clone(), uri, body.clone());
@@ -442,6 +503,7 @@ mod tests {
             let mut middleware = OperationLogMiddleware {
                 inner: service.clone(),
                 enabled: true,
+                redacted_fields: &[],
             };
 
             let mut request = create_request(method, uri, body);
@@ -470,6 +532,7 @@ mod tests {
         let mut middleware = OperationLogMiddleware {
             inner: service,
             enabled: false,
+            redacted_fields: &[],
         };
 
         let request = create_request(Method::POST, "/test", Some(json!({"test": true})));
@@ -478,4 +541,32 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
         assert!(OperationLogContext::get().await.is_none());
     }
+
+    #[test]
+    fn test_redact_fields() {
+        let value = json!({
+            "code": 200,
+            "data": {
+                "access_key_id": "AK1",
+                "access_key_secret": "SK1",
+                "previous_access_key_secret": null,
+                "items": [{ "access_key_secret": "SK2" }]
+            }
+        });
+
+        let redacted = redact_fields(value, &["access_key_secret", "previous_access_key_secret"]);
+
+        assert_eq!(
+            redacted,
+            json!({
+                "code": 200,
+                "data": {
+                    "access_key_id": "AK1",
+                    "access_key_secret": "******",
+                    "previous_access_key_secret": null,
+                    "items": [{ "access_key_secret": "******" }]
+                }
+            })
+        );
+    }
 }
@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+use moka::{sync::Cache, Expiry};
+use once_cell::sync::Lazy;
+use redis::AsyncCommands;
+use server_global::global::{RedisConnection, GLOBAL_PRIMARY_REDIS};
+
+use crate::web::error::AppError;
+
+/// 已吊销令牌在 Redis 中的键前缀
+const REVOKED_JTI_KEY_PREFIX: &str = "soybean:auth:revoked_jti:";
+
+/// 按条目设置过期时间：每个 jti 只需保留到其对应令牌自然过期为止
+struct RevokedTokenExpiry;
+
+impl Expiry<String, Duration> for RevokedTokenExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        value: &Duration,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(*value)
+    }
+}
+
+/// 未配置主 Redis 时使用的进程内吊销列表
+static MEMORY_REVOKED_TOKENS: Lazy<Cache<String, Duration>> =
+    Lazy::new(|| Cache::builder().expire_after(RevokedTokenExpiry).build());
+
+/// JWT 吊销列表
+///
+/// 以 `jti` 为键记录已吊销的访问令牌，配置了主 Redis 时写入 Redis，
+/// 否则写入进程内的 moka 缓存。条目在令牌自然过期后自动清除。
+pub struct TokenRevocation;
+
+impl TokenRevocation {
+    /// 将 `jti` 加入吊销列表，`ttl` 为令牌剩余的有效时间
+    pub async fn revoke(jti: &str, ttl: Duration) -> Result<(), AppError> {
+        if ttl.is_zero() {
+            return Ok(());
+        }
+
+        let key = format!("{}{}", REVOKED_JTI_KEY_PREFIX, jti);
+        // Redis 的过期时间以秒为单位，向上取整避免提前失效
+        let seconds = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+
+        match GLOBAL_PRIMARY_REDIS.read().await.clone() {
+            Some(RedisConnection::Single(client)) => {
+                let mut conn = client.get_multiplexed_async_connection().await?;
+                conn.set_ex::<_, _, ()>(key, 1, seconds).await?;
+            },
+            Some(RedisConnection::Cluster(client)) => {
+                let mut conn = client.get_async_connection().await?;
+                conn.set_ex::<_, _, ()>(key, 1, seconds).await?;
+            },
+            None => MEMORY_REVOKED_TOKENS.insert(jti.to_string(), ttl),
+        }
+
+        Ok(())
+    }
+
+    /// 判断 `jti` 是否已被吊销
+    pub async fn is_revoked(jti: &str) -> Result<bool, AppError> {
+        let key = format!("{}{}", REVOKED_JTI_KEY_PREFIX, jti);
+
+        let revoked = match GLOBAL_PRIMARY_REDIS.read().await.clone() {
+            Some(RedisConnection::Single(client)) => {
+                let mut conn = client.get_multiplexed_async_connection().await?;
+                conn.exists(key).await?
+            },
+            Some(RedisConnection::Cluster(client)) => {
+                let mut conn = client.get_async_connection().await?;
+                conn.exists(key).await?
+            },
+            None => MEMORY_REVOKED_TOKENS.contains_key(jti),
+        };
+
+        Ok(revoked)
+    }
+}
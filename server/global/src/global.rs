@@ -8,7 +8,7 @@ use std::{
 
 use chrono::NaiveDateTime;
 use http::Method;
-use jsonwebtoken::{DecodingKey, EncodingKey, Validation};
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, EncodingKey, Validation};
 use mongodb::Client as MongoClient;
 use once_cell::sync::Lazy;
 use redis::{cluster::ClusterClient, Client};
@@ -70,16 +70,34 @@ pub static GLOBAL_MONGO_POOL: Lazy<RwLock<HashMap<String, Arc<MongoClient>>>> =
 // JWT 密钥和验证
 //*****************************************************************************
 
+/// 按 kid 查找的验证密钥
+pub struct VerificationKey {
+    pub algorithm: Algorithm,
+    pub decoding: DecodingKey,
+}
+
 pub struct Keys {
+    /// 签发令牌使用的算法
+    pub algorithm: Algorithm,
+    /// 签发令牌时写入头部的 kid，使用共享密钥签发时为空
+    pub kid: Option<String>,
     pub encoding: EncodingKey,
-    pub decoding: DecodingKey,
+    /// 验证未携带 kid 的令牌使用的 HMAC 共享密钥
+    pub decoding: Option<DecodingKey>,
+    pub verification_keys: HashMap<String, VerificationKey>,
+    /// 对外公布的验证公钥
+    pub jwks: JwkSet,
 }
 
 impl Keys {
     pub fn new(secret: &[u8]) -> Self {
         Self {
+            algorithm: Algorithm::HS256,
+            kid: None,
             encoding: EncodingKey::from_secret(secret),
-            decoding: DecodingKey::from_secret(secret),
+            decoding: Some(DecodingKey::from_secret(secret)),
+            verification_keys: HashMap::new(),
+            jwks: JwkSet { keys: vec![] },
         }
     }
 }
@@ -211,6 +229,8 @@ pub struct OperationLogContext {
     pub user_id: Option<String>,
     pub username: Option<String>,
     pub domain: Option<String>,
+    /// 模拟登录时实际操作的管理员 ID
+    pub actor_id: Option<String>,
     pub module_name: String,
     pub description: String,
     pub request_id: String,
@@ -0,0 +1,36 @@
+use std::{process, sync::Arc};
+
+use axum_casbin::casbin::CachedEnforcer;
+use server_service::admin::{
+    access_key_sync_clients, access_key_sync_listener, SysAccessKeyService, TAccessKeyService,
+};
+use tokio::sync::RwLock;
+
+use crate::{project_error, project_info};
+
+/// 从数据库加载启用的访问密钥，并启动多实例同步的订阅任务
+///
+/// `enforcer` 为路由使用的 casbin enforcer，其他实例变更授权策略后由订阅任务重新加载
+///
+/// 需在主数据库、主 Redis 与签名验证器初始化之后调用。
+/// 配置了主 Redis 却无法建立订阅客户端时终止启动，避免各实例的密钥悄然不一致
+pub async fn initialize_access_keys(enforcer: Arc<RwLock<CachedEnforcer>>) {
+    match SysAccessKeyService.load_access_keys().await {
+        Ok(count) => project_info!("Loaded {} access keys", count),
+        Err(e) => project_error!("Failed to load access keys: {:?}", e),
+    }
+
+    let clients = match access_key_sync_clients().await {
+        Ok(clients) => clients,
+        Err(e) => {
+            project_error!("Failed to initialize access key sync: {:?}", e);
+            process::exit(1);
+        },
+    };
+    if clients.is_empty() {
+        return;
+    }
+
+    tokio::spawn(access_key_sync_listener(clients, enforcer));
+    project_info!("Access key sync task spawned");
+}
@@ -1,8 +1,10 @@
 use server_global::global;
 
 pub async fn initialize_event_channel() {
+    use server_core::sign::ACCESS_KEY_USAGE_EVENT;
     use server_service::admin::{
-        auth_login_listener, jwt_created_listener, sys_operation_log_listener,
+        access_key_usage_listener, auth_login_listener, jwt_created_listener,
+        sys_operation_log_listener,
     };
 
     global::register_event_listeners(
@@ -16,6 +18,10 @@ pub async fn initialize_event_channel() {
                 "sys_operation_log".to_string(),
                 Box::new(|rx| Box::pin(sys_operation_log_listener(rx))),
             ),
+            (
+                ACCESS_KEY_USAGE_EVENT.to_string(),
+                Box::new(|rx| Box::pin(access_key_usage_listener(rx))),
+            ),
         ],
     )
     .await;
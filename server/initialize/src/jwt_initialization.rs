@@ -1,7 +1,12 @@
-use std::sync::Arc;
+use std::{collections::HashMap, fs, sync::Arc};
 
-use server_config::JwtConfig;
-use server_global::{global, Validation};
+use jsonwebtoken::{crypto, jwk::JwkSet, Algorithm, DecodingKey, EncodingKey};
+use server_config::{JwtAlgorithm, JwtConfig, JwtKeyConfig};
+use server_core::web::jwk::public_key_to_jwk;
+use server_global::{
+    global::{self, Keys, VerificationKey},
+    Validation,
+};
 use tokio::sync::Mutex;
 
 use crate::{project_error, project_info};
@@ -15,7 +20,17 @@ pub async fn initialize_keys_and_validation() {
         },
     };
 
-    let keys = global::Keys::new(jwt_config.jwt_secret.as_bytes());
+    let keys = if jwt_config.keys.is_empty() {
+        Keys::new(jwt_config.jwt_secret.as_bytes())
+    } else {
+        match load_asymmetric_keys(&jwt_config) {
+            Ok(keys) => keys,
+            Err(e) => {
+                project_error!("Failed to load JWT keys: {}", e);
+                return;
+            },
+        }
+    };
     if global::KEYS.set(Arc::new(Mutex::new(keys))).is_err() {
         project_error!("Failed to set KEYS");
     }
@@ -32,3 +47,107 @@ pub async fn initialize_keys_and_validation() {
 
     project_info!("JWT keys and validation initialized");
 }
+
+/// 加载非对称密钥：全部公钥用于验证并公布到 JWKS，`signing_kid` 对应的私钥用于签发
+fn load_asymmetric_keys(config: &JwtConfig) -> Result<Keys, String> {
+    let signing_kid = config
+        .signing_kid
+        .clone()
+        .or_else(|| {
+            config
+                .keys
+                .iter()
+                .find(|key| key.private_key_path.is_some())
+                .map(|key| key.kid.clone())
+        })
+        .ok_or("No signing key configured")?;
+
+    let mut verification_keys = HashMap::new();
+    let mut jwks = JwkSet { keys: vec![] };
+    let mut signing = None;
+
+    for key_config in &config.keys {
+        let algorithm = to_algorithm(key_config.algorithm);
+        let public_key = fs::read(&key_config.public_key_path)
+            .map_err(|e| format!("{}: {}", key_config.public_key_path, e))?;
+        let jwk = public_key_to_jwk(&key_config.kid, algorithm, &public_key)
+            .map_err(|e| format!("{}: {}", key_config.kid, e))?;
+        let decoding = DecodingKey::from_jwk(&jwk).map_err(|e| e.to_string())?;
+
+        if key_config.kid == signing_kid {
+            let encoding = load_encoding_key(key_config, algorithm)?;
+            verify_key_pair(&encoding, &decoding, algorithm)
+                .map_err(|e| format!("{}: {}", key_config.kid, e))?;
+            signing = Some((algorithm, encoding));
+        }
+
+        if verification_keys
+            .insert(
+                key_config.kid.clone(),
+                VerificationKey {
+                    algorithm,
+                    decoding,
+                },
+            )
+            .is_some()
+        {
+            return Err(format!("Duplicate key id: {}", key_config.kid));
+        }
+        jwks.keys.push(jwk);
+    }
+
+    let (algorithm, encoding) =
+        signing.ok_or_else(|| format!("Signing key not found: {}", signing_kid))?;
+
+    Ok(Keys {
+        algorithm,
+        kid: Some(signing_kid),
+        encoding,
+        // 保留共享密钥用于验证切换前签发的、未携带 kid 的令牌
+        decoding: (!config.jwt_secret.is_empty())
+            .then(|| DecodingKey::from_secret(config.jwt_secret.as_bytes())),
+        verification_keys,
+        jwks,
+    })
+}
+
+fn load_encoding_key(
+    key_config: &JwtKeyConfig,
+    algorithm: Algorithm,
+) -> Result<EncodingKey, String> {
+    let path = key_config
+        .private_key_path
+        .as_ref()
+        .ok_or_else(|| format!("{}: private key is not configured", key_config.kid))?;
+    let pem = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+
+    match algorithm {
+        Algorithm::RS256 => EncodingKey::from_rsa_pem(&pem),
+        Algorithm::ES256 => EncodingKey::from_ec_pem(&pem),
+        _ => EncodingKey::from_ed_pem(&pem),
+    }
+    .map_err(|e| format!("{}: {}", path, e))
+}
+
+/// 用私钥签名并用公钥验证，确保密钥对匹配
+fn verify_key_pair(
+    encoding: &EncodingKey,
+    decoding: &DecodingKey,
+    algorithm: Algorithm,
+) -> Result<(), String> {
+    let message = b"soybean-admin-rust";
+    let signature = crypto::sign(message, encoding, algorithm).map_err(|e| e.to_string())?;
+    match crypto::verify(&signature, message, decoding, algorithm) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("private key does not match public key".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn to_algorithm(algorithm: JwtAlgorithm) -> Algorithm {
+    match algorithm {
+        JwtAlgorithm::Rs256 => Algorithm::RS256,
+        JwtAlgorithm::Es256 => Algorithm::ES256,
+        JwtAlgorithm::EdDsa => Algorithm::EdDSA,
+    }
+}
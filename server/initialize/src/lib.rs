@@ -1,3 +1,4 @@
+pub use access_key_initialization::initialize_access_keys;
 pub use casbin_initialization::initialize_casbin;
 pub use config_initialization::initialize_config;
 pub use db_initialization::{get_primary_db_connection, init_primary_connection};
@@ -10,7 +11,10 @@ pub use redis_initialization::{init_primary_redis, init_redis_pools};
 pub use router_initialization::initialize_admin_router;
 pub use server_global::{project_error, project_info};
 pub use server_initialization::get_server_address;
+pub use token_cleanup_initialization::initialize_token_cleanup;
+pub use token_revocation_initialization::initialize_token_revocation;
 
+mod access_key_initialization;
 mod casbin_initialization;
 mod config_initialization;
 mod db_initialization;
@@ -22,6 +26,8 @@ mod mongo_initialization;
 mod redis_initialization;
 mod router_initialization;
 mod server_initialization;
+mod token_cleanup_initialization;
+mod token_revocation_initialization;
 
 #[cfg(test)]
 mod tests {
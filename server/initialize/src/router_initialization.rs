@@ -4,32 +4,49 @@ use axum::{body::Body, http::StatusCode, response::IntoResponse, Extension, Rout
 use axum_casbin::CasbinAxumLayer;
 use chrono::Local;
 use http::Request;
-use server_config::Config;
+use server_config::{AccessKeyConfig, Config, ProtectedRouteConfig, ReplayProtectionConfig};
 use server_constant::definition::Audience;
 use server_core::sign::{
-    api_key_middleware, protect_route, ApiKeySource, ApiKeyValidation, ComplexApiKeyConfig,
-    SimpleApiKeyConfig, ValidatorType,
+    api_key_middleware, ApiKeyConfig, ApiKeySource, ApiKeyValidation, ComplexApiKeyConfig,
+    RouteMatcher, SignedApiKeyConfig, SimpleApiKeyConfig,
 };
-use server_core::web::{RequestId, RequestIdLayer};
+use server_core::web::{operation_log::OperationLogLayer, RequestId, RequestIdLayer};
 use server_global::global::{clear_routes, get_collected_routes, get_config};
 use server_middleware::jwt_auth_middleware;
 use server_router::admin::{
-    SysAccessKeyRouter, SysAuthenticationRouter, SysDomainRouter, SysEndpointRouter,
-    SysLoginLogRouter, SysMenuRouter, SysOperationLogRouter, SysOrganizationRouter, SysRoleRouter,
-    SysSandboxRouter, SysUserRouter,
+    SysAccessKeyRouter, SysAuthenticationRouter, SysCaptchaRouter, SysDomainRouter,
+    SysEndpointRouter, SysLoginLogRouter, SysMenuRouter, SysOperationLogRouter,
+    SysOrganizationRouter, SysPasswordResetRouter, SysRoleRouter, SysSandboxRouter, SysTokenRouter,
+    SysTotpRouter, SysUserRouter, SysWebAuthnRouter,
 };
 use server_service::{
     admin::{
-        SysAccessKeyService, SysAuthService, SysDomainService, SysEndpointService,
-        SysLoginLogService, SysMenuService, SysOperationLogService, SysOrganizationService,
-        SysRoleService, SysUserService, TEndpointService,
+        SysAccessKeyService, SysAuthService, SysCaptchaService, SysDomainService,
+        SysEndpointService, SysLoginLogService, SysMenuService, SysOperationLogService,
+        SysOrganizationService, SysPasswordResetService, SysRoleService, SysTokenService,
+        SysTotpService, SysUserService, SysWebAuthnService, TEndpointService,
     },
     SysEndpoint,
 };
 use tower_http::trace::TraceLayer;
 use tracing::info_span;
 
-use crate::{initialize_casbin, project_error, project_info};
+use crate::{initialize_access_keys, initialize_casbin, project_error, project_info};
+
+/// 操作日志中隐去的敏感字段，在请求体与响应体的任意层级按字段名匹配
+const OPERATION_LOG_REDACTED_FIELDS: &[&str] = &[
+    "password",
+    "oldPassword",
+    "newPassword",
+    "token",
+    "refreshToken",
+    "refresh_token",
+    "secret",
+    "otpauthUrl",
+    "recoveryCodes",
+    "access_key_secret",
+    "previous_access_key_secret",
+];
 
 #[derive(Clone)]
 pub enum Services<T: Send + Sync + 'static> {
@@ -60,6 +77,13 @@ async fn apply_layers<T: Send + Sync + 'static>(
         },
     };
 
+    // 需要登录的路由都记录操作日志，位于认证之后以便记录用户与模拟登录的管理员
+    if need_auth {
+        router = router.layer(
+            OperationLogLayer::new(true).with_redacted_fields(OPERATION_LOG_REDACTED_FIELDS),
+        );
+    }
+
     router = router
         .layer(
             TraceLayer::new_for_http().make_span_with(|request: &Request<Body>| {
@@ -99,41 +123,63 @@ async fn apply_layers<T: Send + Sync + 'static>(
     router
 }
 
+/// 根据配置构建受保护路由
+///
+/// 配置无效时直接终止启动，避免本应受保护的路由在无校验的情况下暴露。
+fn protected_routes(routes: &[ProtectedRouteConfig]) -> RouteMatcher {
+    RouteMatcher::from_config(routes)
+        .unwrap_or_else(|e| panic!("Invalid access key protected routes: {}", e))
+}
+
+fn signature_config(config: &ReplayProtectionConfig) -> ApiKeyConfig {
+    ApiKeyConfig {
+        nonce_ttl_secs: config.nonce_ttl_secs,
+        timestamp_disparity_ms: config.timestamp_disparity_ms,
+        ..Default::default()
+    }
+}
+
 pub async fn initialize_admin_router() -> Router {
     clear_routes().await;
     project_info!("Initializing admin router");
 
     let app_config = get_config::<Config>().await.unwrap();
-    let casbin_layer = initialize_casbin(
+    let mut casbin_layer = initialize_casbin(
         "server/resources/rbac_model.conf",
         app_config.database.url.as_str(),
     )
     .await
     .unwrap();
 
-    // 初始化验证器
-    server_core::sign::init_validators(None).await;
+    let access_key_config = get_config::<AccessKeyConfig>()
+        .await
+        .unwrap_or_else(|| Arc::new(AccessKeyConfig::default()));
+    let routes = &access_key_config.protected_routes;
+
+    // 初始化验证器，防重放配置无效时终止启动
+    server_core::sign::init_validators(
+        signature_config(&access_key_config.complex),
+        signature_config(&access_key_config.signed),
+    )
+    .await
+    .unwrap_or_else(|e| panic!("{}", e));
+    // 加载数据库中启用的访问密钥
+    initialize_access_keys(casbin_layer.get_enforcer()).await;
 
     let simple_validation = {
         let validator = server_core::sign::get_simple_validator().await;
-        server_core::sign::add_key(ValidatorType::Simple, "test-api-key", None).await;
         ApiKeyValidation::Simple(
             validator,
             SimpleApiKeyConfig {
                 source: ApiKeySource::Header,
                 key_name: "x-api-key".to_string(),
+                routes: protected_routes(&routes.simple),
             },
         )
     };
 
     let complex_validation = {
         let validator = server_core::sign::get_complex_validator().await;
-        server_core::sign::add_key(
-            ValidatorType::Complex,
-            "test-access-key",
-            Some("test-secret-key"),
-        )
-        .await;
         ApiKeyValidation::Complex(
             validator,
             ComplexApiKeyConfig {
@@ -141,13 +187,22 @@ pub async fn initialize_admin_router() -> Router {
                 timestamp_name: "t".to_string(),
                 nonce_name: "n".to_string(),
                 signature_name: "sign".to_string(),
+                routes: protected_routes(&routes.complex),
             },
         )
     };
 
-    // 保护路由
-    protect_route("/sandbox/simple-api-key");
-    protect_route("/sandbox/complex-api-key");
+    // v2 签名覆盖方法、路径、查询参数、请求头与请求体
+    let signed_validation = {
+        let validator = server_core::sign::get_signed_validator().await;
+        ApiKeyValidation::Signed(
+            validator,
+            SignedApiKeyConfig {
+                routes: protected_routes(&routes.signed),
+                ..Default::default()
+            },
+        )
+    };
 
     let audience = Audience::ManagementPlatform;
     let casbin = Some(casbin_layer);
@@ -209,6 +264,22 @@ pub async fn initialize_admin_router() -> Router {
         None
     );
 
+    merge_router!(
+        SysCaptchaRouter::init_captcha_router().await,
+        SysCaptchaService,
+        false,
+        false,
+        None
+    );
+
+    merge_router!(
+        SysPasswordResetRouter::init_password_reset_router().await,
+        SysPasswordResetService,
+        false,
+        false,
+        None
+    );
+
     merge_router!(
         SysAuthenticationRouter::init_protected_router().await,
         SysAuthService,
@@ -217,6 +288,30 @@ pub async fn initialize_admin_router() -> Router {
         None
     );
 
+    merge_router!(
+        SysAuthenticationRouter::init_impersonation_router().await,
+        SysAuthService,
+        true,
+        true,
+        None
+    );
+
+    merge_router!(
+        SysTotpRouter::init_totp_router().await,
+        SysTotpService,
+        false,
+        true,
+        None
+    );
+
+    merge_router!(
+        SysWebAuthnRouter::init_webauthn_router().await,
+        SysWebAuthnService,
+        false,
+        true,
+        None
+    );
+
     merge_router!(
         SysMenuRouter::init_menu_router().await,
         SysMenuService,
@@ -240,6 +335,14 @@ pub async fn initialize_admin_router() -> Router {
         true,
         None
     );
+
+    merge_router!(
+        SysUserRouter::init_profile_router().await,
+        SysUserService,
+        false,
+        true,
+        None
+    );
     merge_router!(
         SysDomainRouter::init_domain_router().await,
         SysDomainService,
@@ -283,6 +386,21 @@ pub async fn initialize_admin_router() -> Router {
         None
     );
 
+    merge_router!(
+        SysTokenRouter::init_token_router().await,
+        SysTokenService,
+        true,
+        true,
+        None
+    );
+    merge_router!(
+        SysTokenRouter::init_user_session_router().await,
+        SysTokenService,
+        false,
+        true,
+        None
+    );
+
     merge_router!(
         SysOrganizationRouter::init_organization_router().await,
         SysOrganizationService,
@@ -292,20 +410,28 @@ pub async fn initialize_admin_router() -> Router {
     );
 
     // sandbox
+    // 访问密钥以自身 ID 作为 casbin 主体，只能访问通过授权范围分配给它的路径
     merge_router!(
         SysSandboxRouter::init_simple_sandbox_router().await,
         None,
-        false,
+        true,
         false,
         Some(simple_validation)
     );
     merge_router!(
         SysSandboxRouter::init_complex_sandbox_router().await,
         None,
-        false,
+        true,
         false,
         Some(complex_validation)
     );
+    merge_router!(
+        SysSandboxRouter::init_signed_sandbox_router().await,
+        None,
+        true,
+        false,
+        Some(signed_validation)
+    );
 
     app = app.fallback(handler_404);
 
@@ -360,3 +486,94 @@ fn generate_id(path: &str, method: &str) -> String {
     format!("{}{}", path, method).hash(&mut hasher);
     format!("{:x}", hasher.finish())
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::routing::post;
+    use chrono::{Duration, Utc};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use server_core::web::auth::{Actor, Claims};
+    use server_global::global::{self, OperationLogContext};
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{initialize_config, initialize_keys_and_validation};
+
+    fn impersonation_token() -> String {
+        let mut claims = Claims::new(
+            "user-1".to_string(),
+            Audience::ManagementPlatform.as_str().to_string(),
+            "alice".to_string(),
+            vec!["user".to_string()],
+            "built-in".to_string(),
+            None,
+        );
+        claims.set_exp((Utc::now() + Duration::seconds(600)).timestamp() as usize);
+        claims.set_act(Actor {
+            sub: "admin-1".to_string(),
+        });
+
+        let encoding_key = EncodingKey::from_secret("soybean-admin-rust".as_ref());
+        encode(&Header::default(), &claims, &encoding_key).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_impersonated_request_is_logged_with_actor() {
+        initialize_config("../resources/application.yaml").await;
+        initialize_keys_and_validation().await;
+
+        let (tx, mut logs) = mpsc::unbounded_channel::<OperationLogContext>();
+        global::register_event_listeners(
+            Box::new(|mut rx| Box::pin(async move { while rx.recv().await.is_some() {} })),
+            &[(
+                "sys_operation_log".to_string(),
+                Box::new(move |mut rx| {
+                    let tx = tx.clone();
+                    Box::pin(async move {
+                        while let Some(event) = rx.recv().await {
+                            if let Some(context) = event.downcast_ref::<OperationLogContext>() {
+                                let _ = tx.send(context.clone());
+                            }
+                        }
+                    })
+                }),
+            )],
+        )
+        .await;
+
+        let router = Router::new().route("/user", post(|| async { "ok" }));
+        let app = apply_layers(
+            router,
+            Services::None(std::marker::PhantomData::<()>),
+            false,
+            true,
+            None,
+            None,
+            Audience::ManagementPlatform,
+        )
+        .await;
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/user")
+            .header("Authorization", format!("Bearer {}", impersonation_token()))
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"username":"bob","password":"secret123"}"#))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let log = tokio::time::timeout(std::time::Duration::from_secs(5), logs.recv())
+            .await
+            .expect("operation log should be sent")
+            .unwrap();
+        assert_eq!(log.user_id.as_deref(), Some("user-1"));
+        assert_eq!(log.actor_id.as_deref(), Some("admin-1"));
+        assert_eq!(log.url, "/user");
+        assert_eq!(
+            log.body,
+            Some(serde_json::json!({"username": "bob", "password": "******"}))
+        );
+    }
+}
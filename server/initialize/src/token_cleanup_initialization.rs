@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use server_service::admin::{SysTokenService, TTokenService};
+
+use crate::{project_error, project_info};
+
+/// 过期令牌记录的清理间隔
+const TOKEN_CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// 启动后台任务，定期删除刷新令牌已过期的 sys_tokens 记录
+pub async fn initialize_token_cleanup() {
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(TOKEN_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match SysTokenService.cleanup_expired_tokens().await {
+                Ok(0) => {},
+                Ok(count) => project_info!("Cleaned up {} expired tokens", count),
+                Err(e) => project_error!("Failed to clean up expired tokens: {:?}", e),
+            }
+        }
+    });
+    project_info!("Token cleanup task spawned");
+}
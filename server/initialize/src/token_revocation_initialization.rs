@@ -0,0 +1,13 @@
+use server_service::admin::{SysTokenService, TTokenService};
+
+use crate::{project_error, project_info};
+
+/// 从数据库恢复仍在有效期内的已吊销令牌
+///
+/// 需在主数据库与主 Redis 初始化之后调用
+pub async fn initialize_token_revocation() {
+    match SysTokenService.restore_revoked_tokens().await {
+        Ok(_) => project_info!("Token revocation list initialized"),
+        Err(e) => project_error!("Failed to restore revoked tokens: {:?}", e),
+    }
+}
@@ -1,4 +1,7 @@
-use server_core::web::{auth::Claims, jwt::JwtUtils};
+use server_core::web::{
+    auth::{Actor, Claims, User},
+    jwt::JwtUtils,
+};
 
 #[cfg(test)]
 mod tests {
@@ -61,6 +64,33 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_validate_token_with_actor() {
+        init().await;
+
+        let mut claims = create_claims(
+            "https://github.com/ByteByteBrew/soybean-admin-rust",
+            "audience",
+        );
+        let token = JwtUtils::generate_token(&claims).await.unwrap();
+        let data = JwtUtils::validate_token(&token, "audience").await.unwrap();
+        assert!(data.claims.act().is_none());
+
+        claims.set_act(Actor {
+            sub: "admin456".to_string(),
+        });
+        let token = JwtUtils::generate_token(&claims).await.unwrap();
+        let data = JwtUtils::validate_token(&token, "audience").await.unwrap();
+        assert_eq!(
+            data.claims.act().map(|act| act.sub.as_str()),
+            Some("admin456")
+        );
+
+        let user = User::from(data.claims);
+        assert_eq!(user.user_id(), "user123");
+        assert_eq!(user.actor_id(), Some("admin456".to_string()));
+    }
+
     // #[tokio::test]
     // async fn test_validate_token_invalid_issuer() {
     //     init().await;
@@ -3,7 +3,7 @@ use axum::{
 };
 use axum_casbin::CasbinVals;
 use headers::{authorization::Bearer, Authorization, HeaderMapExt};
-use server_core::web::{auth::User, jwt::JwtUtils, res::Res};
+use server_core::web::{auth::User, jwt::JwtUtils, res::Res, token_revocation::TokenRevocation};
 
 pub async fn jwt_auth_middleware(
     mut req: Request<Body>,
@@ -24,6 +24,25 @@ pub async fn jwt_auth_middleware(
     match JwtUtils::validate_token(&token, audience).await {
         Ok(data) => {
             let claims = data.claims;
+            if let Some(jti) = claims.jti() {
+                match TokenRevocation::is_revoked(jti).await {
+                    Ok(false) => {},
+                    Ok(true) => {
+                        return Res::<String>::new_error(
+                            StatusCode::UNAUTHORIZED.as_u16(),
+                            "Token has been revoked",
+                        )
+                        .into_response();
+                    },
+                    Err(err) => {
+                        return Res::<String>::new_error(
+                            StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+                            err.message.as_str(),
+                        )
+                        .into_response();
+                    },
+                }
+            }
             let user = User::from(claims);
             let vals = CasbinVals {
                 subject: user.subject(),
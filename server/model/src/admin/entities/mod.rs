@@ -5,6 +5,7 @@ pub mod prelude;
 pub mod casbin_rule;
 pub mod sea_orm_active_enums;
 pub mod sys_access_key;
+pub mod sys_access_key_usage;
 pub mod sys_domain;
 pub mod sys_endpoint;
 pub mod sys_login_log;
@@ -15,4 +16,9 @@ pub mod sys_role;
 pub mod sys_role_menu;
 pub mod sys_tokens;
 pub mod sys_user;
+pub mod sys_user_credential;
+pub mod sys_user_identity;
+pub mod sys_user_login_location;
+pub mod sys_user_password_history;
 pub mod sys_user_role;
+pub mod sys_user_totp;
@@ -2,10 +2,14 @@
 
 pub use super::{
     casbin_rule::Entity as CasbinRule, sys_access_key::Entity as SysAccessKey,
-    sys_domain::Entity as SysDomain, sys_endpoint::Entity as SysEndpoint,
-    sys_login_log::Entity as SysLoginLog, sys_menu::Entity as SysMenu,
-    sys_operation_log::Entity as SysOperationLog, sys_organization::Entity as SysOrganization,
-    sys_role::Entity as SysRole, sys_role_menu::Entity as SysRoleMenu,
-    sys_tokens::Entity as SysTokens, sys_user::Entity as SysUser,
-    sys_user_role::Entity as SysUserRole,
+    sys_access_key_usage::Entity as SysAccessKeyUsage, sys_domain::Entity as SysDomain,
+    sys_endpoint::Entity as SysEndpoint, sys_login_log::Entity as SysLoginLog,
+    sys_menu::Entity as SysMenu, sys_operation_log::Entity as SysOperationLog,
+    sys_organization::Entity as SysOrganization, sys_role::Entity as SysRole,
+    sys_role_menu::Entity as SysRoleMenu, sys_tokens::Entity as SysTokens,
+    sys_user::Entity as SysUser, sys_user_credential::Entity as SysUserCredential,
+    sys_user_identity::Entity as SysUserIdentity,
+    sys_user_login_location::Entity as SysUserLoginLocation,
+    sys_user_password_history::Entity as SysUserPasswordHistory,
+    sys_user_role::Entity as SysUserRole, sys_user_totp::Entity as SysUserTotp,
 };
@@ -22,6 +22,14 @@ pub struct Model {
     pub created_at: DateTime,
     #[sea_orm(column_type = "Text")]
     pub created_by: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub previous_access_key_secret: Option<String>,
+    pub previous_secret_expires_at: Option<DateTime>,
+    pub rotated_at: Option<DateTime>,
+    pub expires_at: Option<DateTime>,
+    pub last_used_at: Option<DateTime>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_used_ip: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
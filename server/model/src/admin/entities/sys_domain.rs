@@ -23,6 +23,14 @@ pub struct Model {
     pub updated_at: Option<DateTime>,
     #[sea_orm(column_type = "Text", nullable)]
     pub updated_by: Option<String>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub mfa_required_roles: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub login_identifier_types: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub password_policy: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub login_risk_policy: Option<Json>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
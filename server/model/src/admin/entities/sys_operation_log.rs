@@ -39,6 +39,8 @@ pub struct Model {
     pub end_time: DateTime,
     pub duration: i32,
     pub created_at: DateTime,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub actor_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
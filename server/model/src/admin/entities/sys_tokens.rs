@@ -34,6 +34,14 @@ pub struct Model {
     pub created_at: DateTime,
     #[sea_orm(column_type = "Text")]
     pub created_by: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub family_id: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub jti: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub audience: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub actor_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
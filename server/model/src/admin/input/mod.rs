@@ -1,13 +1,27 @@
-pub use sys_access_key::{AccessKeyPageRequest, CreateAccessKeyInput};
-pub use sys_authentication::LoginInput;
+pub use sys_access_key::{
+    AccessKeyPageRequest, AccessKeyPolicyInput, AccessKeyScopeInput, AccessKeyUsageRequest,
+    CreateAccessKeyInput, UpdateAccessKeyInput,
+};
+pub use sys_authentication::{
+    LoginInput, MfaEnrollInput, MfaVerifyInput, OidcCallbackInput, RefreshTokenInput,
+};
 pub use sys_domain::{CreateDomainInput, DomainPageRequest, UpdateDomainInput};
 pub use sys_endpoint::EndpointPageRequest;
 pub use sys_login_log::LoginLogPageRequest;
 pub use sys_menu::{CreateMenuInput, UpdateMenuInput};
 pub use sys_operation_log::OperationLogPageRequest;
 pub use sys_organization::OrganizationPageRequest;
+pub use sys_password_reset::{ForgotPasswordInput, ResetPasswordInput};
 pub use sys_role::{CreateRoleInput, RolePageRequest, UpdateRoleInput};
-pub use sys_user::{CreateUserInput, UpdateUserInput, UserPageRequest};
+pub use sys_token::SessionPageRequest;
+pub use sys_totp::TotpCodeInput;
+pub use sys_user::{
+    ChangePasswordInput, CreateUserInput, UpdateProfileInput, UpdateUserInput, UserPageRequest,
+};
+pub use sys_webauthn::{
+    PasskeyLoginInput, PasskeyLoginOptionsInput, PasskeyMfaOptionsInput, PasskeyMfaVerifyInput,
+    PasskeyRegisterInput, RenamePasskeyInput,
+};
 
 mod sys_access_key;
 mod sys_authentication;
@@ -17,5 +31,9 @@ mod sys_login_log;
 mod sys_menu;
 mod sys_operation_log;
 mod sys_organization;
+mod sys_password_reset;
 mod sys_role;
+mod sys_token;
+mod sys_totp;
 mod sys_user;
+mod sys_webauthn;
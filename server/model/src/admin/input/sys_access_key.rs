@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use server_core::web::page::PageRequest;
 use validator::Validate;
@@ -17,6 +18,41 @@ pub struct AccessKeyInput {
     pub status: Status,
     #[validate(length(max = 200, message = "Description must not exceed 200 characters"))]
     pub description: Option<String>,
+    /// 过期时间，为空表示永不过期
+    pub expires_at: Option<NaiveDateTime>,
 }
 
 pub type CreateAccessKeyInput = AccessKeyInput;
+
+#[derive(Deserialize, Validate)]
+pub struct UpdateAccessKeyInput {
+    pub id: String,
+    #[serde(flatten)]
+    pub access_key: AccessKeyInput,
+}
+
+/// 访问密钥的授权范围，两种方式可同时使用，最终都以访问密钥 ID 为主体写入 casbin 策略
+#[derive(Deserialize, Validate)]
+pub struct AccessKeyScopeInput {
+    /// 授权访问的 `sys_endpoint` ID
+    #[serde(default)]
+    pub endpoint_ids: Vec<String>,
+    /// 直接授权的路径与方法，路径按 casbin `keyMatch2` 匹配
+    #[serde(default)]
+    #[validate(nested)]
+    pub policies: Vec<AccessKeyPolicyInput>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct AccessKeyPolicyInput {
+    #[validate(length(min = 1, message = "Path must not be empty"))]
+    pub path: String,
+    #[validate(length(min = 1, message = "Method must not be empty"))]
+    pub method: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccessKeyUsageRequest {
+    /// 统计最近多少天，包含当天
+    pub days: Option<u32>,
+}
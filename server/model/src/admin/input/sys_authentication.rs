@@ -2,9 +2,69 @@ use serde::Deserialize;
 use validator::Validate;
 
 #[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
 pub struct LoginInput {
     #[validate(length(min = 5, message = "Username cannot be empty"))]
     pub identifier: String,
     #[validate(length(min = 6, message = "Password cannot be empty"))]
     pub password: String,
+    /// 登录域编码，未指定时从请求头解析，默认为内置域
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Domain must be between 1 and 50 characters"
+    ))]
+    pub domain: Option<String>,
+    /// 令牌受众，如 `management_platform`、`mobile_app`
+    pub audience: Option<String>,
+    #[validate(length(
+        min = 1,
+        max = 32,
+        message = "Login type must be between 1 and 32 characters"
+    ))]
+    pub login_type: Option<String>,
+    /// 验证码编号，由 `/auth/captcha` 签发
+    pub captcha_id: Option<String>,
+    #[validate(length(max = 16, message = "Captcha code cannot exceed 16 characters"))]
+    pub captcha_code: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenInput {
+    #[validate(length(min = 1, message = "Refresh token cannot be empty"))]
+    pub refresh_token: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaVerifyInput {
+    #[validate(length(min = 1, message = "MFA token cannot be empty"))]
+    pub mfa_token: String,
+    #[validate(length(
+        min = 6,
+        max = 32,
+        message = "Code must be between 6 and 32 characters"
+    ))]
+    pub code: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcCallbackInput {
+    #[validate(length(min = 1, message = "Authorization code cannot be empty"))]
+    pub code: String,
+    #[validate(length(min = 1, message = "State cannot be empty"))]
+    pub state: String,
+    /// 异常登录需要额外验证时提交的验证码编号
+    pub captcha_id: Option<String>,
+    #[validate(length(max = 16, message = "Captcha code cannot exceed 16 characters"))]
+    pub captcha_code: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaEnrollInput {
+    #[validate(length(min = 1, message = "MFA token cannot be empty"))]
+    pub mfa_token: String,
 }
@@ -25,6 +25,18 @@ pub struct DomainInput {
     pub name: String,
     #[validate(length(max = 500, message = "Description must not exceed 500 characters"))]
     pub description: Option<String>,
+    /// 必须开启两步验证的角色编码
+    #[serde(rename = "mfaRequiredRoles")]
+    pub mfa_required_roles: Option<Vec<String>>,
+    /// 允许登录的标识类型（USERNAME、EMAIL、PHONE），为空时全部允许
+    #[serde(rename = "loginIdentifierTypes")]
+    pub login_identifier_types: Option<Vec<String>>,
+    /// 密码策略，为空时使用全局默认策略
+    #[serde(rename = "passwordPolicy")]
+    pub password_policy: Option<serde_json::Value>,
+    /// 异常登录检测策略，为空时使用全局默认策略
+    #[serde(rename = "loginRiskPolicy")]
+    pub login_risk_policy: Option<serde_json::Value>,
 }
 
 pub type CreateDomainInput = DomainInput;
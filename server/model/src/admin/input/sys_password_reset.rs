@@ -0,0 +1,34 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordInput {
+    /// 用户名或邮箱
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Identifier must be between 1 and 100 characters"
+    ))]
+    pub identifier: String,
+    /// 登录域编码，未指定时从请求头解析，默认为内置域
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Domain must be between 1 and 50 characters"
+    ))]
+    pub domain: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordInput {
+    #[validate(length(min = 1, message = "Token cannot be empty"))]
+    pub token: String,
+    #[validate(length(
+        min = 6,
+        max = 100,
+        message = "Password must be between 6 and 100 characters"
+    ))]
+    pub new_password: String,
+}
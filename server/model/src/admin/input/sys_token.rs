@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use server_core::web::page::PageRequest;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPageRequest {
+    #[serde(flatten)]
+    pub page_details: PageRequest,
+    pub keywords: Option<String>,
+    pub user_id: Option<String>,
+    pub domain: Option<String>,
+    pub login_type: Option<String>,
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct TotpCodeInput {
+    #[validate(length(
+        min = 6,
+        max = 32,
+        message = "Code must be between 6 and 32 characters"
+    ))]
+    pub code: String,
+}
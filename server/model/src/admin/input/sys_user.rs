@@ -39,6 +39,8 @@ pub struct UserInput {
     #[validate(length(max = 20, message = "Phone number must not exceed 20 characters"))]
     pub phone_number: Option<String>,
     pub status: Status,
+    /// 下次登录时要求修改密码，更新时未指定则保持不变
+    pub must_change_password: Option<bool>,
 }
 
 pub type CreateUserInput = UserInput;
@@ -49,3 +51,32 @@ pub struct UpdateUserInput {
     #[serde(flatten)]
     pub user: UserInput,
 }
+
+/// 当前用户修改个人资料，用户名、所属域与状态只能由管理员修改
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProfileInput {
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Nick name must be between 1 and 50 characters"
+    ))]
+    pub nick_name: String,
+    #[validate(email(message = "Invalid email format"))]
+    pub email: Option<String>,
+    #[validate(length(max = 20, message = "Phone number must not exceed 20 characters"))]
+    pub phone_number: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordInput {
+    #[validate(length(min = 1, message = "Old password cannot be empty"))]
+    pub old_password: String,
+    #[validate(length(
+        min = 6,
+        max = 100,
+        message = "Password must be between 6 and 100 characters"
+    ))]
+    pub new_password: String,
+}
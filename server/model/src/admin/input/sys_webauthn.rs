@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use validator::Validate;
+use webauthn_rs_proto::{PublicKeyCredential, RegisterPublicKeyCredential};
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRegisterInput {
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Name must be between 1 and 50 characters"
+    ))]
+    pub name: String,
+    /// 浏览器 `navigator.credentials.create()` 返回的凭证
+    #[serde(flatten)]
+    pub credential: RegisterPublicKeyCredential,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyLoginOptionsInput {
+    /// 用户名，未指定时由认证器选择可发现凭证
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Identifier must be between 1 and 50 characters"
+    ))]
+    pub identifier: Option<String>,
+    /// 登录域编码，未指定时从请求头解析，默认为内置域
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Domain must be between 1 and 50 characters"
+    ))]
+    pub domain: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyLoginInput {
+    /// 浏览器 `navigator.credentials.get()` 返回的凭证
+    #[serde(flatten)]
+    pub credential: PublicKeyCredential,
+    /// 登录域编码，未指定时从请求头解析，默认为内置域
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Domain must be between 1 and 50 characters"
+    ))]
+    pub domain: Option<String>,
+    /// 令牌受众，如 `management_platform`、`mobile_app`
+    pub audience: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyMfaOptionsInput {
+    #[validate(length(min = 1, message = "MFA token cannot be empty"))]
+    pub mfa_token: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyMfaVerifyInput {
+    #[validate(length(min = 1, message = "MFA token cannot be empty"))]
+    pub mfa_token: String,
+    /// 浏览器 `navigator.credentials.get()` 返回的凭证
+    #[serde(flatten)]
+    pub credential: PublicKeyCredential,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePasskeyInput {
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Name must be between 1 and 50 characters"
+    ))]
+    pub name: String,
+}
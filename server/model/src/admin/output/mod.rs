@@ -1,11 +1,25 @@
-pub use sys_authentication::{AuthOutput, UserInfoOutput, UserRoute};
+pub use sys_access_key::{AccessKeyOutput, AccessKeyPolicyOutput};
+pub use sys_authentication::{
+    AuthOutput, LoginOutput, MfaChallengeOutput, OidcAuthorizeOutput, UserInfoOutput, UserRoute,
+};
+pub use sys_captcha::CaptchaOutput;
 pub use sys_domain::DomainOutput;
 pub use sys_endpoint::EndpointTree;
 pub use sys_menu::{MenuRoute, MenuTree, RouteMeta};
+pub use sys_token::SessionOutput;
+pub use sys_totp::{TotpEnrollOutput, TotpRecoveryCodesOutput};
 pub use sys_user::{UserWithDomainAndOrgOutput, UserWithoutPassword};
+pub use sys_webauthn::{
+    PasskeyCreationOptionsOutput, PasskeyCredentialOutput, PasskeyRequestOptionsOutput,
+};
 
+mod sys_access_key;
 mod sys_authentication;
+mod sys_captcha;
 mod sys_domain;
 mod sys_endpoint;
 mod sys_menu;
+mod sys_token;
+mod sys_totp;
 mod sys_user;
+mod sys_webauthn;
@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+use crate::admin::entities::{
+    sys_access_key::Model as SysAccessKeyModel,
+    sys_access_key_usage::Model as SysAccessKeyUsageModel,
+};
+
+#[derive(Debug, Serialize)]
+pub struct AccessKeyOutput {
+    #[serde(flatten)]
+    pub access_key: SysAccessKeyModel,
+    /// 当天的使用统计，当天没有请求时为空
+    pub today_usage: Option<SysAccessKeyUsageModel>,
+}
+
+/// 访问密钥在所属域内被授权的一条 casbin 策略
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AccessKeyPolicyOutput {
+    pub path: String,
+    pub method: String,
+}
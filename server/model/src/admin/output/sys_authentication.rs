@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use serde::Serialize;
 
 use super::MenuRoute;
@@ -8,6 +9,39 @@ pub struct AuthOutput {
     // 为了复用soybean-admin-nestjs前端,暂时弃用
     // pub access_token: String,
     pub refresh_token: String,
+    #[serde(rename = "passwordChangedAt")]
+    pub password_changed_at: Option<NaiveDateTime>,
+    /// 密码已过期或被要求修改，前端应跳转到修改密码页面
+    #[serde(rename = "mustChangePassword")]
+    pub must_change_password: bool,
+}
+
+/// 密码登录结果，开启两步验证时返回验证挑战而不是令牌
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginOutput {
+    Authenticated(AuthOutput),
+    MfaRequired(MfaChallengeOutput),
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallengeOutput {
+    pub mfa_token: String,
+    /// 挑战的有效期（秒）
+    pub expires_in: u64,
+    /// 角色要求两步验证但用户尚未绑定，需要先绑定身份验证器
+    pub enrollment_required: bool,
+    /// 可用的验证方式：`totp`、`passkey`
+    pub methods: Vec<String>,
+}
+
+/// OpenID Connect 授权地址，前端跳转到该地址完成登录
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcAuthorizeOutput {
+    pub authorization_url: String,
+    pub state: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -17,6 +51,15 @@ pub struct UserInfoOutput {
     #[serde(rename = "userName")]
     pub user_name: String,
     pub roles: Vec<String>,
+    pub avatar: Option<String>,
+    pub email: Option<String>,
+    /// 所属域编码
+    pub domain: String,
+    /// 所属组织名称
+    pub organization: Option<String>,
+    /// 模拟登录时发起模拟的管理员 ID
+    #[serde(rename = "impersonatorId", skip_serializing_if = "Option::is_none")]
+    pub impersonator_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptchaOutput {
+    pub captcha_id: String,
+    /// 验证码图片，`data:` URL 格式
+    pub image: String,
+    pub expires_in: u64,
+}
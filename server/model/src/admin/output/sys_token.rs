@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::admin::entities::sys_tokens::Model as SysTokensModel;
+
+/// 在线会话，不包含令牌本身
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionOutput {
+    pub id: String,
+    pub user_id: String,
+    pub username: String,
+    pub domain: String,
+    pub ip: String,
+    pub port: Option<i32>,
+    pub address: String,
+    pub user_agent: String,
+    pub login_type: String,
+    pub audience: Option<String>,
+    /// 模拟会话的发起人，普通会话为空
+    pub actor_id: Option<String>,
+    pub login_time: NaiveDateTime,
+    /// 是否为发起请求的当前会话
+    pub current: bool,
+}
+
+impl From<SysTokensModel> for SessionOutput {
+    fn from(model: SysTokensModel) -> Self {
+        Self {
+            id: model.id,
+            user_id: model.user_id,
+            username: model.username,
+            domain: model.domain,
+            ip: model.ip,
+            port: model.port,
+            address: model.address,
+            user_agent: model.user_agent,
+            login_type: model.r#type,
+            audience: model.audience,
+            actor_id: model.actor_id,
+            login_time: model.login_time,
+            current: false,
+        }
+    }
+}
@@ -14,6 +14,8 @@ pub struct UserWithDomainAndOrgOutput {
     pub avatar: Option<String>,
     pub domain_code: String,
     pub domain_name: String,
+    pub password_changed_at: Option<NaiveDateTime>,
+    pub must_change_password: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -31,6 +33,8 @@ pub struct UserWithoutPassword {
     pub created_by: String,
     pub updated_at: Option<NaiveDateTime>,
     pub updated_by: Option<String>,
+    pub password_changed_at: Option<NaiveDateTime>,
+    pub must_change_password: bool,
 }
 
 impl From<SysUserModel> for UserWithoutPassword {
@@ -48,6 +52,8 @@ impl From<SysUserModel> for UserWithoutPassword {
             created_by: model.created_by,
             updated_at: model.updated_at,
             updated_by: model.updated_by,
+            password_changed_at: model.password_changed_at,
+            must_change_password: model.must_change_password,
         }
     }
 }
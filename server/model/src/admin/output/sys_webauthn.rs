@@ -0,0 +1,38 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use webauthn_rs_proto::{CreationChallengeResponse, RequestChallengeResponse};
+
+use crate::admin::entities::sys_user_credential::Model as SysUserCredentialModel;
+
+/// 传给 `navigator.credentials.create()` 的参数，二进制字段均为 base64url 编码
+pub type PasskeyCreationOptionsOutput = CreationChallengeResponse;
+
+/// 传给 `navigator.credentials.get()` 的参数，二进制字段均为 base64url 编码
+pub type PasskeyRequestOptionsOutput = RequestChallengeResponse;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyCredentialOutput {
+    pub id: String,
+    pub name: String,
+    pub aaguid: String,
+    pub transports: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+impl From<SysUserCredentialModel> for PasskeyCredentialOutput {
+    fn from(model: SysUserCredentialModel) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            aaguid: model.aaguid,
+            transports: model
+                .transports
+                .map(|transports| transports.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+            created_at: model.created_at,
+            last_used_at: model.last_used_at,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use axum::{
     http::Method,
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 use server_api::admin::SysAccessKeyApi;
@@ -16,12 +16,43 @@ impl SysAccessKeyRouter {
         let routes = vec![
             RouteInfo::new(base_path, Method::GET, service_name, "获取访问密钥列表"),
             RouteInfo::new(base_path, Method::POST, service_name, "创建访问密钥"),
+            RouteInfo::new(base_path, Method::PUT, service_name, "更新访问密钥"),
             RouteInfo::new(
                 &format!("{}/:id", base_path),
                 Method::DELETE,
                 service_name,
                 "删除访问密钥",
             ),
+            RouteInfo::new(
+                &format!("{}/:id/rotate", base_path),
+                Method::POST,
+                service_name,
+                "轮换访问密钥",
+            ),
+            RouteInfo::new(
+                &format!("{}/:id/retire", base_path),
+                Method::POST,
+                service_name,
+                "停用轮换前的访问密钥",
+            ),
+            RouteInfo::new(
+                &format!("{}/:id/stats", base_path),
+                Method::GET,
+                service_name,
+                "获取访问密钥使用统计",
+            ),
+            RouteInfo::new(
+                &format!("{}/:id/scopes", base_path),
+                Method::GET,
+                service_name,
+                "获取访问密钥授权范围",
+            ),
+            RouteInfo::new(
+                &format!("{}/:id/scopes", base_path),
+                Method::PUT,
+                service_name,
+                "设置访问密钥授权范围",
+            ),
         ];
 
         for route in routes {
@@ -31,7 +62,19 @@ impl SysAccessKeyRouter {
         let router = Router::new()
             .route("/", get(SysAccessKeyApi::get_paginated_access_keys))
             .route("/", post(SysAccessKeyApi::create_access_key))
-            .route("/:id", delete(SysAccessKeyApi::delete_access_key));
+            .route("/", put(SysAccessKeyApi::update_access_key))
+            .route("/:id", delete(SysAccessKeyApi::delete_access_key))
+            .route("/:id/stats", get(SysAccessKeyApi::get_access_key_usage))
+            .route("/:id/scopes", get(SysAccessKeyApi::get_access_key_scopes))
+            .route(
+                "/:id/scopes",
+                put(SysAccessKeyApi::update_access_key_scopes),
+            )
+            .route(
+                "/:id/rotate",
+                post(SysAccessKeyApi::rotate_access_key_secret),
+            )
+            .route("/:id/retire", post(SysAccessKeyApi::retire_previous_secret));
 
         Router::new().nest(base_path, router)
     }
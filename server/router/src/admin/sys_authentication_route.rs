@@ -1,19 +1,66 @@
 use axum::{
+    http::Method,
     routing::{get, post},
     Router,
 };
 use server_api::admin::SysAuthenticationApi;
+use server_global::global::{add_route, RouteInfo};
 
 pub struct SysAuthenticationRouter;
 
 impl SysAuthenticationRouter {
     pub async fn init_authentication_router() -> Router {
-        let router = Router::new().route("/login", post(SysAuthenticationApi::login_handler));
-        Router::new().nest("/auth", router)
+        let router = Router::new()
+            .route("/login", post(SysAuthenticationApi::login_handler))
+            .route(
+                "/refreshToken",
+                post(SysAuthenticationApi::refresh_token_handler),
+            )
+            .route(
+                "/mfa/verify",
+                post(SysAuthenticationApi::verify_mfa_handler),
+            )
+            .route(
+                "/mfa/enroll",
+                post(SysAuthenticationApi::enroll_mfa_handler),
+            )
+            .route(
+                "/passkey/login/options",
+                post(SysAuthenticationApi::passkey_login_options_handler),
+            )
+            .route(
+                "/passkey/login",
+                post(SysAuthenticationApi::passkey_login_handler),
+            )
+            .route(
+                "/mfa/passkey/options",
+                post(SysAuthenticationApi::passkey_mfa_options_handler),
+            )
+            .route(
+                "/mfa/passkey/verify",
+                post(SysAuthenticationApi::verify_passkey_mfa_handler),
+            )
+            .route(
+                "/oidc/:provider/authorize",
+                get(SysAuthenticationApi::oidc_authorize_handler),
+            )
+            .route(
+                "/oidc/:provider/callback",
+                post(SysAuthenticationApi::oidc_callback_handler),
+            );
+        Router::new()
+            .route("/.well-known/jwks.json", get(SysAuthenticationApi::jwks))
+            .nest("/auth", router)
     }
 
     pub async fn init_protected_router() -> Router {
-        let router = Router::new().route("/getUserInfo", get(SysAuthenticationApi::get_user_info));
+        let router = Router::new()
+            .route("/getUserInfo", get(SysAuthenticationApi::get_user_info))
+            .route("/logout", post(SysAuthenticationApi::logout))
+            .route(
+                "/impersonation/stop",
+                post(SysAuthenticationApi::stop_impersonation),
+            );
 
         let authorization_router =
             Router::new().route("/getUserRoutes", get(SysAuthenticationApi::get_user_routes));
@@ -22,4 +69,22 @@ impl SysAuthenticationRouter {
             .nest("/auth", router)
             .nest("/authorization", authorization_router)
     }
+
+    /// 模拟登录需要权限校验，结束模拟只需登录
+    pub async fn init_impersonation_router() -> Router {
+        let base_path = "/impersonation";
+        let service_name = "SysAuthenticationApi";
+
+        add_route(RouteInfo::new(
+            &format!("{}/:userId", base_path),
+            Method::POST,
+            service_name,
+            "模拟登录用户",
+        ))
+        .await;
+
+        let router = Router::new().route("/:userId", post(SysAuthenticationApi::impersonate));
+
+        Router::new().nest(base_path, router)
+    }
 }
@@ -0,0 +1,11 @@
+use axum::{routing::get, Router};
+use server_api::admin::SysCaptchaApi;
+
+pub struct SysCaptchaRouter;
+
+impl SysCaptchaRouter {
+    /// 登录验证码，无需登录
+    pub async fn init_captcha_router() -> Router {
+        Router::new().route("/auth/captcha", get(SysCaptchaApi::generate))
+    }
+}
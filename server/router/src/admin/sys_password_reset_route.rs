@@ -0,0 +1,15 @@
+use axum::{routing::post, Router};
+use server_api::admin::SysPasswordResetApi;
+
+pub struct SysPasswordResetRouter;
+
+impl SysPasswordResetRouter {
+    /// 找回密码，无需登录
+    pub async fn init_password_reset_router() -> Router {
+        let router = Router::new()
+            .route("/forgot", post(SysPasswordResetApi::forgot_password))
+            .route("/reset", post(SysPasswordResetApi::reset_password));
+
+        Router::new().nest("/auth/password", router)
+    }
+}
@@ -1,4 +1,7 @@
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 use server_api::admin::SysSandboxApi;
 
 pub struct SysSandboxRouter;
@@ -17,4 +20,10 @@ impl SysSandboxRouter {
             Router::new().route("/complex-api-key", get(SysSandboxApi::test_complex_api_key));
         Router::new().nest(Self::BASE_PATH, router)
     }
+
+    pub async fn init_signed_sandbox_router() -> Router {
+        let router =
+            Router::new().route("/signed-api-key", post(SysSandboxApi::test_signed_api_key));
+        Router::new().nest(Self::BASE_PATH, router)
+    }
 }
@@ -0,0 +1,57 @@
+use axum::{
+    http::Method,
+    routing::{delete, get},
+    Router,
+};
+use server_api::admin::SysTokenApi;
+use server_global::global::{add_route, RouteInfo};
+
+pub struct SysTokenRouter;
+
+impl SysTokenRouter {
+    pub async fn init_token_router() -> Router {
+        let base_path = "/token";
+        let service_name = "SysTokenApi";
+
+        let routes = vec![
+            RouteInfo::new(
+                &format!("{}/user/:userId", base_path),
+                Method::DELETE,
+                service_name,
+                "吊销用户全部令牌",
+            ),
+            RouteInfo::new(
+                &format!("{}/sessions", base_path),
+                Method::GET,
+                service_name,
+                "获取在线会话列表",
+            ),
+            RouteInfo::new(
+                &format!("{}/sessions/:id", base_path),
+                Method::DELETE,
+                service_name,
+                "强制下线会话",
+            ),
+        ];
+
+        for route in routes {
+            add_route(route).await;
+        }
+
+        let router = Router::new()
+            .route("/user/:userId", delete(SysTokenApi::revoke_user_tokens))
+            .route("/sessions", get(SysTokenApi::get_paginated_sessions))
+            .route("/sessions/:id", delete(SysTokenApi::revoke_session));
+
+        Router::new().nest(base_path, router)
+    }
+
+    /// 当前用户管理自己的会话，只需登录，不做权限校验
+    pub async fn init_user_session_router() -> Router {
+        let router = Router::new()
+            .route("/", get(SysTokenApi::get_my_sessions))
+            .route("/:id", delete(SysTokenApi::revoke_my_session));
+
+        Router::new().nest("/auth/sessions", router)
+    }
+}
@@ -0,0 +1,20 @@
+use axum::{routing::post, Router};
+use server_api::admin::SysTotpApi;
+
+pub struct SysTotpRouter;
+
+impl SysTotpRouter {
+    /// 当前用户自助管理两步验证，只需登录，不做权限校验
+    pub async fn init_totp_router() -> Router {
+        let router = Router::new()
+            .route("/enroll", post(SysTotpApi::enroll))
+            .route("/confirm", post(SysTotpApi::confirm))
+            .route("/disable", post(SysTotpApi::disable))
+            .route(
+                "/recoveryCodes",
+                post(SysTotpApi::regenerate_recovery_codes),
+            );
+
+        Router::new().nest("/auth/totp", router)
+    }
+}
@@ -65,4 +65,14 @@ impl SysUserRouter {
 
         Router::new().nest(base_path, router)
     }
+
+    /// 当前用户的个人资料与密码，只需登录
+    pub async fn init_profile_router() -> Router {
+        let router = Router::new()
+            .route("/profile", get(SysUserApi::get_profile))
+            .route("/profile", put(SysUserApi::update_profile))
+            .route("/password", put(SysUserApi::change_password));
+
+        Router::new().nest("/auth", router)
+    }
 }
@@ -0,0 +1,27 @@
+use axum::{
+    routing::{delete, get, post, put},
+    Router,
+};
+use server_api::admin::SysWebAuthnApi;
+
+pub struct SysWebAuthnRouter;
+
+impl SysWebAuthnRouter {
+    /// 当前用户自助管理通行密钥，只需登录，不做权限校验
+    pub async fn init_webauthn_router() -> Router {
+        let router = Router::new()
+            .route(
+                "/register/options",
+                post(SysWebAuthnApi::registration_options),
+            )
+            .route("/register", post(SysWebAuthnApi::register))
+            .route("/credentials", get(SysWebAuthnApi::get_credentials))
+            .route("/credentials/:id", put(SysWebAuthnApi::rename_credential))
+            .route(
+                "/credentials/:id",
+                delete(SysWebAuthnApi::delete_credential),
+            );
+
+        Router::new().nest("/auth/passkey", router)
+    }
+}
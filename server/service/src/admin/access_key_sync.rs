@@ -0,0 +1,373 @@
+//! 访问密钥验证器的加载与多实例同步
+//!
+//! 验证器保存在各实例的内存中：启动时从数据库加载全部 `ENABLED` 的密钥，
+//! 之后每次创建、修改或删除密钥都会在本地刷新，并通过主 Redis 的发布/订阅
+//! 通知其他实例按 `access_key_id` 回源数据库刷新。消息中不携带密钥本身。
+//!
+//! 访问密钥的授权范围保存在 casbin 策略中，授权或撤销后同样通过该频道通知，
+//! 其他实例收到后从数据库重新加载各自的 enforcer。
+//!
+//! 全量重载与单个密钥的刷新都会先读数据库再写验证器，两者互斥执行，
+//! 避免较早读到的全量快照覆盖之后刷新的密钥状态。
+use std::{sync::Arc, time::Duration};
+
+use axum_casbin::casbin::{CachedApi, CachedEnforcer, CoreApi};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use redis::{AsyncCommands, Client};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use server_config::RedisConfig;
+use server_core::{
+    sign::{ApiKeyCredentials, ApiKeySecret, ValidatorType},
+    web::error::AppError,
+};
+use server_global::{
+    global::{get_config, RedisConnection, GLOBAL_PRIMARY_REDIS},
+    project_error, project_info,
+};
+use server_model::admin::entities::{
+    prelude::SysAccessKey,
+    sea_orm_active_enums::Status,
+    sys_access_key::{Column as SysAccessKeyColumn, Model as SysAccessKeyModel},
+};
+use tokio::sync::{Mutex, RwLock};
+use ulid::Ulid;
+
+use crate::helper::db_helper;
+
+/// 访问密钥变更的发布/订阅频道
+const ACCESS_KEY_CHANNEL: &str = "soybean:access_key:changed";
+
+/// 订阅断开后重连的等待时间
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// 当前进程的实例标识，用于忽略自己发布的消息
+static INSTANCE_ID: Lazy<String> = Lazy::new(|| Ulid::new().to_string());
+
+/// 串行化全量重载与单个密钥的刷新
+static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// 广播给其他实例的变更通知
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct AccessKeyChanged {
+    origin: String,
+    #[serde(flatten)]
+    change: AccessKeyChange,
+}
+
+/// 变更内容
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum AccessKeyChange {
+    /// 密钥本身变更，按 `access_key_id` 回源刷新验证器
+    Key { access_key_id: String },
+    /// 授权策略变更，重新加载 casbin 策略
+    Policies,
+}
+
+/// 访问密钥当前接受的 secret、密钥本身的过期时间及所属域
+///
+/// 轮换宽限期内的旧 secret 带过期时间，与密钥过期一样由验证器自行判断失效
+fn key_credentials(access_key: &SysAccessKeyModel) -> ApiKeyCredentials {
+    let mut secrets = vec![ApiKeySecret::new(access_key.access_key_secret.clone())];
+    if let (Some(secret), Some(expires_at)) = (
+        &access_key.previous_access_key_secret,
+        access_key.previous_secret_expires_at,
+    ) {
+        secrets.push(ApiKeySecret::expiring(secret.clone(), expires_at));
+    }
+    ApiKeyCredentials {
+        secrets,
+        expires_at: access_key.expires_at,
+        domain: Some(access_key.domain.clone()),
+    }
+}
+
+/// 从数据库加载全部启用的访问密钥，整体替换验证器中的密钥，返回加载的数量
+pub(crate) async fn load_enabled_keys() -> Result<usize, AppError> {
+    let _guard = SYNC_LOCK.lock().await;
+    let db = db_helper::get_db_connection().await?;
+    let key_credentials: Vec<(String, ApiKeyCredentials)> = SysAccessKey::find()
+        .filter(SysAccessKeyColumn::Status.eq(Status::ENABLED))
+        .all(db.as_ref())
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .map(|key| {
+            let credentials = key_credentials(&key);
+            (key.access_key_id, credentials)
+        })
+        .collect();
+
+    server_core::sign::replace_keys(&key_credentials).await;
+    Ok(key_credentials.len())
+}
+
+/// 按数据库中的最新状态刷新单个访问密钥：启用则加入验证器，否则移除
+async fn refresh_key(access_key_id: &str) -> Result<(), AppError> {
+    let _guard = SYNC_LOCK.lock().await;
+    let db = db_helper::get_db_connection().await?;
+    let access_key = SysAccessKey::find()
+        .filter(SysAccessKeyColumn::AccessKeyId.eq(access_key_id))
+        .one(db.as_ref())
+        .await
+        .map_err(AppError::from)?;
+
+    match access_key {
+        Some(key) if key.status == Status::ENABLED => {
+            server_core::sign::set_key_credentials(&key.access_key_id, key_credentials(&key)).await;
+        },
+        _ => {
+            server_core::sign::remove_key(ValidatorType::Simple, access_key_id).await;
+            server_core::sign::remove_key(ValidatorType::Complex, access_key_id).await;
+            server_core::sign::remove_key(ValidatorType::Signed, access_key_id).await;
+        },
+    }
+
+    Ok(())
+}
+
+/// 在数据库事务提交后调用：刷新本地验证器并通知其他实例
+///
+/// 数据库已是最终状态，同步失败只记录日志，不影响请求结果
+pub(crate) async fn key_changed(access_key_id: &str) {
+    if let Err(e) = refresh_key(access_key_id).await {
+        project_error!("Failed to refresh access key {}: {:?}", access_key_id, e);
+    }
+    let change = AccessKeyChange::Key {
+        access_key_id: access_key_id.to_string(),
+    };
+    if let Err(e) = publish(change).await {
+        project_error!(
+            "Failed to publish access key change {}: {:?}",
+            access_key_id,
+            e
+        );
+    }
+}
+
+/// 在授权策略写入本地 enforcer 之后调用：通知其他实例重新加载策略
+///
+/// 策略已持久化到数据库，通知失败只记录日志，不影响请求结果
+pub async fn access_key_policies_changed() {
+    if let Err(e) = publish(AccessKeyChange::Policies).await {
+        project_error!("Failed to publish access key policy change: {:?}", e);
+    }
+}
+
+/// 从数据库重新加载 casbin 策略，并清空判定缓存
+async fn reload_policies(enforcer: &RwLock<CachedEnforcer>) -> Result<(), AppError> {
+    let mut enforcer = enforcer.write().await;
+    enforcer.load_policy().await.map_err(|e| AppError {
+        code: 500,
+        message: e.to_string(),
+    })?;
+    enforcer.get_mut_cache().clear();
+    Ok(())
+}
+
+/// 未配置主 Redis 时只有单个实例，无需广播
+async fn publish(change: AccessKeyChange) -> Result<(), AppError> {
+    let message = serde_json::to_string(&AccessKeyChanged {
+        origin: INSTANCE_ID.clone(),
+        change,
+    })
+    .map_err(|e| AppError {
+        code: 500,
+        message: e.to_string(),
+    })?;
+
+    match GLOBAL_PRIMARY_REDIS.read().await.clone() {
+        Some(RedisConnection::Single(client)) => {
+            let mut conn = client.get_multiplexed_async_connection().await?;
+            conn.publish::<_, _, ()>(ACCESS_KEY_CHANNEL, message)
+                .await?;
+        },
+        Some(RedisConnection::Cluster(client)) => {
+            let mut conn = client.get_async_connection().await?;
+            conn.publish::<_, _, ()>(ACCESS_KEY_CHANNEL, message)
+                .await?;
+        },
+        None => {},
+    }
+
+    Ok(())
+}
+
+/// 解析变更通知，忽略本实例发出的消息
+fn parse_message(payload: &str) -> Option<AccessKeyChanged> {
+    serde_json::from_str::<AccessKeyChanged>(payload)
+        .ok()
+        .filter(|message| message.origin != *INSTANCE_ID)
+}
+
+/// 创建用于订阅变更通知的 Redis 客户端，未配置主 Redis 时返回空列表
+///
+/// 集群会把 `PUBLISH` 广播到所有节点，订阅任意一个节点即可收到全部通知，
+/// 因此集群模式下为每个节点各创建一个普通客户端，订阅失败时依次切换节点。
+/// 集群未配置节点地址或地址无效时返回错误，应在启动时处理。
+pub async fn access_key_sync_clients() -> Result<Vec<Client>, AppError> {
+    match GLOBAL_PRIMARY_REDIS.read().await.clone() {
+        Some(RedisConnection::Single(client)) => Ok(vec![client.as_ref().clone()]),
+        Some(RedisConnection::Cluster(_)) => {
+            let config = get_config::<RedisConfig>().await.ok_or_else(|| AppError {
+                code: 500,
+                message: "Primary Redis config is missing".to_string(),
+            })?;
+            cluster_node_clients(&config)
+        },
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 为集群的每个节点地址创建普通客户端
+fn cluster_node_clients(config: &RedisConfig) -> Result<Vec<Client>, AppError> {
+    let urls = config.get_urls().unwrap_or_default();
+    if urls.is_empty() {
+        return Err(AppError {
+            code: 500,
+            message: "Access key sync requires at least one Redis cluster node URL".to_string(),
+        });
+    }
+
+    urls.iter()
+        .map(|url| Client::open(url.as_str()).map_err(AppError::from))
+        .collect()
+}
+
+/// 订阅其他实例发布的访问密钥变更
+///
+/// `clients` 由 [`access_key_sync_clients`] 创建，为空时直接返回。
+/// 每次(重新)订阅成功后都会全量重载密钥与 `enforcer` 的策略，以弥补断线期间错过的通知；
+/// 订阅先于重载建立，重载期间到达的通知会在重载完成后依次处理。
+pub async fn access_key_sync_listener(clients: Vec<Client>, enforcer: Arc<RwLock<CachedEnforcer>>) {
+    if clients.is_empty() {
+        return;
+    }
+
+    let mut node = 0;
+    loop {
+        let client = &clients[node];
+        // 连接或订阅失败、频道断开时都切换到下一个节点
+        node = (node + 1) % clients.len();
+
+        let mut pubsub = match client.get_async_pubsub().await {
+            Ok(pubsub) => pubsub,
+            Err(e) => {
+                project_error!("Failed to connect access key sync channel: {:?}", e);
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+                continue;
+            },
+        };
+        if let Err(e) = pubsub.subscribe(ACCESS_KEY_CHANNEL).await {
+            project_error!("Failed to subscribe access key sync channel: {:?}", e);
+            tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            continue;
+        }
+
+        match load_enabled_keys().await {
+            Ok(count) => project_info!("Access key sync subscribed, {} keys loaded", count),
+            Err(e) => project_error!("Failed to reload access keys: {:?}", e),
+        }
+        if let Err(e) = reload_policies(&enforcer).await {
+            project_error!("Failed to reload access key policies: {:?}", e);
+        }
+
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            let Ok(payload) = msg.get_payload::<String>() else {
+                continue;
+            };
+            match parse_message(&payload).map(|message| message.change) {
+                Some(AccessKeyChange::Key { access_key_id }) => {
+                    if let Err(e) = refresh_key(&access_key_id).await {
+                        project_error!("Failed to refresh access key {}: {:?}", access_key_id, e);
+                    }
+                },
+                Some(AccessKeyChange::Policies) => {
+                    if let Err(e) = reload_policies(&enforcer).await {
+                        project_error!("Failed to reload access key policies: {:?}", e);
+                    }
+                },
+                None => {},
+            }
+        }
+
+        project_error!("Access key sync channel closed, resubscribing");
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use server_config::RedisMode;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_message_skips_own_instance() {
+        let key_changed = |origin: &str| AccessKeyChanged {
+            origin: origin.to_string(),
+            change: AccessKeyChange::Key {
+                access_key_id: "AK1".to_string(),
+            },
+        };
+
+        let own = serde_json::to_string(&key_changed(&INSTANCE_ID)).unwrap();
+        assert_eq!(parse_message(&own), None);
+
+        let other = serde_json::to_string(&key_changed("other")).unwrap();
+        assert_eq!(parse_message(&other), Some(key_changed("other")));
+
+        assert_eq!(parse_message("not json"), None);
+    }
+
+    #[test]
+    fn test_parse_policies_message() {
+        assert_eq!(
+            parse_message(r#"{"origin":"other","kind":"policies"}"#),
+            Some(AccessKeyChanged {
+                origin: "other".to_string(),
+                change: AccessKeyChange::Policies,
+            })
+        );
+        assert_eq!(
+            parse_message(r#"{"origin":"other","kind":"key","access_key_id":"AK1"}"#),
+            Some(AccessKeyChanged {
+                origin: "other".to_string(),
+                change: AccessKeyChange::Key {
+                    access_key_id: "AK1".to_string(),
+                },
+            })
+        );
+        assert_eq!(
+            parse_message(r#"{"origin":"other","kind":"unknown"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cluster_node_clients() {
+        let config = |urls: Option<Vec<&str>>| RedisConfig {
+            mode: RedisMode::Cluster,
+            url: None,
+            urls: urls.map(|urls| urls.into_iter().map(String::from).collect()),
+        };
+
+        let clients = cluster_node_clients(&config(Some(vec![
+            "redis://127.0.0.1:7001",
+            "redis://127.0.0.1:7002",
+        ])))
+        .unwrap();
+        assert_eq!(clients.len(), 2);
+        assert_eq!(
+            clients[1].get_connection_info().addr.to_string(),
+            "127.0.0.1:7002"
+        );
+
+        assert!(cluster_node_clients(&config(None)).is_err());
+        assert!(cluster_node_clients(&config(Some(vec![]))).is_err());
+        assert!(cluster_node_clients(&config(Some(vec!["not a url"]))).is_err());
+    }
+}
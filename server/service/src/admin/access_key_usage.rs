@@ -0,0 +1,235 @@
+//! 访问密钥使用情况的异步记录
+//!
+//! 验证中间件只通过事件通道发送 [`AccessKeyUsageEvent`]，不在请求路径上写库。
+//! 监听器每次取出通道中积压的全部事件，按密钥和日期聚合后批量写入
+//! `sys_access_key_usage`，并更新密钥最近一次成功使用的时间与 IP。
+use std::{any::Any, collections::HashMap};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::{
+    sea_query::{Alias, Expr, OnConflict, SimpleExpr},
+    ColumnTrait, EntityTrait, QueryFilter, Set,
+};
+use server_core::{
+    sign::{AccessKeyUsageEvent, ApiKeyRejection},
+    web::error::AppError,
+};
+use server_global::project_error;
+use server_model::admin::entities::{
+    prelude::{SysAccessKey, SysAccessKeyUsage},
+    sys_access_key::Column as SysAccessKeyColumn,
+    sys_access_key_usage::{
+        ActiveModel as SysAccessKeyUsageActiveModel, Column as SysAccessKeyUsageColumn,
+    },
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::instrument;
+
+use crate::helper::db_helper;
+
+/// 单次聚合写入的最大事件数
+const MAX_BATCH_SIZE: usize = 1000;
+
+/// 一个密钥在一天内的计数
+#[derive(Debug, Default, PartialEq, Eq)]
+struct UsageCounts {
+    request_count: i64,
+    success_count: i64,
+    bad_signature_count: i64,
+    replayed_nonce_count: i64,
+    expired_timestamp_count: i64,
+    expired_key_count: i64,
+}
+
+impl UsageCounts {
+    fn add(&mut self, outcome: Result<(), ApiKeyRejection>) {
+        self.request_count += 1;
+        match outcome {
+            Ok(()) => self.success_count += 1,
+            Err(ApiKeyRejection::BadSignature) => self.bad_signature_count += 1,
+            Err(ApiKeyRejection::ReplayedNonce) => self.replayed_nonce_count += 1,
+            Err(ApiKeyRejection::ExpiredTimestamp) => self.expired_timestamp_count += 1,
+            Err(ApiKeyRejection::ExpiredKey) => self.expired_key_count += 1,
+            Err(ApiKeyRejection::UnknownKey) => {},
+        }
+    }
+}
+
+/// 一批事件的聚合结果
+#[derive(Debug, Default)]
+struct UsageBatch {
+    counts: HashMap<(String, NaiveDate), UsageCounts>,
+    /// 每个密钥最近一次成功使用的时间与 IP
+    last_used: HashMap<String, (NaiveDateTime, String)>,
+}
+
+impl UsageBatch {
+    fn add(&mut self, event: &AccessKeyUsageEvent) {
+        self.counts
+            .entry((event.access_key_id.clone(), event.occurred_at.date()))
+            .or_default()
+            .add(event.outcome);
+
+        if event.outcome.is_ok() {
+            let last_used = self
+                .last_used
+                .entry(event.access_key_id.clone())
+                .or_insert_with(|| (event.occurred_at, event.ip.clone()));
+            if event.occurred_at > last_used.0 {
+                *last_used = (event.occurred_at, event.ip.clone());
+            }
+        }
+    }
+}
+
+/// 计数列冲突时累加到已有的行上
+fn increment(column: SysAccessKeyUsageColumn) -> (SysAccessKeyUsageColumn, SimpleExpr) {
+    (
+        column,
+        Expr::col((SysAccessKeyUsage, column)).add(Expr::col((Alias::new("excluded"), column))),
+    )
+}
+
+async fn write_batch(batch: UsageBatch) -> Result<(), AppError> {
+    let db = db_helper::get_db_connection().await?;
+
+    let rows: Vec<SysAccessKeyUsageActiveModel> = batch
+        .counts
+        .into_iter()
+        .map(
+            |((access_key_id, usage_date), counts)| SysAccessKeyUsageActiveModel {
+                access_key_id: Set(access_key_id),
+                usage_date: Set(usage_date),
+                request_count: Set(counts.request_count),
+                success_count: Set(counts.success_count),
+                bad_signature_count: Set(counts.bad_signature_count),
+                replayed_nonce_count: Set(counts.replayed_nonce_count),
+                expired_timestamp_count: Set(counts.expired_timestamp_count),
+                expired_key_count: Set(counts.expired_key_count),
+            },
+        )
+        .collect();
+
+    if !rows.is_empty() {
+        SysAccessKeyUsage::insert_many(rows)
+            .on_conflict(
+                OnConflict::columns([
+                    SysAccessKeyUsageColumn::AccessKeyId,
+                    SysAccessKeyUsageColumn::UsageDate,
+                ])
+                .values([
+                    increment(SysAccessKeyUsageColumn::RequestCount),
+                    increment(SysAccessKeyUsageColumn::SuccessCount),
+                    increment(SysAccessKeyUsageColumn::BadSignatureCount),
+                    increment(SysAccessKeyUsageColumn::ReplayedNonceCount),
+                    increment(SysAccessKeyUsageColumn::ExpiredTimestampCount),
+                    increment(SysAccessKeyUsageColumn::ExpiredKeyCount),
+                ])
+                .to_owned(),
+            )
+            .exec(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+    }
+
+    for (access_key_id, (last_used_at, ip)) in batch.last_used {
+        // 事件可能乱序到达，只向前更新
+        SysAccessKey::update_many()
+            .col_expr(SysAccessKeyColumn::LastUsedAt, Expr::value(last_used_at))
+            .col_expr(SysAccessKeyColumn::LastUsedIp, Expr::value(ip))
+            .filter(SysAccessKeyColumn::AccessKeyId.eq(access_key_id))
+            .filter(
+                SysAccessKeyColumn::LastUsedAt
+                    .is_null()
+                    .or(SysAccessKeyColumn::LastUsedAt.lt(last_used_at)),
+            )
+            .exec(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+    }
+
+    Ok(())
+}
+
+#[instrument(skip(rx))]
+pub async fn access_key_usage_listener(mut rx: UnboundedReceiver<Box<dyn Any + Send>>) {
+    while let Some(event) = rx.recv().await {
+        let mut batch = UsageBatch::default();
+        let mut next = Some(event);
+        let mut size = 0;
+
+        while let Some(event) = next.take() {
+            match event.downcast_ref::<AccessKeyUsageEvent>() {
+                Some(usage_event) => batch.add(usage_event),
+                None => project_error!("Received unknown event type in access key usage listener"),
+            }
+            size += 1;
+            if size < MAX_BATCH_SIZE {
+                next = rx.try_recv().ok();
+            }
+        }
+
+        if let Err(e) = write_batch(batch).await {
+            project_error!("Failed to record access key usage: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(
+        access_key_id: &str,
+        ip: &str,
+        outcome: Result<(), ApiKeyRejection>,
+        occurred_at: &str,
+    ) -> AccessKeyUsageEvent {
+        AccessKeyUsageEvent {
+            access_key_id: access_key_id.to_string(),
+            ip: ip.to_string(),
+            outcome,
+            occurred_at: NaiveDateTime::parse_from_str(occurred_at, "%Y-%m-%d %H:%M:%S").unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_usage_batch() {
+        let mut batch = UsageBatch::default();
+        batch.add(&event("AK1", "10.0.0.2", Ok(()), "2024-12-01 10:00:00"));
+        batch.add(&event("AK1", "10.0.0.1", Ok(()), "2024-12-01 09:00:00"));
+        batch.add(&event(
+            "AK1",
+            "10.0.0.3",
+            Err(ApiKeyRejection::BadSignature),
+            "2024-12-01 11:00:00",
+        ));
+        batch.add(&event(
+            "AK1",
+            "10.0.0.1",
+            Err(ApiKeyRejection::ReplayedNonce),
+            "2024-12-02 00:00:01",
+        ));
+
+        let day = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        assert_eq!(
+            batch.counts[&("AK1".to_string(), day("2024-12-01"))],
+            UsageCounts {
+                request_count: 3,
+                success_count: 2,
+                bad_signature_count: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            batch.counts[&("AK1".to_string(), day("2024-12-02"))],
+            UsageCounts {
+                request_count: 1,
+                replayed_nonce_count: 1,
+                ..Default::default()
+            }
+        );
+        // 失败的请求不更新最近使用信息
+        assert_eq!(batch.last_used["AK1"].1, "10.0.0.2");
+    }
+}
@@ -1,5 +1,13 @@
 pub mod sys_access_key_error;
+pub mod sys_captcha_error;
 pub mod sys_domain_error;
+pub mod sys_impersonation_error;
+pub mod sys_login_security_error;
 pub mod sys_menu_error;
+pub mod sys_mfa_error;
+pub mod sys_oidc_error;
+pub mod sys_password_reset_error;
 pub mod sys_role_error;
+pub mod sys_token_error;
 pub mod sys_user_error;
+pub mod sys_webauthn_error;
@@ -5,12 +5,18 @@ use thiserror::Error;
 pub enum AccessKeyError {
     #[error("Access key not found")]
     AccessKeyNotFound,
+    #[error("Access key has no previous secret to retire")]
+    NoPreviousSecret,
+    #[error("Endpoint not found")]
+    EndpointNotFound,
 }
 
 impl ApiError for AccessKeyError {
     fn code(&self) -> u16 {
         match self {
             AccessKeyError::AccessKeyNotFound => 5001,
+            AccessKeyError::NoPreviousSecret => 5002,
+            AccessKeyError::EndpointNotFound => 5003,
         }
     }
 
@@ -0,0 +1,42 @@
+use server_constant::definition::consts::LoginFailureReason;
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CaptchaError {
+    #[error("Captcha is required")]
+    Required,
+    #[error("Captcha is invalid or has expired")]
+    Invalid,
+}
+
+impl CaptchaError {
+    pub fn failure_reason(&self) -> LoginFailureReason {
+        match self {
+            CaptchaError::Required => LoginFailureReason::CaptchaRequired,
+            CaptchaError::Invalid => LoginFailureReason::InvalidCaptcha,
+        }
+    }
+}
+
+impl ApiError for CaptchaError {
+    fn code(&self) -> u16 {
+        match self {
+            CaptchaError::Required => 9001,
+            CaptchaError::Invalid => 9002,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<CaptchaError> for AppError {
+    fn from(err: CaptchaError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
@@ -11,6 +11,14 @@ pub enum DomainError {
     DuplicateName,
     #[error("Cannot modify or delete built-in domain")]
     BuiltInDomain,
+    #[error("Domain is disabled")]
+    DomainDisabled,
+    #[error("Invalid login identifier type: {0}")]
+    InvalidLoginIdentifierType(String),
+    #[error("Invalid password policy: {0}")]
+    InvalidPasswordPolicy(String),
+    #[error("Invalid login risk policy: {0}")]
+    InvalidLoginRiskPolicy(String),
 }
 
 impl ApiError for DomainError {
@@ -20,6 +28,10 @@ impl ApiError for DomainError {
             DomainError::DuplicateCode => 2002,
             DomainError::DuplicateName => 2003,
             DomainError::BuiltInDomain => 2004,
+            DomainError::DomainDisabled => 2005,
+            DomainError::InvalidLoginIdentifierType(_) => 2006,
+            DomainError::InvalidPasswordPolicy(_) => 2007,
+            DomainError::InvalidLoginRiskPolicy(_) => 2008,
         }
     }
 
@@ -0,0 +1,44 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ImpersonationError {
+    #[error("Built-in users cannot be impersonated")]
+    BuiltInUser,
+    #[error("Cannot impersonate while already impersonating another user")]
+    NestedImpersonation,
+    #[error("Cannot impersonate yourself")]
+    SelfImpersonation,
+    #[error("Current session is not an impersonation session")]
+    NotImpersonating,
+    #[error("Cannot impersonate users of another domain")]
+    CrossDomain,
+    #[error("Action not allowed during impersonation")]
+    ActionNotAllowed,
+}
+
+impl ApiError for ImpersonationError {
+    fn code(&self) -> u16 {
+        match self {
+            ImpersonationError::BuiltInUser => 12001,
+            ImpersonationError::NestedImpersonation => 12002,
+            ImpersonationError::SelfImpersonation => 12003,
+            ImpersonationError::NotImpersonating => 12004,
+            ImpersonationError::CrossDomain => 12005,
+            ImpersonationError::ActionNotAllowed => 12006,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<ImpersonationError> for AppError {
+    fn from(err: ImpersonationError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use server_constant::definition::consts::LoginFailureReason;
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LoginSecurityError {
+    #[error("Account is locked, please try again in {0} seconds")]
+    AccountLocked(u64),
+    #[error("Login from this IP address is not allowed")]
+    IpBlocked,
+    #[error("Too many failed login attempts from this IP address, please try again later")]
+    TooManyIpAttempts,
+    #[error("Login is not allowed at this time")]
+    OutsideLoginWindow,
+}
+
+impl LoginSecurityError {
+    pub fn failure_reason(&self) -> LoginFailureReason {
+        match self {
+            LoginSecurityError::AccountLocked(_) => LoginFailureReason::AccountLocked,
+            LoginSecurityError::IpBlocked => LoginFailureReason::IpBlocked,
+            LoginSecurityError::TooManyIpAttempts => LoginFailureReason::TooManyIpAttempts,
+            LoginSecurityError::OutsideLoginWindow => LoginFailureReason::OutsideLoginWindow,
+        }
+    }
+}
+
+impl ApiError for LoginSecurityError {
+    fn code(&self) -> u16 {
+        match self {
+            LoginSecurityError::AccountLocked(_) => 7001,
+            LoginSecurityError::IpBlocked => 7002,
+            LoginSecurityError::TooManyIpAttempts => 7003,
+            LoginSecurityError::OutsideLoginWindow => 7004,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<LoginSecurityError> for AppError {
+    fn from(err: LoginSecurityError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
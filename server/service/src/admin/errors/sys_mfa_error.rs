@@ -0,0 +1,50 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MfaError {
+    #[error("Two-factor authentication challenge is invalid or has expired")]
+    InvalidChallenge,
+    #[error("Invalid two-factor authentication code")]
+    InvalidCode,
+    #[error("Too many invalid two-factor authentication codes, please login again")]
+    TooManyAttempts,
+    #[error("Two-factor authentication has not been enrolled")]
+    NotEnrolled,
+    #[error("Two-factor authentication is already enabled")]
+    AlreadyEnabled,
+    #[error("Two-factor authentication is not enabled")]
+    NotEnabled,
+    #[error("Two-factor authentication is required for your role and cannot be disabled")]
+    Required,
+    #[error("Failed to generate two-factor authentication secret")]
+    SecretGenerationFailed,
+}
+
+impl ApiError for MfaError {
+    fn code(&self) -> u16 {
+        match self {
+            MfaError::InvalidChallenge => 8001,
+            MfaError::InvalidCode => 8002,
+            MfaError::TooManyAttempts => 8003,
+            MfaError::NotEnrolled => 8004,
+            MfaError::AlreadyEnabled => 8005,
+            MfaError::NotEnabled => 8006,
+            MfaError::Required => 8007,
+            MfaError::SecretGenerationFailed => 8008,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<MfaError> for AppError {
+    fn from(err: MfaError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
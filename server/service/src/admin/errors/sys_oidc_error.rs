@@ -0,0 +1,47 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum OidcError {
+    #[error("OIDC provider not found")]
+    ProviderNotFound,
+    #[error("Authorization state is invalid or has expired")]
+    InvalidState,
+    #[error("Invalid ID token: {0}")]
+    InvalidIdToken(String),
+    #[error("OIDC provider request failed: {0}")]
+    ProviderRequestFailed(String),
+    #[error("External identity is not linked to any user")]
+    IdentityNotLinked,
+    #[error("Unable to provision a unique username for the external identity")]
+    UsernameConflict,
+    #[error("Discovery document issuer {0} does not match the configured issuer")]
+    IssuerMismatch(String),
+}
+
+impl ApiError for OidcError {
+    fn code(&self) -> u16 {
+        match self {
+            OidcError::ProviderNotFound => 11001,
+            OidcError::InvalidState => 11002,
+            OidcError::InvalidIdToken(_) => 11003,
+            OidcError::ProviderRequestFailed(_) => 11004,
+            OidcError::IdentityNotLinked => 11005,
+            OidcError::UsernameConflict => 11006,
+            OidcError::IssuerMismatch(_) => 11007,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<OidcError> for AppError {
+    fn from(err: OidcError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PasswordResetError {
+    #[error("Password reset token is invalid or has expired")]
+    InvalidToken,
+    #[error("Too many password reset requests, please try again later")]
+    TooManyRequests,
+}
+
+impl ApiError for PasswordResetError {
+    fn code(&self) -> u16 {
+        match self {
+            PasswordResetError::InvalidToken => 10001,
+            PasswordResetError::TooManyRequests => 10002,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<PasswordResetError> for AppError {
+    fn from(err: PasswordResetError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
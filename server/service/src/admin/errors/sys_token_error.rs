@@ -0,0 +1,50 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TokenError {
+    #[error("Refresh token not found")]
+    RefreshTokenNotFound,
+    #[error("Refresh token has expired")]
+    RefreshTokenExpired,
+    #[error("Refresh token has been revoked")]
+    RefreshTokenRevoked,
+    #[error("Refresh token reuse detected, all related sessions have been revoked")]
+    RefreshTokenReused,
+    #[error("Invalid token status")]
+    InvalidTokenStatus,
+    #[error("Session not found")]
+    SessionNotFound,
+    #[error("Impersonation sessions cannot be refreshed")]
+    ImpersonationNotRefreshable,
+    #[error("Maximum number of concurrent sessions reached")]
+    SessionLimitExceeded,
+}
+
+impl ApiError for TokenError {
+    fn code(&self) -> u16 {
+        match self {
+            TokenError::RefreshTokenNotFound => 6001,
+            TokenError::RefreshTokenExpired => 6002,
+            TokenError::RefreshTokenRevoked => 6003,
+            TokenError::RefreshTokenReused => 6004,
+            TokenError::InvalidTokenStatus => 6005,
+            TokenError::SessionNotFound => 6006,
+            TokenError::ImpersonationNotRefreshable => 6007,
+            TokenError::SessionLimitExceeded => 6008,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<TokenError> for AppError {
+    fn from(err: TokenError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
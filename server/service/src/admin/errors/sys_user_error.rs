@@ -13,6 +13,14 @@ pub enum UserError {
     UsernameAlreadyExists,
     #[error("Invalid user status")]
     InvalidUserStatus,
+    #[error("{0}")]
+    PasswordPolicyViolation(String),
+    #[error("Password has been used recently")]
+    PasswordReused,
+    #[error("Email already exists")]
+    EmailAlreadyExists,
+    #[error("Phone number already exists")]
+    PhoneNumberAlreadyExists,
 }
 
 impl ApiError for UserError {
@@ -23,6 +31,10 @@ impl ApiError for UserError {
             UserError::AuthenticationFailed => 1003,
             UserError::UsernameAlreadyExists => 1004,
             UserError::InvalidUserStatus => 1005,
+            UserError::PasswordPolicyViolation(_) => 1006,
+            UserError::PasswordReused => 1007,
+            UserError::EmailAlreadyExists => 1008,
+            UserError::PhoneNumberAlreadyExists => 1009,
         }
     }
 
@@ -0,0 +1,64 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+use webauthn_rs::prelude::WebauthnError;
+
+#[derive(Error, Debug)]
+pub enum WebAuthnError {
+    #[error("Passkey authentication is disabled")]
+    Disabled,
+    #[error("Passkey challenge is invalid or has expired")]
+    InvalidChallenge,
+    #[error("Invalid passkey response: {0}")]
+    InvalidResponse(String),
+    #[error("Unsupported passkey algorithm")]
+    UnsupportedAlgorithm,
+    #[error("Passkey signature verification failed")]
+    VerificationFailed,
+    #[error("Passkey not found")]
+    CredentialNotFound,
+    #[error("Passkey is already registered")]
+    CredentialAlreadyRegistered,
+    #[error("Passkey signature counter did not increase, the authenticator may have been cloned")]
+    CounterRegression,
+}
+
+impl ApiError for WebAuthnError {
+    fn code(&self) -> u16 {
+        match self {
+            WebAuthnError::Disabled => 13001,
+            WebAuthnError::InvalidChallenge => 13002,
+            WebAuthnError::InvalidResponse(_) => 13003,
+            WebAuthnError::UnsupportedAlgorithm => 13004,
+            WebAuthnError::VerificationFailed => 13005,
+            WebAuthnError::CredentialNotFound => 13006,
+            WebAuthnError::CredentialAlreadyRegistered => 13007,
+            WebAuthnError::CounterRegression => 13008,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<WebAuthnError> for AppError {
+    fn from(err: WebAuthnError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
+
+impl From<WebauthnError> for WebAuthnError {
+    fn from(err: WebauthnError) -> Self {
+        match err {
+            WebauthnError::MismatchedChallenge => WebAuthnError::InvalidChallenge,
+            WebauthnError::AuthenticationFailure => WebAuthnError::VerificationFailed,
+            WebauthnError::CredentialPossibleCompromise => WebAuthnError::CounterRegression,
+            WebauthnError::COSEKeyInvalidAlgorithm
+            | WebauthnError::CredentialAlteredAlgFromRequest => WebAuthnError::UnsupportedAlgorithm,
+            err => WebAuthnError::InvalidResponse(err.to_string()),
+        }
+    }
+}
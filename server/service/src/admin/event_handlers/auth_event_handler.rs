@@ -1,22 +1,35 @@
+use server_constant::definition::consts::LoginStatus;
 use server_core::web::error::AppError;
 
-use crate::{
-    admin::events::{access_token_event::AccessTokenEvent, login_log_event::LoginLogEvent},
-    helper::db_helper,
-};
+use crate::{admin::events::login_log_event::LoginLogEvent, helper::db_helper};
 
 pub struct AuthEvent {
     pub user_id: String,
     pub username: String,
     pub domain: String,
-    pub access_token: String,
-    pub refresh_token: String,
     pub client_ip: String,
     pub client_port: Option<i32>,
     pub address: String,
     pub user_agent: String,
     pub request_id: String,
     pub login_type: String,
+    pub identifier_type: String,
+}
+
+/// 登录失败事件，用户不存在时 `user_id` 为空
+pub struct LoginFailedEvent {
+    pub user_id: String,
+    pub username: String,
+    pub domain: String,
+    pub client_ip: String,
+    pub client_port: Option<i32>,
+    pub address: String,
+    pub user_agent: String,
+    pub request_id: String,
+    pub login_type: String,
+    pub reason: String,
+    /// 未解析出标识类型（如登录域校验失败）时为空
+    pub identifier_type: Option<String>,
 }
 
 pub struct AuthEventHandler;
@@ -26,37 +39,43 @@ impl AuthEventHandler {
         let db = db_helper::get_db_connection().await?;
 
         // 处理登录日志
-        let login_log_event = LoginLogEvent {
-            user_id: event.user_id.clone(),
-            username: event.username.clone(),
-            domain: event.domain.clone(),
-            ip: event.client_ip.clone(),
-            port: event.client_port,
-            address: event.address.clone(),
-            user_agent: event.user_agent.clone(),
-            request_id: event.request_id.clone(),
-            login_type: event.login_type.clone(),
-        };
-
-        login_log_event.handle(&db).await?;
-
-        // 处理访问令牌
-        let access_token_event = AccessTokenEvent {
-            access_token: event.access_token,
-            refresh_token: event.refresh_token,
+        // 令牌记录已在登录请求中同步写入，这里只处理登录日志
+        LoginLogEvent {
             user_id: event.user_id,
             username: event.username,
             domain: event.domain,
             ip: event.client_ip,
             port: event.client_port,
-            address: event.address.clone(),
+            address: event.address,
             user_agent: event.user_agent,
             request_id: event.request_id,
             login_type: event.login_type,
-        };
+            status: LoginStatus::Success.to_string(),
+            failure_reason: None,
+            identifier_type: Some(event.identifier_type),
+        }
+        .handle(&db)
+        .await
+    }
 
-        access_token_event.handle(&db).await?;
+    pub async fn handle_login_failure(event: LoginFailedEvent) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
 
-        Ok(())
+        LoginLogEvent {
+            user_id: event.user_id,
+            username: event.username,
+            domain: event.domain,
+            ip: event.client_ip,
+            port: event.client_port,
+            address: event.address,
+            user_agent: event.user_agent,
+            request_id: event.request_id,
+            login_type: event.login_type,
+            status: LoginStatus::Failure.to_string(),
+            failure_reason: Some(event.reason),
+            identifier_type: event.identifier_type,
+        }
+        .handle(&db)
+        .await
     }
 }
@@ -14,6 +14,12 @@ pub struct LoginLogEvent {
     pub user_agent: String,
     pub request_id: String,
     pub login_type: String,
+    /// 登录结果，见 `LoginStatus`
+    pub status: String,
+    /// 登录失败原因，见 `LoginFailureReason`
+    pub failure_reason: Option<String>,
+    /// 登录标识类型，见 `LoginIdentifierType`
+    pub identifier_type: Option<String>,
 }
 
 impl LoginLogEvent {
@@ -32,6 +38,9 @@ impl LoginLogEvent {
             r#type: Set(self.login_type),
             created_at: Set(Local::now().naive_local()),
             created_by: Set(self.username),
+            status: Set(self.status),
+            failure_reason: Set(self.failure_reason),
+            identifier_type: Set(self.identifier_type),
         }
         .insert(db)
         .await
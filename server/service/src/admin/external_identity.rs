@@ -0,0 +1,198 @@
+//! 外部身份与本地用户的关联
+//!
+//! OpenID Connect、LDAP 等外部认证通过后按 (provider, subject) 查找关联的本地用户，
+//! 未关联时可以创建本地用户并写入关联，令牌签发等流程仍基于本地用户。
+use chrono::Local;
+use sea_orm::{
+    sea_query::{Condition, Expr, Query},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, Set, TransactionTrait,
+};
+use server_core::web::error::AppError;
+use server_model::admin::entities::{
+    casbin_rule::Column as CasbinRuleColumn,
+    prelude::{CasbinRule, SysRole, SysRoleMenu, SysUser, SysUserIdentity},
+    sea_orm_active_enums::Status,
+    sys_role::Column as SysRoleColumn,
+    sys_role_menu::Column as SysRoleMenuColumn,
+    sys_user::{ActiveModel as SysUserActiveModel, Column as SysUserColumn},
+    sys_user_identity::{
+        ActiveModel as SysUserIdentityActiveModel, Column as SysUserIdentityColumn,
+    },
+    sys_user_role::ActiveModel as SysUserRoleActiveModel,
+};
+use ulid::Ulid;
+
+use super::sys_user_service::SysUserService;
+use crate::{project_error, project_info};
+
+/// 创建本地用户所需的外部身份信息
+pub(crate) struct ExternalProfile<'a> {
+    pub provider: &'a str,
+    pub subject: &'a str,
+    pub domain: &'a str,
+    pub username: &'a str,
+    pub nick_name: &'a str,
+    pub email: Option<&'a str>,
+}
+
+pub(crate) struct ExternalIdentity;
+
+impl ExternalIdentity {
+    /// 查找外部身份关联的用户并更新最近登录时间
+    pub(crate) async fn linked_user(
+        db: &DatabaseConnection,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<String>, AppError> {
+        let Some(identity) = SysUserIdentity::find()
+            .filter(SysUserIdentityColumn::Provider.eq(provider))
+            .filter(SysUserIdentityColumn::Subject.eq(subject))
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+        else {
+            return Ok(None);
+        };
+
+        SysUserIdentity::update_many()
+            .col_expr(
+                SysUserIdentityColumn::LastLoginAt,
+                Expr::value(Local::now().naive_local()),
+            )
+            .filter(SysUserIdentityColumn::Id.eq(&identity.id))
+            .exec(db)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Some(identity.user_id))
+    }
+
+    pub(crate) async fn username_exists<C: ConnectionTrait>(
+        db: &C,
+        username: &str,
+    ) -> Result<bool, AppError> {
+        SysUser::find()
+            .filter(SysUserColumn::Username.eq(username))
+            .count(db)
+            .await
+            .map(|count| count > 0)
+            .map_err(AppError::from)
+    }
+
+    /// 创建本地用户、分配角色并关联外部身份，返回用户 ID
+    pub(crate) async fn provision(
+        db: &DatabaseConnection,
+        profile: &ExternalProfile<'_>,
+        role_codes: &[String],
+    ) -> Result<String, AppError> {
+        let now = Local::now().naive_local();
+        let txn = db.begin().await.map_err(AppError::from)?;
+
+        // 邮箱已被其他用户占用时不写入，避免违反唯一约束
+        let email = match profile.email {
+            Some(email)
+                if SysUser::find()
+                    .filter(SysUserColumn::Email.eq(email))
+                    .count(&txn)
+                    .await
+                    .map_err(AppError::from)?
+                    == 0 =>
+            {
+                Some(email.to_string())
+            },
+            _ => None,
+        };
+        // 外部身份用户不使用本地密码，写入无人知晓的随机密码
+        let password = SysUserService::hash_password(&Ulid::new().to_string())?;
+
+        let user = SysUserActiveModel {
+            id: Set(Ulid::new().to_string()),
+            domain: Set(profile.domain.to_string()),
+            username: Set(profile.username.to_string()),
+            password: Set(password),
+            password_changed_at: Set(Some(now)),
+            must_change_password: Set(false),
+            built_in: Set(false),
+            nick_name: Set(profile.nick_name.to_string()),
+            email: Set(email.clone()),
+            status: Set(Status::ENABLED),
+            created_at: Set(now),
+            created_by: Set(format!("external:{}", profile.provider)),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(AppError::from)?;
+
+        if !role_codes.is_empty() {
+            // 角色本身不区分域，只分配在本域内拥有授权或菜单的角色，避免授予其他域的角色
+            let roles = SysRole::find()
+                .filter(SysRoleColumn::Code.is_in(role_codes.to_vec()))
+                .filter(
+                    Condition::any()
+                        .add(
+                            SysRoleColumn::Code.in_subquery(
+                                Query::select()
+                                    .column(CasbinRuleColumn::V0)
+                                    .from(CasbinRule)
+                                    .and_where(CasbinRuleColumn::Ptype.eq("p"))
+                                    .and_where(CasbinRuleColumn::V1.eq(profile.domain))
+                                    .to_owned(),
+                            ),
+                        )
+                        .add(
+                            SysRoleColumn::Id.in_subquery(
+                                Query::select()
+                                    .column(SysRoleMenuColumn::RoleId)
+                                    .from(SysRoleMenu)
+                                    .and_where(SysRoleMenuColumn::Domain.eq(profile.domain))
+                                    .to_owned(),
+                            ),
+                        ),
+                )
+                .all(&txn)
+                .await
+                .map_err(AppError::from)?;
+            if roles.len() < role_codes.len() {
+                project_error!(
+                    "Some roles of {:?} for provider {} not found in domain {}",
+                    role_codes,
+                    profile.provider,
+                    profile.domain
+                );
+            }
+            for role in roles {
+                SysUserRoleActiveModel {
+                    user_id: Set(user.id.clone()),
+                    role_id: Set(role.id),
+                }
+                .insert(&txn)
+                .await
+                .map_err(AppError::from)?;
+            }
+        }
+
+        SysUserIdentityActiveModel {
+            id: Set(Ulid::new().to_string()),
+            user_id: Set(user.id.clone()),
+            provider: Set(profile.provider.to_string()),
+            subject: Set(profile.subject.to_string()),
+            email: Set(email),
+            created_at: Set(now),
+            last_login_at: Set(Some(now)),
+        }
+        .insert(&txn)
+        .await
+        .map_err(AppError::from)?;
+
+        txn.commit().await.map_err(AppError::from)?;
+
+        project_info!(
+            "Provisioned user {} for external provider {}",
+            user.username,
+            profile.provider
+        );
+        Ok(user.id)
+    }
+}
@@ -0,0 +1,269 @@
+use std::{sync::Arc, time::Duration};
+
+use server_config::LdapConfig;
+use server_global::global;
+
+use crate::{
+    ldap::{self, LdapConnection, LdapError, INVALID_CREDENTIALS},
+    project_error,
+};
+
+/// 外部身份表中目录用户的提供方名称
+pub(crate) const LDAP_PROVIDER: &str = "ldap";
+const USERNAME_PLACEHOLDER: &str = "{username}";
+
+/// 目录认证结果
+#[derive(Debug)]
+pub(crate) enum DirectoryOutcome {
+    Authenticated(DirectoryUser),
+    /// 目录中不存在该用户，或匹配到多个条目
+    NotFound,
+    InvalidCredentials,
+}
+
+#[derive(Debug)]
+pub(crate) struct DirectoryUser {
+    pub dn: String,
+    pub username: String,
+    pub nick_name: String,
+    pub email: Option<String>,
+    pub groups: Vec<String>,
+}
+
+/// LDAP / Active Directory 认证：服务账号搜索用户后以用户 DN 绑定
+pub(crate) struct LdapDirectory;
+
+impl LdapDirectory {
+    async fn config() -> Arc<LdapConfig> {
+        global::get_config::<LdapConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(LdapConfig::default()))
+    }
+
+    pub(crate) async fn enabled_for(domain: &str) -> bool {
+        let config = Self::config().await;
+        config.enabled && config.domains.iter().any(|d| d == domain)
+    }
+
+    pub(crate) async fn authenticate(
+        username: &str,
+        password: &str,
+    ) -> Result<DirectoryOutcome, LdapError> {
+        let config = Self::config().await;
+        tokio::time::timeout(
+            Duration::from_secs(config.timeout),
+            Self::authenticate_with(&config, username, password),
+        )
+        .await
+        .map_err(|_| LdapError::Timeout)?
+    }
+
+    /// 登录域内用户所属目录组映射到的角色编码
+    pub(crate) async fn group_roles(domain: &str, groups: &[String]) -> Vec<String> {
+        map_group_roles(Self::config().await.as_ref(), domain, groups)
+    }
+
+    async fn authenticate_with(
+        config: &LdapConfig,
+        username: &str,
+        password: &str,
+    ) -> Result<DirectoryOutcome, LdapError> {
+        // 空密码的简单绑定会被服务器当作匿名绑定而成功
+        if password.is_empty() {
+            return Ok(DirectoryOutcome::InvalidCredentials);
+        }
+
+        let filter = ldap::build_filter(&config.user_filter, USERNAME_PLACEHOLDER, username)?;
+        let mut conn = LdapConnection::connect(&config.url).await?;
+        if !config.bind_dn.is_empty() {
+            conn.simple_bind(&config.bind_dn, &config.bind_password)
+                .await?;
+        }
+
+        let mut entries = conn
+            .search(
+                &config.base_dn,
+                &filter,
+                &[
+                    &config.username_attribute,
+                    &config.nick_name_attribute,
+                    &config.email_attribute,
+                    &config.group_attribute,
+                ],
+                2,
+            )
+            .await?;
+        if entries.len() != 1 {
+            if entries.len() > 1 {
+                project_error!("LDAP filter matched multiple entries for user {}", username);
+            }
+            conn.unbind().await;
+            return Ok(DirectoryOutcome::NotFound);
+        }
+        let entry = entries.remove(0);
+
+        match conn.simple_bind(&entry.dn, password).await {
+            Ok(()) => {},
+            Err(LdapError::Operation { code, .. }) if code == INVALID_CREDENTIALS => {
+                conn.unbind().await;
+                return Ok(DirectoryOutcome::InvalidCredentials);
+            },
+            Err(e) => return Err(e),
+        }
+        conn.unbind().await;
+
+        let username = entry
+            .first(&config.username_attribute)
+            .unwrap_or(username)
+            .to_string();
+        Ok(DirectoryOutcome::Authenticated(DirectoryUser {
+            nick_name: entry
+                .first(&config.nick_name_attribute)
+                .unwrap_or(&username)
+                .to_string(),
+            email: entry
+                .first(&config.email_attribute)
+                .map(ToString::to_string),
+            groups: entry.values(&config.group_attribute).to_vec(),
+            // DN 不区分大小写，统一为小写作为外部身份标识
+            dn: entry.dn.to_lowercase(),
+            username,
+        }))
+    }
+}
+
+fn map_group_roles(config: &LdapConfig, domain: &str, groups: &[String]) -> Vec<String> {
+    let mut roles: Vec<String> = Vec::new();
+    for mapping in config.group_roles.iter().filter(|m| m.domain == domain) {
+        if groups
+            .iter()
+            .any(|g| g.eq_ignore_ascii_case(&mapping.group))
+            && !roles.contains(&mapping.role)
+        {
+            roles.push(mapping.role.clone());
+        }
+    }
+    roles
+}
+
+#[cfg(test)]
+mod tests {
+    use server_config::LdapGroupRole;
+
+    use super::*;
+    use crate::ldap::fake::{self, FakeDirectory};
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    fn config(url: String) -> LdapConfig {
+        LdapConfig {
+            enabled: true,
+            url,
+            bind_dn: fake::ADMIN_DN.to_string(),
+            bind_password: fake::ADMIN_PASSWORD.to_string(),
+            base_dn: "dc=example,dc=org".to_string(),
+            user_filter: "(&(objectClass=person)(uid={username}))".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        runtime().block_on(async {
+            let config = config(FakeDirectory::sample().start().await);
+
+            match LdapDirectory::authenticate_with(&config, "alice", "wonderland")
+                .await
+                .unwrap()
+            {
+                DirectoryOutcome::Authenticated(user) => {
+                    assert_eq!(user.dn, fake::ALICE_DN);
+                    assert_eq!(user.username, "alice");
+                    assert_eq!(user.nick_name, "Alice Liddell");
+                    assert_eq!(user.email.as_deref(), Some("alice@example.org"));
+                    assert_eq!(user.groups.len(), 2);
+                },
+                other => panic!("unexpected outcome: {:?}", other),
+            }
+
+            match LdapDirectory::authenticate_with(&config, "bob", "builder")
+                .await
+                .unwrap()
+            {
+                DirectoryOutcome::Authenticated(user) => {
+                    assert_eq!(user.nick_name, "bob");
+                    assert!(user.email.is_none());
+                    assert!(user.groups.is_empty());
+                },
+                other => panic!("unexpected outcome: {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_authenticate_rejections() {
+        runtime().block_on(async {
+            let config = config(FakeDirectory::sample().start().await);
+
+            for (username, password, expected) in [
+                ("alice", "wrong", "InvalidCredentials"),
+                ("alice", "", "InvalidCredentials"),
+                ("nobody", "secret", "NotFound"),
+                ("twin", "twin", "NotFound"),
+                ("*", "wonderland", "NotFound"),
+            ] {
+                let outcome = LdapDirectory::authenticate_with(&config, username, password)
+                    .await
+                    .unwrap();
+                assert_eq!(format!("{:?}", outcome), expected, "{}", username);
+            }
+
+            let mut config = config;
+            config.bind_password = "wrong".to_string();
+            assert!(matches!(
+                LdapDirectory::authenticate_with(&config, "alice", "wonderland").await,
+                Err(LdapError::Operation { code: 49, .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn test_map_group_roles() {
+        let config = LdapConfig {
+            group_roles: vec![
+                LdapGroupRole {
+                    domain: "built-in".to_string(),
+                    group: "CN=Admins,OU=Groups,DC=example,DC=org".to_string(),
+                    role: "R_ADMIN".to_string(),
+                },
+                LdapGroupRole {
+                    domain: "built-in".to_string(),
+                    group: fake::STAFF_GROUP.to_string(),
+                    role: "R_USER".to_string(),
+                },
+                LdapGroupRole {
+                    domain: "tenant".to_string(),
+                    group: fake::ADMINS_GROUP.to_string(),
+                    role: "R_TENANT_ADMIN".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+        let groups = vec![fake::ADMINS_GROUP.to_string()];
+
+        assert_eq!(
+            map_group_roles(&config, "built-in", &groups),
+            vec!["R_ADMIN"]
+        );
+        assert_eq!(
+            map_group_roles(&config, "tenant", &groups),
+            vec!["R_TENANT_ADMIN"]
+        );
+        assert!(map_group_roles(&config, "other", &groups).is_empty());
+    }
+}
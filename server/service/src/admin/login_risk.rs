@@ -0,0 +1,496 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Local, NaiveDateTime};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use server_config::{LoginRiskAction, LoginRiskConfig};
+use server_constant::definition::consts::LoginRiskSignal;
+use server_core::web::error::AppError;
+use server_global::global;
+use server_model::admin::{
+    entities::{
+        prelude::{SysDomain, SysUser, SysUserLoginLocation},
+        sys_domain::Column as SysDomainColumn,
+        sys_user_login_location::{
+            ActiveModel as SysUserLoginLocationActiveModel, Column as SysUserLoginLocationColumn,
+            Model as SysUserLoginLocationModel,
+        },
+    },
+    output::UserWithDomainAndOrgOutput,
+};
+use ulid::Ulid;
+
+use super::dto::sys_auth_dto::LoginContext;
+use crate::{
+    mailer::{self, MailMessage},
+    project_error, project_info,
+};
+
+/// 从 xdb 查询结果（`国家|区域|省份|城市|运营商`）解析的地理位置，未知的部分为空
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct GeoLocation {
+    pub country: String,
+    pub province: String,
+    pub city: String,
+}
+
+impl GeoLocation {
+    pub fn parse(address: &str) -> Self {
+        let parts: Vec<&str> = address.split('|').collect();
+        if parts.len() < 4 {
+            return Self::default();
+        }
+        let part = |index: usize| {
+            let value = parts[index].trim();
+            if value.is_empty() || value == "0" || value == "内网IP" {
+                String::new()
+            } else {
+                value.to_string()
+            }
+        };
+
+        Self {
+            country: part(0),
+            province: part(2),
+            city: part(3),
+        }
+    }
+
+    /// 内网地址或查询失败时国家未知，不参与地点相关的检测
+    pub fn is_known(&self) -> bool {
+        !self.country.is_empty()
+    }
+}
+
+/// 浏览器与操作系统组合，如 `Chrome/Windows`
+pub(crate) fn user_agent_family(user_agent: &str) -> String {
+    let browser = [
+        ("MicroMessenger/", "WeChat"),
+        ("Edg", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("FxiOS/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("CriOS/", "Chrome"),
+        ("Safari/", "Safari"),
+    ]
+    .iter()
+    .find(|(token, _)| user_agent.contains(token))
+    .map_or("Other", |(_, name)| name);
+    let os = [
+        ("Android", "Android"),
+        ("iPhone", "iOS"),
+        ("iPad", "iOS"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("Linux", "Linux"),
+    ]
+    .iter()
+    .find(|(token, _)| user_agent.contains(token))
+    .map_or("Other", |(_, name)| name);
+
+    format!("{}/{}", browser, os)
+}
+
+/// 异常登录检测结果
+#[derive(Debug)]
+pub(crate) struct LoginRiskAssessment {
+    pub signals: Vec<LoginRiskSignal>,
+    pub action: LoginRiskAction,
+}
+
+impl LoginRiskAssessment {
+    pub fn is_suspicious(&self) -> bool {
+        !self.signals.is_empty()
+    }
+}
+
+/// 异常登录检测：新地点、不可能的移动与新设备
+pub(crate) struct LoginRisk;
+
+impl LoginRisk {
+    async fn default_policy() -> Arc<LoginRiskConfig> {
+        global::get_config::<LoginRiskConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(LoginRiskConfig::default()))
+    }
+
+    /// 获取登录域的检测策略，域未配置时使用全局默认策略
+    pub async fn for_domain(
+        db: &DatabaseConnection,
+        domain: &str,
+    ) -> Result<LoginRiskConfig, AppError> {
+        let policy = SysDomain::find()
+            .filter(SysDomainColumn::Code.eq(domain))
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+            .and_then(|domain| domain.login_risk_policy)
+            .and_then(|policy| serde_json::from_value(policy).ok());
+
+        match policy {
+            Some(policy) => Ok(policy),
+            None => Ok(Self::default_policy().await.as_ref().clone()),
+        }
+    }
+
+    /// 对比用户最近的登录地点评估本次登录
+    pub async fn evaluate(
+        db: &DatabaseConnection,
+        user: &UserWithDomainAndOrgOutput,
+        context: &LoginContext,
+    ) -> Result<LoginRiskAssessment, AppError> {
+        let policy = Self::for_domain(db, &user.domain_code).await?;
+        if !policy.enabled {
+            return Ok(LoginRiskAssessment {
+                signals: Vec::new(),
+                action: policy.action,
+            });
+        }
+
+        let history = Self::history(db, &policy, &user.id).await?;
+        Ok(LoginRiskAssessment {
+            signals: detect(
+                &policy,
+                &history,
+                &GeoLocation::parse(&context.address),
+                &user_agent_family(&context.user_agent),
+                Local::now().naive_local(),
+            ),
+            action: policy.action,
+        })
+    }
+
+    /// 登录成功后记录登录地点，检测到异常时记录安全事件并按策略通知用户
+    ///
+    /// 只保留策略要求的最近条数，记录失败不影响登录
+    pub async fn record(
+        db: &DatabaseConnection,
+        user: &UserWithDomainAndOrgOutput,
+        context: &LoginContext,
+    ) {
+        if let Err(e) = Self::try_record(db, user, context).await {
+            project_error!("Failed to record login location for {}: {:?}", user.id, e);
+        }
+    }
+
+    async fn try_record(
+        db: &DatabaseConnection,
+        user: &UserWithDomainAndOrgOutput,
+        context: &LoginContext,
+    ) -> Result<(), AppError> {
+        let policy = Self::for_domain(db, &user.domain_code).await?;
+        if !policy.enabled {
+            return Ok(());
+        }
+
+        let now = Local::now().naive_local();
+        let location = GeoLocation::parse(&context.address);
+        let device = user_agent_family(&context.user_agent);
+        let history = Self::history(db, &policy, &user.id).await?;
+        let signals = detect(&policy, &history, &location, &device, now);
+
+        SysUserLoginLocationActiveModel {
+            id: Set(Ulid::new().to_string()),
+            user_id: Set(user.id.clone()),
+            ip: Set(context.client_ip.clone()),
+            country: Set(location.country.clone()),
+            province: Set(location.province.clone()),
+            city: Set(location.city.clone()),
+            user_agent_family: Set(device.clone()),
+            risk_signals: Set((!signals.is_empty()).then(|| join_signals(&signals))),
+            login_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .map_err(AppError::from)?;
+
+        let expired_ids: Vec<String> = SysUserLoginLocation::find()
+            .select_only()
+            .column(SysUserLoginLocationColumn::Id)
+            .filter(SysUserLoginLocationColumn::UserId.eq(&user.id))
+            .order_by_desc(SysUserLoginLocationColumn::LoginAt)
+            .offset(u64::from(policy.history_size.max(1)))
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(AppError::from)?;
+        if !expired_ids.is_empty() {
+            SysUserLoginLocation::delete_many()
+                .filter(SysUserLoginLocationColumn::Id.is_in(expired_ids))
+                .exec(db)
+                .await
+                .map_err(AppError::from)?;
+        }
+
+        if signals.is_empty() {
+            return Ok(());
+        }
+        project_info!(
+            "Suspicious login for user {} ({}) from {} [{}|{}|{}] with {}: {}",
+            user.username,
+            user.id,
+            context.client_ip,
+            location.country,
+            location.province,
+            location.city,
+            device,
+            join_signals(&signals)
+        );
+        if policy.action == LoginRiskAction::Notify {
+            Self::notify(db, user, context, &location, &device, now).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn history(
+        db: &DatabaseConnection,
+        policy: &LoginRiskConfig,
+        user_id: &str,
+    ) -> Result<Vec<SysUserLoginLocationModel>, AppError> {
+        SysUserLoginLocation::find()
+            .filter(SysUserLoginLocationColumn::UserId.eq(user_id))
+            .order_by_desc(SysUserLoginLocationColumn::LoginAt)
+            .limit(u64::from(policy.history_size.max(1)))
+            .all(db)
+            .await
+            .map_err(AppError::from)
+    }
+
+    async fn notify(
+        db: &DatabaseConnection,
+        user: &UserWithDomainAndOrgOutput,
+        context: &LoginContext,
+        location: &GeoLocation,
+        device: &str,
+        now: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        let Some(email) = SysUser::find_by_id(&user.id)
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+            .and_then(|user| user.email)
+            .filter(|email| !email.is_empty())
+        else {
+            return Ok(());
+        };
+
+        let message = MailMessage {
+            to: email,
+            subject: "New sign-in to your account".to_string(),
+            body: format!(
+                "Hello {},\n\nYour account was signed in at {} from {} ({} {} {}) using {}.\n\n\
+                 If this was not you, change your password immediately and review your active \
+                 sessions.\n",
+                user.nick_name,
+                now.format("%Y-%m-%d %H:%M:%S"),
+                context.client_ip,
+                location.country,
+                location.province,
+                location.city,
+                device
+            ),
+        };
+        if let Err(e) = mailer::mailer().await.send(&message).await {
+            project_error!("Failed to send suspicious login email: {}", e);
+        }
+
+        Ok(())
+    }
+}
+
+fn join_signals(signals: &[LoginRiskSignal]) -> String {
+    signals
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<&str>>()
+        .join(",")
+}
+
+/// 检测异常信号，`history` 按登录时间倒序，没有历史记录的首次登录不视为异常
+fn detect(
+    policy: &LoginRiskConfig,
+    history: &[SysUserLoginLocationModel],
+    location: &GeoLocation,
+    device: &str,
+    now: NaiveDateTime,
+) -> Vec<LoginRiskSignal> {
+    let mut signals = Vec::new();
+    if history.is_empty() {
+        return signals;
+    }
+
+    if location.is_known() {
+        if policy.detect_new_location
+            && !history.iter().any(|entry| {
+                entry.country == location.country && entry.province == location.province
+            })
+        {
+            signals.push(LoginRiskSignal::NewLocation);
+        }
+
+        let last_known = history.iter().find(|entry| !entry.country.is_empty());
+        if let Some(last) = last_known {
+            if policy.impossible_travel_window > 0
+                && last.country != location.country
+                && now - last.login_at < Duration::seconds(policy.impossible_travel_window as i64)
+            {
+                signals.push(LoginRiskSignal::ImpossibleTravel);
+            }
+        }
+    }
+
+    if policy.detect_new_device
+        && !history
+            .iter()
+            .any(|entry| entry.user_agent_family == device)
+    {
+        signals.push(LoginRiskSignal::NewDevice);
+    }
+
+    signals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                                  (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) \
+                                 AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 \
+                                 Mobile/15E148 Safari/604.1";
+
+    fn entry(
+        country: &str,
+        province: &str,
+        device: &str,
+        login_at: NaiveDateTime,
+    ) -> SysUserLoginLocationModel {
+        SysUserLoginLocationModel {
+            id: Ulid::new().to_string(),
+            user_id: "user".to_string(),
+            ip: "1.1.1.1".to_string(),
+            country: country.to_string(),
+            province: province.to_string(),
+            city: String::new(),
+            user_agent_family: device.to_string(),
+            risk_signals: None,
+            login_at,
+        }
+    }
+
+    fn location(country: &str, province: &str) -> GeoLocation {
+        GeoLocation {
+            country: country.to_string(),
+            province: province.to_string(),
+            city: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(
+            GeoLocation::parse("中国|0|广东省|深圳市|电信"),
+            GeoLocation {
+                city: "深圳市".to_string(),
+                ..location("中国", "广东省")
+            }
+        );
+        assert!(!GeoLocation::parse("0|0|0|内网IP|内网IP").is_known());
+        assert!(!GeoLocation::parse("Unknown Location").is_known());
+    }
+
+    #[test]
+    fn test_user_agent_family() {
+        assert_eq!(user_agent_family(CHROME_WINDOWS), "Chrome/Windows");
+        assert_eq!(user_agent_family(SAFARI_IPHONE), "Safari/iOS");
+        assert_eq!(
+            user_agent_family(
+                "Mozilla/5.0 (Windows NT 10.0) AppleWebKit/537.36 Chrome/120.0 Safari/537.36 \
+                 Edg/120.0"
+            ),
+            "Edge/Windows"
+        );
+        assert_eq!(user_agent_family("curl/8.0"), "Other/Other");
+    }
+
+    #[test]
+    fn test_detect() {
+        let policy = LoginRiskConfig::default();
+        let now = Local::now().naive_local();
+        let history = vec![
+            entry(
+                "中国",
+                "广东省",
+                "Chrome/Windows",
+                now - Duration::minutes(10),
+            ),
+            entry("中国", "北京", "Safari/iOS", now - Duration::days(3)),
+        ];
+
+        // 首次登录没有可比较的记录
+        assert!(detect(&policy, &[], &location("美国", ""), "Other/Other", now).is_empty());
+        assert!(detect(
+            &policy,
+            &history,
+            &location("中国", "北京"),
+            "Safari/iOS",
+            now
+        )
+        .is_empty());
+        assert_eq!(
+            detect(
+                &policy,
+                &history,
+                &location("中国", "上海"),
+                "Chrome/Windows",
+                now
+            ),
+            vec![LoginRiskSignal::NewLocation]
+        );
+        assert_eq!(
+            detect(
+                &policy,
+                &history,
+                &location("美国", "加利福尼亚"),
+                "Firefox/Linux",
+                now
+            ),
+            vec![
+                LoginRiskSignal::NewLocation,
+                LoginRiskSignal::ImpossibleTravel,
+                LoginRiskSignal::NewDevice
+            ]
+        );
+        // 内网地址只检测设备
+        assert_eq!(
+            detect(
+                &policy,
+                &history,
+                &GeoLocation::default(),
+                "Firefox/Linux",
+                now
+            ),
+            vec![LoginRiskSignal::NewDevice]
+        );
+
+        let later = now + Duration::hours(2);
+        let relaxed = LoginRiskConfig {
+            detect_new_location: false,
+            detect_new_device: false,
+            ..policy
+        };
+        assert!(detect(
+            &relaxed,
+            &history,
+            &location("美国", ""),
+            "Firefox/Linux",
+            later
+        )
+        .is_empty());
+    }
+}
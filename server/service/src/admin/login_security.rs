@@ -0,0 +1,265 @@
+use std::{net::IpAddr, str::FromStr, sync::Arc, time::Duration};
+
+use chrono::{Local, NaiveTime};
+use ipnet::IpNet;
+use server_config::{LoginSecurityConfig, LoginTimeWindow};
+use server_core::web::error::AppError;
+use server_global::global;
+
+use super::sys_login_security_error::LoginSecurityError;
+use crate::helper::cache_helper;
+
+const FAILED_ATTEMPTS_KEY_PREFIX: &str = "soybean:login:failed:user:";
+const IP_FAILED_ATTEMPTS_KEY_PREFIX: &str = "soybean:login:failed:ip:";
+const LOCK_KEY_PREFIX: &str = "soybean:login:lock:";
+const LOCK_LEVEL_KEY_PREFIX: &str = "soybean:login:lock_level:";
+
+/// 登录安全策略：失败计数、账号锁定、IP 黑名单与登录时间段
+pub(crate) struct LoginSecurity;
+
+impl LoginSecurity {
+    async fn config() -> Arc<LoginSecurityConfig> {
+        global::get_config::<LoginSecurityConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(LoginSecurityConfig::default()))
+    }
+
+    /// 登录前检查，返回拒绝登录的原因
+    pub async fn check(
+        domain: &str,
+        username: &str,
+        ip: &str,
+    ) -> Result<Option<LoginSecurityError>, AppError> {
+        let config = Self::config().await;
+
+        if Self::is_ip_denied(&config.ip_deny_list, ip) {
+            return Ok(Some(LoginSecurityError::IpBlocked));
+        }
+
+        if !Self::is_within_login_windows(&config.allowed_login_windows, Local::now().time()) {
+            return Ok(Some(LoginSecurityError::OutsideLoginWindow));
+        }
+
+        if let Some(remaining) =
+            cache_helper::ttl(&Self::account_key(LOCK_KEY_PREFIX, domain, username)).await?
+        {
+            return Ok(Some(LoginSecurityError::AccountLocked(
+                remaining.as_secs().max(1),
+            )));
+        }
+
+        if config.max_ip_failed_attempts > 0 {
+            let ip_failures =
+                cache_helper::get(&format!("{}{}", IP_FAILED_ATTEMPTS_KEY_PREFIX, ip))
+                    .await?
+                    .and_then(|count| count.parse::<u32>().ok())
+                    .unwrap_or(0);
+            if ip_failures >= config.max_ip_failed_attempts {
+                return Ok(Some(LoginSecurityError::TooManyIpAttempts));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// 记录一次凭证错误，账号失败次数达到阈值时锁定账号
+    ///
+    /// 每次锁定的时长按倍数递增，直到达到上限
+    pub async fn record_failure(domain: &str, username: &str, ip: &str) -> Result<(), AppError> {
+        let config = Self::config().await;
+        let window = Duration::from_secs(config.failure_window);
+
+        cache_helper::incr(&format!("{}{}", IP_FAILED_ATTEMPTS_KEY_PREFIX, ip), window).await?;
+
+        let failed_key = Self::account_key(FAILED_ATTEMPTS_KEY_PREFIX, domain, username);
+        let failures = cache_helper::incr(&failed_key, window).await?;
+        if config.max_failed_attempts == 0 || failures < i64::from(config.max_failed_attempts) {
+            return Ok(());
+        }
+
+        let level_key = Self::account_key(LOCK_LEVEL_KEY_PREFIX, domain, username);
+        let level = cache_helper::get(&level_key)
+            .await?
+            .and_then(|level| level.parse::<u32>().ok())
+            .unwrap_or(0)
+            + 1;
+        let lock_duration = Self::lockout_duration(&config, level);
+
+        cache_helper::set_ex(
+            &Self::account_key(LOCK_KEY_PREFIX, domain, username),
+            "1",
+            lock_duration,
+        )
+        .await?;
+        // 锁定级别在本次锁定结束后继续保留一个锁定时长上限，期间再次锁定会延长锁定时间
+        cache_helper::set_ex(
+            &level_key,
+            &level.to_string(),
+            lock_duration + Duration::from_secs(config.max_lockout_duration),
+        )
+        .await?;
+        cache_helper::del(&failed_key).await
+    }
+
+    /// 统计窗口内账号和 IP 的失败次数
+    pub async fn failure_counts(
+        domain: &str,
+        username: &str,
+        ip: &str,
+    ) -> Result<(u32, u32), AppError> {
+        let account_failures = cache_helper::get(&Self::account_key(
+            FAILED_ATTEMPTS_KEY_PREFIX,
+            domain,
+            username,
+        ))
+        .await?
+        .and_then(|count| count.parse::<u32>().ok())
+        .unwrap_or(0);
+        let ip_failures = cache_helper::get(&format!("{}{}", IP_FAILED_ATTEMPTS_KEY_PREFIX, ip))
+            .await?
+            .and_then(|count| count.parse::<u32>().ok())
+            .unwrap_or(0);
+
+        Ok((account_failures, ip_failures))
+    }
+
+    /// 登录成功后清除账号的失败计数和锁定级别
+    pub async fn reset(domain: &str, username: &str) -> Result<(), AppError> {
+        cache_helper::del(&Self::account_key(
+            FAILED_ATTEMPTS_KEY_PREFIX,
+            domain,
+            username,
+        ))
+        .await?;
+        cache_helper::del(&Self::account_key(LOCK_LEVEL_KEY_PREFIX, domain, username)).await
+    }
+
+    fn account_key(prefix: &str, domain: &str, username: &str) -> String {
+        format!("{}{}:{}", prefix, domain, username)
+    }
+
+    fn lockout_duration(config: &LoginSecurityConfig, level: u32) -> Duration {
+        let multiplier = u64::from(config.lockout_backoff_multiplier.max(1))
+            .saturating_pow(level.saturating_sub(1));
+        Duration::from_secs(
+            config
+                .lockout_duration
+                .saturating_mul(multiplier)
+                .min(config.max_lockout_duration.max(config.lockout_duration)),
+        )
+    }
+
+    fn is_ip_denied(deny_list: &[String], ip: &str) -> bool {
+        let Ok(ip) = IpAddr::from_str(ip) else {
+            return false;
+        };
+
+        deny_list.iter().any(|entry| {
+            let entry = entry.trim();
+            if entry.contains('/') {
+                IpNet::from_str(entry)
+                    .map(|net| net.contains(&ip))
+                    .unwrap_or(false)
+            } else {
+                IpAddr::from_str(entry)
+                    .map(|denied| denied == ip)
+                    .unwrap_or(false)
+            }
+        })
+    }
+
+    /// 未配置或配置均无效时不限制登录时间
+    fn is_within_login_windows(windows: &[LoginTimeWindow], now: NaiveTime) -> bool {
+        let windows: Vec<(NaiveTime, NaiveTime)> = windows
+            .iter()
+            .filter_map(|window| {
+                let start = NaiveTime::parse_from_str(&window.start, "%H:%M").ok()?;
+                let end = NaiveTime::parse_from_str(&window.end, "%H:%M").ok()?;
+                Some((start, end))
+            })
+            .collect();
+
+        if windows.is_empty() {
+            return true;
+        }
+
+        windows.iter().any(|&(start, end)| {
+            if start <= end {
+                start <= now && now < end
+            } else {
+                // 跨越午夜的时间段
+                now >= start || now < end
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: &str, end: &str) -> LoginTimeWindow {
+        LoginTimeWindow {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    fn time(value: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(value, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_is_ip_denied() {
+        let deny_list = vec!["10.0.0.0/8".to_string(), "192.168.1.10".to_string()];
+        assert!(LoginSecurity::is_ip_denied(&deny_list, "10.1.2.3"));
+        assert!(LoginSecurity::is_ip_denied(&deny_list, "192.168.1.10"));
+        assert!(!LoginSecurity::is_ip_denied(&deny_list, "192.168.1.11"));
+        assert!(!LoginSecurity::is_ip_denied(&deny_list, "unknown"));
+    }
+
+    #[test]
+    fn test_is_within_login_windows() {
+        assert!(LoginSecurity::is_within_login_windows(&[], time("03:00")));
+
+        let day = vec![window("08:00", "20:00")];
+        assert!(LoginSecurity::is_within_login_windows(&day, time("08:00")));
+        assert!(!LoginSecurity::is_within_login_windows(&day, time("20:00")));
+
+        let night = vec![window("22:00", "06:00")];
+        assert!(LoginSecurity::is_within_login_windows(
+            &night,
+            time("23:30")
+        ));
+        assert!(LoginSecurity::is_within_login_windows(
+            &night,
+            time("05:59")
+        ));
+        assert!(!LoginSecurity::is_within_login_windows(
+            &night,
+            time("12:00")
+        ));
+    }
+
+    #[test]
+    fn test_lockout_duration() {
+        let config = LoginSecurityConfig {
+            lockout_duration: 300,
+            lockout_backoff_multiplier: 2,
+            max_lockout_duration: 1000,
+            ..Default::default()
+        };
+        assert_eq!(
+            LoginSecurity::lockout_duration(&config, 1),
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            LoginSecurity::lockout_duration(&config, 2),
+            Duration::from_secs(600)
+        );
+        assert_eq!(
+            LoginSecurity::lockout_duration(&config, 3),
+            Duration::from_secs(1000)
+        );
+    }
+}
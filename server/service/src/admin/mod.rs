@@ -1,8 +1,13 @@
+pub use access_key_sync::{
+    access_key_policies_changed, access_key_sync_clients, access_key_sync_listener,
+};
+pub use access_key_usage::access_key_usage_listener;
 pub use errors::*;
 pub use server_model::admin::{
     entities::{
         prelude::{SysDomain, SysEndpoint, SysMenu, SysRole, SysUser},
         sys_access_key::Model as SysAccessKeyModel,
+        sys_access_key_usage::Model as SysAccessKeyUsageModel,
         sys_domain::Model as SysDomainModel,
         sys_endpoint::Model as SysEndpointModel,
         sys_login_log::Model as SysLoginLogModel,
@@ -18,6 +23,9 @@ pub use sys_access_key_service::{SysAccessKeyService, TAccessKeyService};
 pub use sys_auth_service::{
     auth_login_listener, jwt_created_listener, SysAuthService, TAuthService,
 };
+pub use sys_captcha_service::{
+    set_captcha_provider, CaptchaProvider, ImageCaptchaProvider, SysCaptchaService, TCaptchaService,
+};
 pub use sys_domain_service::{SysDomainService, TDomainService};
 pub use sys_endpoint_service::{SysEndpointService, TEndpointService};
 pub use sys_login_log_service::{SysLoginLogService, TLoginLogService};
@@ -26,20 +34,38 @@ pub use sys_operation_log_service::{
     sys_operation_log_listener, SysOperationLogService, TOperationLogService,
 };
 pub use sys_organization_service::{SysOrganizationService, TOrganizationService};
+pub use sys_password_reset_service::{SysPasswordResetService, TPasswordResetService};
 pub use sys_role_service::{SysRoleService, TRoleService};
+pub use sys_token_service::{SysTokenService, TTokenService};
+pub use sys_totp_service::{SysTotpService, TTotpService};
 pub use sys_user_service::{SysUserService, TUserService};
+pub use sys_webauthn_service::{SysWebAuthnService, TWebAuthnService};
+mod access_key_sync;
+mod access_key_usage;
 pub mod dto;
 pub mod errors;
+mod external_identity;
+mod ldap_directory;
+mod login_risk;
+mod login_security;
+mod oidc;
+mod password_policy;
 mod sys_access_key_service;
 mod sys_auth_service;
+mod sys_captcha_service;
 mod sys_domain_service;
 mod sys_endpoint_service;
 mod sys_login_log_service;
 mod sys_menu_service;
 mod sys_operation_log_service;
 mod sys_organization_service;
+mod sys_password_reset_service;
 mod sys_role_service;
+mod sys_token_service;
+mod sys_totp_service;
 mod sys_user_service;
+mod sys_webauthn_service;
+mod webauthn;
 
 mod event_handlers;
 mod events;
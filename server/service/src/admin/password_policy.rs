@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Local, NaiveDateTime};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use server_config::PasswordPolicyConfig;
+use server_core::web::error::AppError;
+use server_global::global;
+use server_model::admin::entities::{
+    prelude::{SysDomain, SysUserPasswordHistory},
+    sys_domain::Column as SysDomainColumn,
+    sys_user_password_history::{
+        ActiveModel as SysUserPasswordHistoryActiveModel, Column as SysUserPasswordHistoryColumn,
+    },
+};
+use server_utils::{PasswordCharClasses, SecureUtil};
+use ulid::Ulid;
+
+use super::sys_user_error::UserError;
+
+/// 密码策略：复杂度、历史密码与有效期
+pub(crate) struct PasswordPolicy;
+
+impl PasswordPolicy {
+    async fn default_policy() -> Arc<PasswordPolicyConfig> {
+        global::get_config::<PasswordPolicyConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(PasswordPolicyConfig::default()))
+    }
+
+    /// 获取登录域的密码策略，域未配置时使用全局默认策略
+    pub async fn for_domain(
+        db: &DatabaseConnection,
+        domain: &str,
+    ) -> Result<PasswordPolicyConfig, AppError> {
+        let policy = SysDomain::find()
+            .filter(SysDomainColumn::Code.eq(domain))
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+            .and_then(|domain| domain.password_policy)
+            .and_then(|policy| serde_json::from_value(policy).ok());
+
+        match policy {
+            Some(policy) => Ok(policy),
+            None => Ok(Self::default_policy().await.as_ref().clone()),
+        }
+    }
+
+    /// 校验新密码：复杂度规则与最近使用过的密码
+    ///
+    /// `current_hash` 为用户当前的密码哈希，新建用户时为空
+    pub async fn validate(
+        db: &DatabaseConnection,
+        policy: &PasswordPolicyConfig,
+        user_id: Option<&str>,
+        username: &str,
+        password: &str,
+        current_hash: Option<&str>,
+    ) -> Result<(), AppError> {
+        Self::check_rules(policy, username, password)?;
+
+        let (Some(user_id), true) = (user_id, policy.history_count > 0) else {
+            return Ok(());
+        };
+        let history = SysUserPasswordHistory::find()
+            .filter(SysUserPasswordHistoryColumn::UserId.eq(user_id))
+            .order_by_desc(SysUserPasswordHistoryColumn::CreatedAt)
+            .limit(u64::from(policy.history_count))
+            .all(db)
+            .await
+            .map_err(AppError::from)?;
+
+        let reused = current_hash
+            .into_iter()
+            .chain(history.iter().map(|entry| entry.password.as_str()))
+            .any(|hash| {
+                matches!(
+                    SecureUtil::verify_password(password.as_bytes(), hash),
+                    Ok(true)
+                )
+            });
+        if reused {
+            return Err(UserError::PasswordReused.into());
+        }
+
+        Ok(())
+    }
+
+    /// 记录新密码，只保留策略要求的历史条数
+    pub async fn record(
+        db: &DatabaseConnection,
+        policy: &PasswordPolicyConfig,
+        user_id: &str,
+        password_hash: &str,
+    ) -> Result<(), AppError> {
+        SysUserPasswordHistoryActiveModel {
+            id: Set(Ulid::new().to_string()),
+            user_id: Set(user_id.to_string()),
+            password: Set(password_hash.to_string()),
+            created_at: Set(Local::now().naive_local()),
+        }
+        .insert(db)
+        .await
+        .map_err(AppError::from)?;
+
+        let expired_ids: Vec<String> = SysUserPasswordHistory::find()
+            .select_only()
+            .column(SysUserPasswordHistoryColumn::Id)
+            .filter(SysUserPasswordHistoryColumn::UserId.eq(user_id))
+            .order_by_desc(SysUserPasswordHistoryColumn::CreatedAt)
+            .offset(u64::from(policy.history_count.max(1)))
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(AppError::from)?;
+        if !expired_ids.is_empty() {
+            SysUserPasswordHistory::delete_many()
+                .filter(SysUserPasswordHistoryColumn::Id.is_in(expired_ids))
+                .exec(db)
+                .await
+                .map_err(AppError::from)?;
+        }
+
+        Ok(())
+    }
+
+    /// 密码是否已超过有效期，未记录修改时间的密码视为未过期
+    pub fn is_expired(
+        policy: &PasswordPolicyConfig,
+        changed_at: Option<NaiveDateTime>,
+        now: NaiveDateTime,
+    ) -> bool {
+        policy.max_age_days > 0
+            && changed_at.is_some_and(|changed_at| {
+                now - changed_at >= Duration::days(i64::from(policy.max_age_days))
+            })
+    }
+
+    fn check_rules(
+        policy: &PasswordPolicyConfig,
+        username: &str,
+        password: &str,
+    ) -> Result<(), UserError> {
+        let violation = |message: String| Err(UserError::PasswordPolicyViolation(message));
+
+        if password.chars().count() < policy.min_length {
+            return violation(format!(
+                "Password must be at least {} characters",
+                policy.min_length
+            ));
+        }
+
+        let classes = PasswordCharClasses::of(password);
+        let required = [
+            (
+                policy.require_uppercase,
+                classes.uppercase,
+                "an uppercase letter",
+            ),
+            (
+                policy.require_lowercase,
+                classes.lowercase,
+                "a lowercase letter",
+            ),
+            (policy.require_digit, classes.digit, "a digit"),
+            (
+                policy.require_special,
+                classes.special,
+                "a special character",
+            ),
+        ];
+        if let Some((_, _, name)) = required
+            .iter()
+            .find(|(required, present, _)| *required && !present)
+        {
+            return violation(format!("Password must contain {}", name));
+        }
+
+        if SecureUtil::password_strength(password) < policy.min_strength {
+            return violation("Password is too weak".to_string());
+        }
+
+        if policy.forbid_username
+            && !username.is_empty()
+            && password.to_lowercase().contains(&username.to_lowercase())
+        {
+            return violation("Password must not contain the username".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_check_rules() {
+        let policy = PasswordPolicyConfig {
+            require_uppercase: true,
+            ..Default::default()
+        };
+
+        assert!(PasswordPolicy::check_rules(&policy, "soybean", "Abc123").is_err());
+        assert!(PasswordPolicy::check_rules(&policy, "soybean", "abcdef123").is_err());
+        assert!(PasswordPolicy::check_rules(&policy, "soybean", "Soybean123").is_err());
+        assert!(PasswordPolicy::check_rules(&policy, "soybean", "Abcdef123").is_ok());
+    }
+
+    #[test]
+    fn test_check_rules_min_strength() {
+        let policy = PasswordPolicyConfig {
+            require_lowercase: false,
+            require_digit: false,
+            min_strength: 3,
+            ..Default::default()
+        };
+
+        assert!(PasswordPolicy::check_rules(&policy, "admin", "abcdefgh").is_err());
+        assert!(PasswordPolicy::check_rules(&policy, "admin", "Abcdefg1").is_ok());
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let policy = PasswordPolicyConfig {
+            max_age_days: 90,
+            ..Default::default()
+        };
+        let now = datetime("2024-12-31 00:00:00");
+
+        assert!(PasswordPolicy::is_expired(
+            &policy,
+            Some(datetime("2024-09-01 00:00:00")),
+            now
+        ));
+        assert!(!PasswordPolicy::is_expired(
+            &policy,
+            Some(datetime("2024-12-01 00:00:00")),
+            now
+        ));
+        assert!(!PasswordPolicy::is_expired(&policy, None, now));
+        assert!(!PasswordPolicy::is_expired(
+            &PasswordPolicyConfig::default(),
+            Some(datetime("2020-01-01 00:00:00")),
+            now
+        ));
+    }
+}
@@ -1,70 +1,119 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{Duration, Local};
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, DatabaseTransaction, EntityTrait, PaginatorTrait,
-    QueryFilter, Set, TransactionTrait,
-};
-use server_core::{
-    sign::ValidatorType,
-    web::{error::AppError, page::PaginatedData},
+    QueryFilter, QueryOrder, Set, TransactionTrait,
 };
+use server_config::AccessKeyConfig;
+use server_core::web::{error::AppError, page::PaginatedData};
+use server_global::global;
 use server_model::admin::{
     entities::{
-        prelude::SysAccessKey,
+        prelude::{SysAccessKey, SysAccessKeyUsage, SysEndpoint},
         sys_access_key::{
             ActiveModel as SysAccessKeyActiveModel, Column as SysAccessKeyColumn,
             Model as SysAccessKeyModel,
         },
+        sys_access_key_usage::{
+            Column as SysAccessKeyUsageColumn, Model as SysAccessKeyUsageModel,
+        },
+        sys_endpoint::Column as SysEndpointColumn,
+    },
+    input::{
+        AccessKeyPageRequest, AccessKeyScopeInput, AccessKeyUsageRequest, CreateAccessKeyInput,
+        UpdateAccessKeyInput,
     },
-    input::{AccessKeyPageRequest, CreateAccessKeyInput},
+    output::{AccessKeyOutput, AccessKeyPolicyOutput},
 };
 use ulid::Ulid;
 
 use crate::helper::db_helper;
 
-use super::sys_access_key_error::AccessKeyError;
+use super::{access_key_sync, sys_access_key_error::AccessKeyError};
+
+/// 使用统计默认查询的天数
+const DEFAULT_USAGE_DAYS: u32 = 30;
+/// 使用统计最多查询的天数
+const MAX_USAGE_DAYS: u32 = 366;
 
 #[async_trait]
 pub trait TAccessKeyService {
     async fn find_paginated_access_keys(
         &self,
         params: AccessKeyPageRequest,
-    ) -> Result<PaginatedData<SysAccessKeyModel>, AppError>;
+    ) -> Result<PaginatedData<AccessKeyOutput>, AppError>;
+    async fn get_access_key(&self, id: &str) -> Result<SysAccessKeyModel, AppError>;
     async fn create_access_key(
         &self,
         input: CreateAccessKeyInput,
     ) -> Result<SysAccessKeyModel, AppError>;
+    async fn update_access_key(
+        &self,
+        input: UpdateAccessKeyInput,
+    ) -> Result<SysAccessKeyModel, AppError>;
     async fn delete_access_key(&self, id: &str) -> Result<(), AppError>;
+
+    /// 生成新的 secret，旧 secret 在宽限期内继续有效
+    async fn rotate_access_key_secret(&self, id: &str) -> Result<SysAccessKeyModel, AppError>;
+
+    /// 立即停用轮换前的旧 secret，不再等待宽限期结束
+    async fn retire_previous_secret(&self, id: &str) -> Result<SysAccessKeyModel, AppError>;
+
+    /// 按天查询访问密钥最近的使用统计，按日期倒序
+    async fn find_access_key_usage(
+        &self,
+        id: &str,
+        params: AccessKeyUsageRequest,
+    ) -> Result<Vec<SysAccessKeyUsageModel>, AppError>;
+
+    /// 将授权范围展开为去重后的路径与方法，`sys_endpoint` ID 必须全部存在
+    async fn resolve_access_key_policies(
+        &self,
+        id: &str,
+        input: AccessKeyScopeInput,
+    ) -> Result<(SysAccessKeyModel, Vec<AccessKeyPolicyOutput>), AppError>;
+
+    /// 启动时从数据库加载全部启用的访问密钥到验证器，返回加载的数量
+    async fn load_access_keys(&self) -> Result<usize, AppError>;
 }
 
 #[derive(Clone)]
 pub struct SysAccessKeyService;
 
 impl SysAccessKeyService {
+    async fn config() -> Arc<AccessKeyConfig> {
+        global::get_config::<AccessKeyConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(AccessKeyConfig::default()))
+    }
+
+    async fn find_access_key(id: &str) -> Result<SysAccessKeyModel, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        SysAccessKey::find_by_id(id)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(AccessKeyError::AccessKeyNotFound))
+    }
+
     async fn create_access_key_in_transaction(
         &self,
         txn: &DatabaseTransaction,
         access_key: SysAccessKeyActiveModel,
     ) -> Result<SysAccessKeyModel, AppError> {
-        let result = access_key.insert(txn).await.map_err(AppError::from)?;
-
-        // 添加到验证器
-        server_core::sign::add_key(ValidatorType::Simple, &result.access_key_id, None).await;
-        server_core::sign::add_key(
-            ValidatorType::Complex,
-            &result.access_key_id,
-            Some(&result.access_key_secret),
-        )
-        .await;
-
-        Ok(result)
+        access_key.insert(txn).await.map_err(AppError::from)
     }
 
     async fn delete_access_key_in_transaction(
         &self,
         txn: &DatabaseTransaction,
         id: &str,
-    ) -> Result<(), AppError> {
+    ) -> Result<SysAccessKeyModel, AppError> {
         // 先获取 access key 信息
         let access_key = SysAccessKey::find_by_id(id)
             .one(txn)
@@ -78,11 +127,13 @@ impl SysAccessKeyService {
             .await
             .map_err(AppError::from)?;
 
-        // 从验证器中移除
-        server_core::sign::remove_key(ValidatorType::Simple, &access_key.access_key_id).await;
-        server_core::sign::remove_key(ValidatorType::Complex, &access_key.access_key_id).await;
+        SysAccessKeyUsage::delete_many()
+            .filter(SysAccessKeyUsageColumn::AccessKeyId.eq(&access_key.access_key_id))
+            .exec(txn)
+            .await
+            .map_err(AppError::from)?;
 
-        Ok(())
+        Ok(access_key)
     }
 }
 
@@ -91,7 +142,7 @@ impl TAccessKeyService for SysAccessKeyService {
     async fn find_paginated_access_keys(
         &self,
         params: AccessKeyPageRequest,
-    ) -> Result<PaginatedData<SysAccessKeyModel>, AppError> {
+    ) -> Result<PaginatedData<AccessKeyOutput>, AppError> {
         let db = db_helper::get_db_connection().await?;
         let mut query = SysAccessKey::find();
 
@@ -112,6 +163,28 @@ impl TAccessKeyService for SysAccessKeyService {
             .await
             .map_err(AppError::from)?;
 
+        let access_key_ids: Vec<String> = records
+            .iter()
+            .map(|access_key| access_key.access_key_id.clone())
+            .collect();
+        let mut today_usage: HashMap<String, SysAccessKeyUsageModel> = SysAccessKeyUsage::find()
+            .filter(SysAccessKeyUsageColumn::AccessKeyId.is_in(access_key_ids))
+            .filter(SysAccessKeyUsageColumn::UsageDate.eq(Local::now().date_naive()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|usage| (usage.access_key_id.clone(), usage))
+            .collect();
+
+        let records = records
+            .into_iter()
+            .map(|access_key| AccessKeyOutput {
+                today_usage: today_usage.remove(&access_key.access_key_id),
+                access_key,
+            })
+            .collect();
+
         Ok(PaginatedData {
             current: params.page_details.current,
             size: params.page_details.size,
@@ -120,6 +193,10 @@ impl TAccessKeyService for SysAccessKeyService {
         })
     }
 
+    async fn get_access_key(&self, id: &str) -> Result<SysAccessKeyModel, AppError> {
+        Self::find_access_key(id).await
+    }
+
     async fn create_access_key(
         &self,
         input: CreateAccessKeyInput,
@@ -135,6 +212,7 @@ impl TAccessKeyService for SysAccessKeyService {
             domain: Set(input.domain),
             status: Set(input.status),
             description: Set(input.description),
+            expires_at: Set(input.expires_at),
             access_key_id: Set(access_key_id),
             access_key_secret: Set(access_key_secret),
             created_at: Set(Local::now().naive_local()),
@@ -148,6 +226,8 @@ impl TAccessKeyService for SysAccessKeyService {
         {
             Ok(result) => {
                 txn.commit().await.map_err(AppError::from)?;
+                // 提交后再同步验证器，避免回滚时留下无效密钥
+                access_key_sync::key_changed(&result.access_key_id).await;
                 result
             },
             Err(e) => {
@@ -159,13 +239,46 @@ impl TAccessKeyService for SysAccessKeyService {
         Ok(result)
     }
 
+    async fn update_access_key(
+        &self,
+        input: UpdateAccessKeyInput,
+    ) -> Result<SysAccessKeyModel, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let access_key: SysAccessKeyActiveModel = SysAccessKey::find_by_id(&input.id)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(AccessKeyError::AccessKeyNotFound))?
+            .into();
+
+        let access_key = SysAccessKeyActiveModel {
+            domain: Set(input.access_key.domain),
+            status: Set(input.access_key.status),
+            description: Set(input.access_key.description),
+            expires_at: Set(input.access_key.expires_at),
+            ..access_key
+        };
+
+        let updated = access_key
+            .update(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        // 状态和过期时间的变化需要同步到各实例的验证器
+        access_key_sync::key_changed(&updated.access_key_id).await;
+
+        Ok(updated)
+    }
+
     async fn delete_access_key(&self, id: &str) -> Result<(), AppError> {
         let db = db_helper::get_db_connection().await?;
         let txn = db.begin().await.map_err(AppError::from)?;
 
         match self.delete_access_key_in_transaction(&txn, id).await {
-            Ok(_) => {
+            Ok(access_key) => {
                 txn.commit().await.map_err(AppError::from)?;
+                access_key_sync::key_changed(&access_key.access_key_id).await;
                 Ok(())
             },
             Err(e) => {
@@ -174,4 +287,115 @@ impl TAccessKeyService for SysAccessKeyService {
             },
         }
     }
+
+    async fn rotate_access_key_secret(&self, id: &str) -> Result<SysAccessKeyModel, AppError> {
+        let access_key = Self::find_access_key(id).await?;
+        let grace_period = Self::config().await.rotation_grace_period;
+        let now = Local::now().naive_local();
+
+        // 只保留一个旧 secret：宽限期内再次轮换时，更早的 secret 直接失效
+        let previous_secret = access_key.access_key_secret.clone();
+        let access_key = SysAccessKeyActiveModel {
+            access_key_secret: Set(format!("SK{}", Ulid::new().to_string())),
+            previous_access_key_secret: Set(Some(previous_secret)),
+            previous_secret_expires_at: Set(Some(now + Duration::seconds(grace_period as i64))),
+            rotated_at: Set(Some(now)),
+            ..access_key.into()
+        };
+
+        let db = db_helper::get_db_connection().await?;
+        let updated = access_key
+            .update(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        access_key_sync::key_changed(&updated.access_key_id).await;
+
+        Ok(updated)
+    }
+
+    async fn retire_previous_secret(&self, id: &str) -> Result<SysAccessKeyModel, AppError> {
+        let access_key = Self::find_access_key(id).await?;
+        if access_key.previous_access_key_secret.is_none() {
+            return Err(AccessKeyError::NoPreviousSecret.into());
+        }
+
+        let access_key = SysAccessKeyActiveModel {
+            previous_access_key_secret: Set(None),
+            previous_secret_expires_at: Set(None),
+            ..access_key.into()
+        };
+
+        let db = db_helper::get_db_connection().await?;
+        let updated = access_key
+            .update(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        access_key_sync::key_changed(&updated.access_key_id).await;
+
+        Ok(updated)
+    }
+
+    async fn find_access_key_usage(
+        &self,
+        id: &str,
+        params: AccessKeyUsageRequest,
+    ) -> Result<Vec<SysAccessKeyUsageModel>, AppError> {
+        let access_key = Self::find_access_key(id).await?;
+        let days = params
+            .days
+            .unwrap_or(DEFAULT_USAGE_DAYS)
+            .clamp(1, MAX_USAGE_DAYS);
+        let since = Local::now().date_naive() - Duration::days(i64::from(days) - 1);
+
+        let db = db_helper::get_db_connection().await?;
+        SysAccessKeyUsage::find()
+            .filter(SysAccessKeyUsageColumn::AccessKeyId.eq(access_key.access_key_id))
+            .filter(SysAccessKeyUsageColumn::UsageDate.gte(since))
+            .order_by_desc(SysAccessKeyUsageColumn::UsageDate)
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)
+    }
+
+    async fn resolve_access_key_policies(
+        &self,
+        id: &str,
+        input: AccessKeyScopeInput,
+    ) -> Result<(SysAccessKeyModel, Vec<AccessKeyPolicyOutput>), AppError> {
+        let access_key = Self::find_access_key(id).await?;
+        let mut policies: BTreeSet<AccessKeyPolicyOutput> = input
+            .policies
+            .into_iter()
+            .map(|policy| AccessKeyPolicyOutput {
+                path: policy.path,
+                method: policy.method.to_uppercase(),
+            })
+            .collect();
+
+        let endpoint_ids: BTreeSet<String> = input.endpoint_ids.into_iter().collect();
+        if !endpoint_ids.is_empty() {
+            let db = db_helper::get_db_connection().await?;
+            let endpoints = SysEndpoint::find()
+                .filter(SysEndpointColumn::Id.is_in(endpoint_ids.iter().cloned()))
+                .all(db.as_ref())
+                .await
+                .map_err(AppError::from)?;
+            if endpoints.len() != endpoint_ids.len() {
+                return Err(AccessKeyError::EndpointNotFound.into());
+            }
+
+            policies.extend(endpoints.into_iter().map(|endpoint| AccessKeyPolicyOutput {
+                path: endpoint.path,
+                method: endpoint.method,
+            }));
+        }
+
+        Ok((access_key, policies.into_iter().collect()))
+    }
+
+    async fn load_access_keys(&self) -> Result<usize, AppError> {
+        access_key_sync::load_enabled_keys().await
+    }
 }
@@ -1,30 +1,44 @@
-use std::any::Any;
+use std::{any::Any, str::FromStr, sync::Arc, time::Duration as StdDuration};
 
 use async_trait::async_trait;
+use chrono::{Duration, Local};
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect,
-    RelationTrait,
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, JoinType,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use server_config::{JwtConfig, LoginRiskAction, MfaConfig, OidcProviderConfig};
+use server_constant::definition::{
+    consts::{LoginFailureReason, LoginIdentifierType, TokenStatus},
+    Audience,
 };
-use server_constant::definition::Audience;
 use server_core::web::{
-    auth::Claims,
+    auth::{Actor, Claims, User},
     error::AppError,
     jwt::{JwtError, JwtUtils},
 };
 use server_global::global;
 use server_model::admin::{
     entities::{
-        prelude::{SysRole, SysUser},
+        prelude::{SysDomain, SysRole, SysTokens, SysUser},
         sea_orm_active_enums::Status,
-        sys_domain::Column as SysDomainColumn,
+        sys_domain::{Column as SysDomainColumn, Model as SysDomainModel},
         sys_menu::{Column as SysMenuColumn, Entity as SysMenuEntity, Model as SysMenuModel},
         sys_role::{Column as SysRoleColumn, Entity as SysRoleEntity, Relation as SysRoleRelation},
         sys_role_menu::{Column as SysRoleMenuColumn, Entity as SysRoleMenuEntity},
+        sys_tokens::{Column as SysTokensColumn, Model as SysTokensModel},
         sys_user::{Column as SysUserColumn, Relation as SysUserRelation},
         sys_user_role::Relation as SysUserRoleRelation,
     },
-    input::LoginInput,
-    output::{AuthOutput, MenuRoute, RouteMeta, UserRoute, UserWithDomainAndOrgOutput},
+    input::{
+        LoginInput, MfaEnrollInput, MfaVerifyInput, OidcCallbackInput, PasskeyLoginInput,
+        PasskeyLoginOptionsInput, PasskeyMfaOptionsInput, PasskeyMfaVerifyInput, RefreshTokenInput,
+    },
+    output::{
+        AuthOutput, LoginOutput, MenuRoute, MfaChallengeOutput, OidcAuthorizeOutput,
+        PasskeyRequestOptionsOutput, RouteMeta, TotpEnrollOutput, UserInfoOutput, UserRoute,
+        UserWithDomainAndOrgOutput,
+    },
 };
 use server_utils::{SecureUtil, TreeBuilder};
 use thiserror::Error;
@@ -33,11 +47,33 @@ use tracing::{error, instrument};
 use ulid::Ulid;
 
 use super::{
-    dto::sys_auth_dto::LoginContext, event_handlers::auth_event_handler::AuthEventHandler,
+    dto::sys_auth_dto::LoginContext,
+    event_handlers::auth_event_handler::AuthEventHandler,
+    events::access_token_event::AccessTokenEvent,
+    external_identity::{ExternalIdentity, ExternalProfile},
+    ldap_directory::{DirectoryOutcome, LdapDirectory, LDAP_PROVIDER},
+    login_risk::LoginRisk,
+    login_security::LoginSecurity,
+    oidc::{IdTokenClaims, OidcClient},
+    password_policy::PasswordPolicy,
+    sys_captcha_service::{CaptchaCheck, SysCaptchaService},
+    sys_token_service::{SysTokenService, TTokenService},
+    sys_totp_service::SysTotpService,
+    sys_webauthn_service::SysWebAuthnService,
+    webauthn::WebAuthn,
 };
 use crate::{
-    admin::{event_handlers::auth_event_handler::AuthEvent, sys_user_error::UserError},
-    helper::db_helper,
+    admin::{
+        event_handlers::auth_event_handler::{AuthEvent, LoginFailedEvent},
+        sys_domain_error::DomainError,
+        sys_impersonation_error::ImpersonationError,
+        sys_mfa_error::MfaError,
+        sys_oidc_error::OidcError,
+        sys_token_error::TokenError,
+        sys_user_error::UserError,
+        sys_webauthn_error::WebAuthnError,
+    },
+    helper::{cache_helper, db_helper},
     project_error, project_info,
 };
 
@@ -53,8 +89,32 @@ macro_rules! select_user_with_domain_and_org_info {
             .column_as(SysUserColumn::Avatar, "avatar")
             .column_as(SysDomainColumn::Code, "domain_code")
             .column_as(SysDomainColumn::Name, "domain_name")
+            .column_as(SysUserColumn::PasswordChangedAt, "password_changed_at")
+            .column_as(SysUserColumn::MustChangePassword, "must_change_password")
     }};
 }
+const MFA_CHALLENGE_KEY_PREFIX: &str = "soybean:mfa:challenge:";
+const MFA_ATTEMPTS_KEY_PREFIX: &str = "soybean:mfa:attempts:";
+const MFA_METHOD_TOTP: &str = "totp";
+const MFA_METHOD_PASSKEY: &str = "passkey";
+/// 模拟登录签发的令牌在令牌表与登录日志中的登录类型
+pub(crate) const IMPERSONATION_LOGIN_TYPE: &str = "IMPERSONATION";
+/// 内置域的管理员可以模拟任意域的用户
+const BUILT_IN_DOMAIN: &str = "built-in";
+
+/// 密码校验通过、等待两步验证的登录挑战
+#[derive(Serialize, Deserialize)]
+struct MfaChallenge {
+    user_id: String,
+    username: String,
+    identifier: String,
+    identifier_type: String,
+    domain: String,
+    audience: String,
+    login_type: String,
+    enrollment_required: bool,
+}
+
 #[derive(Error, Debug)]
 pub enum EventError {
     #[error("Failed to send event: {0}")]
@@ -69,8 +129,72 @@ pub trait TAuthService: Send + Sync {
         &self,
         input: LoginInput,
         context: LoginContext,
+    ) -> Result<LoginOutput, AppError>;
+
+    async fn verify_mfa(
+        &self,
+        input: MfaVerifyInput,
+        context: LoginContext,
     ) -> Result<AuthOutput, AppError>;
 
+    async fn enroll_mfa(&self, input: MfaEnrollInput) -> Result<TotpEnrollOutput, AppError>;
+
+    /// 生成通行密钥登录挑战
+    async fn passkey_login_options(
+        &self,
+        input: PasskeyLoginOptionsInput,
+        context: LoginContext,
+    ) -> Result<PasskeyRequestOptionsOutput, AppError>;
+
+    /// 通行密钥登录，认证器已完成用户验证，不再要求两步验证
+    async fn passkey_login(
+        &self,
+        input: PasskeyLoginInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError>;
+
+    /// 生成以通行密钥完成两步验证的挑战
+    async fn passkey_mfa_options(
+        &self,
+        input: PasskeyMfaOptionsInput,
+    ) -> Result<PasskeyRequestOptionsOutput, AppError>;
+
+    async fn verify_passkey_mfa(
+        &self,
+        input: PasskeyMfaVerifyInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError>;
+
+    async fn oidc_authorize(&self, provider: &str) -> Result<OidcAuthorizeOutput, AppError>;
+
+    async fn oidc_login(
+        &self,
+        provider: &str,
+        input: OidcCallbackInput,
+        context: LoginContext,
+    ) -> Result<LoginOutput, AppError>;
+
+    async fn refresh_token(
+        &self,
+        input: RefreshTokenInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError>;
+
+    async fn logout(&self, jti: &str) -> Result<(), AppError>;
+
+    /// 以目标用户身份签发令牌，令牌携带发起模拟的管理员（`act` 声明）
+    async fn impersonate(
+        &self,
+        actor: &User,
+        user_id: &str,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError>;
+
+    /// 结束当前模拟会话，吊销模拟令牌
+    async fn stop_impersonation(&self, user: &User) -> Result<(), AppError>;
+
+    async fn get_user_info(&self, user: &User) -> Result<UserInfoOutput, AppError>;
+
     async fn get_user_routes(
         &self,
         role_codes: &[String],
@@ -106,14 +230,394 @@ impl TAuthService for SysAuthService {
         &self,
         input: LoginInput,
         context: LoginContext,
-    ) -> Result<AuthOutput, AppError> {
+    ) -> Result<LoginOutput, AppError> {
+        // 登录安全检查
+        self.check_login_security(&input.identifier, &context)
+            .await?;
+
+        // 验证码检查
+        let captcha_passed = self.check_captcha(&input, &context).await?;
+
+        // 登录域检查
+        let domain = self.check_login_domain(&input.identifier, &context).await?;
+
         // 验证用户并获取角色
-        let (user, role_codes) = self
-            .verify_user(&input.identifier, &input.password, &context.domain)
+        let (user, role_codes, identifier_type) = self
+            .verify_user(&input.identifier, &input.password, &domain, &context)
             .await?;
 
-        // 生成认证输出
-        let auth_output = generate_auth_output(
+        // 异常登录按登录域策略要求额外验证
+        self.check_login_risk(
+            &user,
+            &input.identifier,
+            input.captcha_id.as_deref(),
+            input.captcha_code.as_deref(),
+            captcha_passed,
+            &context,
+        )
+        .await?;
+
+        self.finish_first_factor(
+            &user,
+            role_codes,
+            &input.identifier,
+            identifier_type,
+            &context,
+        )
+        .await
+    }
+
+    #[instrument(skip(self, input, context))]
+    async fn verify_mfa(
+        &self,
+        input: MfaVerifyInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError> {
+        let (challenge, context, identifier_type) =
+            self.resume_mfa_challenge(&input.mfa_token, context).await?;
+
+        let db = db_helper::get_db_connection().await?;
+        let totp = SysTotpService::find_by_user(db.as_ref(), &challenge.user_id)
+            .await?
+            .ok_or(MfaError::NotEnrolled)?;
+        if !totp.enabled && !challenge.enrollment_required {
+            return Err(MfaError::InvalidChallenge.into());
+        }
+
+        if !SysTotpService::verify_code(db.as_ref(), &totp, &input.code).await? {
+            self.record_login_failure(
+                &challenge.user_id,
+                &challenge.identifier,
+                Some(identifier_type),
+                &context,
+                LoginFailureReason::InvalidMfaCode,
+            )
+            .await?;
+            return Err(MfaError::InvalidCode.into());
+        }
+
+        if !totp.enabled {
+            SysTotpService::activate(db.as_ref(), totp).await?;
+        }
+
+        self.finish_mfa_login(&input.mfa_token, &challenge, identifier_type, &context)
+            .await
+    }
+
+    async fn oidc_authorize(&self, provider: &str) -> Result<OidcAuthorizeOutput, AppError> {
+        let provider = OidcClient::provider(provider).await?;
+        let (authorization_url, state) = OidcClient::authorization_url(&provider).await?;
+
+        Ok(OidcAuthorizeOutput {
+            authorization_url,
+            state,
+        })
+    }
+
+    /// 外部身份登录，身份提供方的认证视为第一因素，之后与密码登录一样进行异常登录检查与两步验证
+    #[instrument(skip(self, input, context))]
+    async fn oidc_login(
+        &self,
+        provider: &str,
+        input: OidcCallbackInput,
+        context: LoginContext,
+    ) -> Result<LoginOutput, AppError> {
+        let provider = OidcClient::provider(provider).await?;
+        let mut context = context;
+        context.domain = provider.domain.clone();
+        context.login_type = provider.name.clone();
+
+        let claims = OidcClient::exchange(&provider, &input.code, &input.state).await?;
+        let identifier = format!("{}:{}", provider.name, claims.subject());
+
+        self.check_login_security(&identifier, &context).await?;
+        self.check_login_domain(&identifier, &context).await?;
+
+        let db = db_helper::get_db_connection().await?;
+        let Some(user_id) = Self::resolve_identity(db.as_ref(), &provider, &claims).await? else {
+            self.record_login_failure(
+                "",
+                &identifier,
+                None,
+                &context,
+                LoginFailureReason::IdentityNotLinked,
+            )
+            .await?;
+            return Err(OidcError::IdentityNotLinked.into());
+        };
+
+        let Some(user) = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Code.eq(&context.domain))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+        else {
+            self.record_login_failure(
+                &user_id,
+                &identifier,
+                None,
+                &context,
+                LoginFailureReason::UserNotFound,
+            )
+            .await?;
+            return Err(UserError::UserNotFound.into());
+        };
+
+        self.check_login_risk(
+            &user,
+            &identifier,
+            input.captcha_id.as_deref(),
+            input.captcha_code.as_deref(),
+            false,
+            &context,
+        )
+        .await?;
+
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
+        self.finish_first_factor(
+            &user,
+            role_codes,
+            &identifier,
+            LoginIdentifierType::Username,
+            &context,
+        )
+        .await
+    }
+
+    #[instrument(skip(self, input))]
+    async fn enroll_mfa(&self, input: MfaEnrollInput) -> Result<TotpEnrollOutput, AppError> {
+        let challenge = Self::load_mfa_challenge(&input.mfa_token).await?;
+        if !challenge.enrollment_required {
+            return Err(MfaError::InvalidChallenge.into());
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        SysTotpService::start_enrollment(db.as_ref(), &challenge.user_id, &challenge.username).await
+    }
+
+    #[instrument(skip(self, input, context), fields(domain = %context.domain))]
+    async fn passkey_login_options(
+        &self,
+        input: PasskeyLoginOptionsInput,
+        context: LoginContext,
+    ) -> Result<PasskeyRequestOptionsOutput, AppError> {
+        let config = WebAuthn::config().await?;
+        let db = db_helper::get_db_connection().await?;
+
+        // 用户不存在时同样返回挑战，避免泄露用户名是否存在
+        let user_id = match &input.identifier {
+            Some(identifier) => SysUser::find()
+                .filter(SysUserColumn::Username.eq(identifier))
+                .filter(SysUserColumn::Domain.eq(&context.domain))
+                .one(db.as_ref())
+                .await
+                .map_err(AppError::from)?
+                .map(|user| user.id),
+            None => None,
+        };
+
+        SysWebAuthnService::authentication_options(db.as_ref(), &config, user_id.as_deref(), None)
+            .await
+    }
+
+    #[instrument(skip(self, input, context), fields(domain = %context.domain))]
+    async fn passkey_login(
+        &self,
+        input: PasskeyLoginInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError> {
+        WebAuthn::config().await?;
+        let db = db_helper::get_db_connection().await?;
+
+        let pending =
+            SysWebAuthnService::begin_authentication(db.as_ref(), input.credential).await?;
+        if pending.mfa_token.is_some() {
+            return Err(WebAuthnError::InvalidChallenge.into());
+        }
+
+        let owner = SysUser::find_by_id(pending.credential.user_id.clone())
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or(WebAuthnError::CredentialNotFound)?;
+        let identifier = owner.username;
+
+        self.check_login_security(&identifier, &context).await?;
+        self.check_login_domain(&identifier, &context).await?;
+
+        if let Err(error) = SysWebAuthnService::finish_authentication(db.as_ref(), pending).await {
+            self.record_login_failure(
+                &owner.id,
+                &identifier,
+                Some(LoginIdentifierType::Username),
+                &context,
+                LoginFailureReason::InvalidPasskey,
+            )
+            .await?;
+            return Err(error);
+        }
+
+        let Some(user) = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&owner.id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Code.eq(&context.domain))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+        else {
+            self.record_login_failure(
+                &owner.id,
+                &identifier,
+                Some(LoginIdentifierType::Username),
+                &context,
+                LoginFailureReason::UserNotFound,
+            )
+            .await?;
+            return Err(UserError::UserNotFound.into());
+        };
+
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
+        self.complete_login(
+            &user,
+            role_codes,
+            &identifier,
+            LoginIdentifierType::Username,
+            &context,
+        )
+        .await
+    }
+
+    #[instrument(skip(self, input))]
+    async fn passkey_mfa_options(
+        &self,
+        input: PasskeyMfaOptionsInput,
+    ) -> Result<PasskeyRequestOptionsOutput, AppError> {
+        let config = WebAuthn::config().await?;
+        let challenge = Self::load_mfa_challenge(&input.mfa_token).await?;
+
+        let db = db_helper::get_db_connection().await?;
+        if !SysWebAuthnService::has_credentials(db.as_ref(), &challenge.user_id).await? {
+            return Err(WebAuthnError::CredentialNotFound.into());
+        }
+
+        SysWebAuthnService::authentication_options(
+            db.as_ref(),
+            &config,
+            Some(&challenge.user_id),
+            Some(input.mfa_token),
+        )
+        .await
+    }
+
+    #[instrument(skip(self, input, context))]
+    async fn verify_passkey_mfa(
+        &self,
+        input: PasskeyMfaVerifyInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError> {
+        let (challenge, context, identifier_type) =
+            self.resume_mfa_challenge(&input.mfa_token, context).await?;
+
+        let db = db_helper::get_db_connection().await?;
+        let pending =
+            SysWebAuthnService::begin_authentication(db.as_ref(), input.credential).await?;
+        if pending.mfa_token.as_deref() != Some(input.mfa_token.as_str()) {
+            return Err(WebAuthnError::InvalidChallenge.into());
+        }
+
+        if let Err(error) = SysWebAuthnService::finish_authentication(db.as_ref(), pending).await {
+            self.record_login_failure(
+                &challenge.user_id,
+                &challenge.identifier,
+                Some(identifier_type),
+                &context,
+                LoginFailureReason::InvalidPasskey,
+            )
+            .await?;
+            return Err(error);
+        }
+
+        self.finish_mfa_login(&input.mfa_token, &challenge, identifier_type, &context)
+            .await
+    }
+
+    #[instrument(skip(self, input, context))]
+    async fn refresh_token(
+        &self,
+        input: RefreshTokenInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let token = SysTokens::find()
+            .filter(SysTokensColumn::RefreshToken.eq(&input.refresh_token))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(TokenError::RefreshTokenNotFound))?;
+
+        let family_id = token.family_id.clone().unwrap_or_else(|| token.id.clone());
+        let status = TokenStatus::from_str(&token.status)
+            .map_err(|_| AppError::from(TokenError::InvalidTokenStatus))?;
+
+        if !status.can_refresh() {
+            if status == TokenStatus::Refreshed {
+                // 已轮换的刷新令牌被再次使用，视为令牌泄露，撤销整个令牌族
+                SysTokenService::revoke_token_family(db.as_ref(), &family_id).await?;
+                return Err(TokenError::RefreshTokenReused.into());
+            }
+            return Err(TokenError::RefreshTokenRevoked.into());
+        }
+
+        // 模拟令牌到期后需要管理员重新发起模拟，避免刷新后丢失执行者信息
+        if token.actor_id.is_some() {
+            return Err(TokenError::ImpersonationNotRefreshable.into());
+        }
+
+        // 刷新后的令牌沿用会话原有的受众，旧记录没有受众时以请求为准
+        let context = LoginContext {
+            audience: token
+                .audience
+                .as_deref()
+                .and_then(|audience| Audience::from_str(audience).ok())
+                .unwrap_or(context.audience),
+            ..context
+        };
+
+        let jwt_config = global::get_config::<JwtConfig>()
+            .await
+            .ok_or_else(|| AppError {
+                code: 500,
+                message: "JWT config not initialized".to_string(),
+            })?;
+        if Local::now().naive_local() - token.created_at
+            > Duration::seconds(jwt_config.refresh_expire_for(context.audience.as_str()))
+        {
+            return Err(TokenError::RefreshTokenExpired.into());
+        }
+
+        // 用户状态或角色可能在上次登录后发生变化，重新加载
+        let user = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&token.user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Status.eq(Status::ENABLED))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::InvalidUserStatus))?;
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
+
+        let jti = Ulid::new().to_string();
+        let mut auth_output = generate_auth_output(
+            jti.clone(),
             user.id.clone(),
             user.username.clone(),
             role_codes,
@@ -123,12 +627,175 @@ impl TAuthService for SysAuthService {
         )
         .await?;
 
-        // 发送认证事件
-        self.send_login_event(&user, &auth_output, &context).await;
+        // 密码被要求修改或已过期时提示前端跳转修改密码
+        let policy = PasswordPolicy::for_domain(db.as_ref(), &user.domain_code).await?;
+        auth_output.password_changed_at = user.password_changed_at;
+        auth_output.must_change_password = user.must_change_password
+            || PasswordPolicy::is_expired(
+                &policy,
+                user.password_changed_at,
+                Local::now().naive_local(),
+            );
+
+        let txn = db.begin().await.map_err(AppError::from)?;
+        match Self::rotate_token(
+            &txn,
+            &token,
+            &user,
+            &auth_output,
+            &jti,
+            &context,
+            &family_id,
+        )
+        .await
+        {
+            Ok(true) => {
+                txn.commit().await.map_err(AppError::from)?;
+                Ok(auth_output)
+            },
+            Ok(false) => {
+                // 并发请求已抢先轮换该令牌
+                txn.rollback().await.map_err(AppError::from)?;
+                SysTokenService::revoke_token_family(db.as_ref(), &family_id).await?;
+                Err(TokenError::RefreshTokenReused.into())
+            },
+            Err(e) => {
+                txn.rollback().await.map_err(AppError::from)?;
+                Err(e)
+            },
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn logout(&self, jti: &str) -> Result<(), AppError> {
+        SysTokenService.revoke_token(jti).await
+    }
+
+    #[instrument(skip(self, actor, context), fields(actor = %actor.user_id(), user_id = %user_id))]
+    async fn impersonate(
+        &self,
+        actor: &User,
+        user_id: &str,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError> {
+        // 模拟会话内不允许再次发起模拟，保证审计记录只有一层执行者
+        if actor.actor_id().is_some() {
+            return Err(ImpersonationError::NestedImpersonation.into());
+        }
+        if actor.user_id() == user_id {
+            return Err(ImpersonationError::SelfImpersonation.into());
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let target = SysUser::find_by_id(user_id)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::UserNotFound))?;
+        if target.built_in {
+            return Err(ImpersonationError::BuiltInUser.into());
+        }
+        if target.domain != actor.domain() && actor.domain() != BUILT_IN_DOMAIN {
+            return Err(ImpersonationError::CrossDomain.into());
+        }
+
+        let user = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Status.eq(Status::ENABLED))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::InvalidUserStatus))?;
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
+
+        let jti = Ulid::new().to_string();
+        let mut claims = Claims::new(
+            user.id.clone(),
+            context.audience.as_str().to_string(),
+            user.username.clone(),
+            role_codes,
+            user.domain_code.clone(),
+            None,
+        );
+        claims.set_jti(jti.clone());
+        claims.set_act(Actor {
+            sub: actor.user_id(),
+        });
+        let auth_output = sign_auth_output(&claims).await?;
+
+        // 模拟会话同样写入令牌表与登录日志，登录类型标记为模拟登录
+        let context = LoginContext {
+            login_type: IMPERSONATION_LOGIN_TYPE.to_string(),
+            domain: user.domain_code.clone(),
+            ..context
+        };
+        Self::record_login(
+            db.as_ref(),
+            &user,
+            &auth_output,
+            &jti,
+            LoginIdentifierType::Username,
+            &context,
+            Some(&actor.user_id()),
+        )
+        .await?;
+
+        project_info!(
+            "User {} ({}) started impersonating user {} ({}), session {}",
+            actor.username(),
+            actor.user_id(),
+            user.username,
+            user.id,
+            jti
+        );
 
         Ok(auth_output)
     }
 
+    #[instrument(skip(self, user), fields(user_id = %user.user_id()))]
+    async fn stop_impersonation(&self, user: &User) -> Result<(), AppError> {
+        let Some(actor_id) = user.actor_id() else {
+            return Err(ImpersonationError::NotImpersonating.into());
+        };
+
+        if let Some(jti) = user.jti() {
+            SysTokenService.revoke_token(&jti).await?;
+        }
+
+        project_info!(
+            "User {} stopped impersonating user {} ({})",
+            actor_id,
+            user.username(),
+            user.user_id()
+        );
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, user), fields(user_id = %user.user_id()))]
+    async fn get_user_info(&self, user: &User) -> Result<UserInfoOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let profile = SysUser::find_by_id(user.user_id())
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::UserNotFound))?;
+
+        Ok(UserInfoOutput {
+            user_id: user.user_id(),
+            user_name: user.username(),
+            roles: user.subject(),
+            avatar: profile.avatar,
+            email: profile.email,
+            domain: user.domain(),
+            organization: user.organization(),
+            impersonator_id: user.actor_id(),
+        })
+    }
+
     #[instrument(skip(self), fields(roles = ?role_codes, domain = %domain))]
     async fn get_user_routes(
         &self,
@@ -166,89 +833,503 @@ impl TAuthService for SysAuthService {
             .all(db.as_ref())
             .await?;
 
-        let menu_routes: Vec<MenuRoute> = menus
-            .into_iter()
-            .map(|menu| MenuRoute {
-                name: menu.route_name,
-                path: menu.route_path,
-                component: menu.component,
-                meta: RouteMeta {
-                    title: menu.menu_name,
-                    i18n_key: menu.i18n_key,
-                    keep_alive: menu.keep_alive,
-                    constant: menu.constant,
-                    icon: menu.icon,
-                    order: menu.sequence,
-                    href: menu.href,
-                    hide_in_menu: menu.hide_in_menu,
-                    active_menu: menu.active_menu,
-                    multi_tab: menu.multi_tab,
-                },
-                children: Some(vec![]),
-                id: menu.id,
-                pid: menu.pid,
-            })
-            .collect();
+        let menu_routes: Vec<MenuRoute> = menus
+            .into_iter()
+            .map(|menu| MenuRoute {
+                name: menu.route_name,
+                path: menu.route_path,
+                component: menu.component,
+                meta: RouteMeta {
+                    title: menu.menu_name,
+                    i18n_key: menu.i18n_key,
+                    keep_alive: menu.keep_alive,
+                    constant: menu.constant,
+                    icon: menu.icon,
+                    order: menu.sequence,
+                    href: menu.href,
+                    hide_in_menu: menu.hide_in_menu,
+                    active_menu: menu.active_menu,
+                    multi_tab: menu.multi_tab,
+                },
+                children: Some(vec![]),
+                id: menu.id,
+                pid: menu.pid,
+            })
+            .collect();
+
+        let menu_routes_ref = menu_routes.clone();
+
+        let routes = TreeBuilder::build(
+            menu_routes,
+            |route| route.name.clone(),
+            |route| {
+                if route.pid == "0" {
+                    None
+                } else {
+                    menu_routes_ref
+                        .iter()
+                        .find(|m| m.id.to_string() == route.pid)
+                        .map(|m| m.name.clone())
+                }
+            },
+            |route| route.meta.order,
+            |route, children| {
+                route.children = Some(children);
+            },
+        );
+
+        // let home = Self::find_first_valid_route(&routes).unwrap_or_else(|| "/home".to_string());
+        let home = "home".to_string();
+
+        Ok(UserRoute { routes, home })
+    }
+}
+
+impl SysAuthService {
+    /// 第一因素认证通过：开启或要求两步验证时返回验证挑战，否则完成登录
+    async fn finish_first_factor(
+        &self,
+        user: &UserWithDomainAndOrgOutput,
+        role_codes: Vec<String>,
+        identifier: &str,
+        identifier_type: LoginIdentifierType,
+        context: &LoginContext,
+    ) -> Result<LoginOutput, AppError> {
+        if let Some(challenge) = self
+            .create_mfa_challenge(user, &role_codes, identifier, identifier_type, context)
+            .await?
+        {
+            return Ok(LoginOutput::MfaRequired(challenge));
+        }
+
+        self.complete_login(user, role_codes, identifier, identifier_type, context)
+            .await
+            .map(LoginOutput::Authenticated)
+    }
+
+    /// 登录成功：清除失败计数、签发令牌并发送认证事件
+    async fn complete_login(
+        &self,
+        user: &UserWithDomainAndOrgOutput,
+        role_codes: Vec<String>,
+        identifier: &str,
+        identifier_type: LoginIdentifierType,
+        context: &LoginContext,
+    ) -> Result<AuthOutput, AppError> {
+        LoginSecurity::reset(&context.domain, identifier).await?;
+
+        let db = db_helper::get_db_connection().await?;
+        SysTokenService::enforce_session_limit(db.as_ref(), &user.id, context.audience.as_str())
+            .await?;
+
+        let jti = Ulid::new().to_string();
+        let auth_output = generate_auth_output(
+            jti.clone(),
+            user.id.clone(),
+            user.username.clone(),
+            role_codes,
+            user.domain_code.clone(),
+            None,
+            context.audience,
+        )
+        .await?;
+
+        Self::record_login(
+            db.as_ref(),
+            user,
+            &auth_output,
+            &jti,
+            identifier_type,
+            context,
+            None,
+        )
+        .await?;
+        LoginRisk::record(db.as_ref(), user, context).await;
+
+        Ok(auth_output)
+    }
+
+    async fn mfa_config() -> Arc<MfaConfig> {
+        global::get_config::<MfaConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(MfaConfig::default()))
+    }
+
+    /// 用户已开启两步验证，或所在域要求其角色开启两步验证时，创建验证挑战
+    async fn create_mfa_challenge(
+        &self,
+        user: &UserWithDomainAndOrgOutput,
+        role_codes: &[String],
+        identifier: &str,
+        identifier_type: LoginIdentifierType,
+        context: &LoginContext,
+    ) -> Result<Option<MfaChallengeOutput>, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let mut methods = Self::mfa_methods(db.as_ref(), &user.id).await?;
+        let enrollment_required = methods.is_empty()
+            && SysTotpService::is_required(db.as_ref(), &user.domain_code, role_codes).await?;
+        if methods.is_empty() && !enrollment_required {
+            return Ok(None);
+        }
+        if enrollment_required {
+            methods.push(MFA_METHOD_TOTP.to_string());
+        }
+
+        let config = Self::mfa_config().await;
+        let mfa_token = Ulid::new().to_string();
+        let challenge = MfaChallenge {
+            user_id: user.id.clone(),
+            username: user.username.clone(),
+            identifier: identifier.to_string(),
+            identifier_type: identifier_type.to_string(),
+            domain: context.domain.clone(),
+            audience: context.audience.as_str().to_string(),
+            login_type: context.login_type.clone(),
+            enrollment_required,
+        };
+        let value = serde_json::to_string(&challenge).map_err(|e| AppError {
+            code: 500,
+            message: format!("Failed to serialize MFA challenge: {}", e),
+        })?;
+        cache_helper::set_ex(
+            &format!("{}{}", MFA_CHALLENGE_KEY_PREFIX, mfa_token),
+            &value,
+            StdDuration::from_secs(config.challenge_expire),
+        )
+        .await?;
+
+        Ok(Some(MfaChallengeOutput {
+            mfa_token,
+            expires_in: config.challenge_expire,
+            enrollment_required,
+            methods,
+        }))
+    }
+
+    /// 用户已开启的两步验证方式
+    async fn mfa_methods(db: &DatabaseConnection, user_id: &str) -> Result<Vec<String>, AppError> {
+        let mut methods = vec![];
+        if SysTotpService::find_by_user(db, user_id)
+            .await?
+            .is_some_and(|totp| totp.enabled)
+        {
+            methods.push(MFA_METHOD_TOTP.to_string());
+        }
+        if SysWebAuthnService::has_credentials(db, user_id).await? {
+            methods.push(MFA_METHOD_PASSKEY.to_string());
+        }
+        Ok(methods)
+    }
+
+    /// 取出登录挑战并计入一次验证，令牌的域、受众与登录类型以密码校验时的请求为准
+    async fn resume_mfa_challenge(
+        &self,
+        mfa_token: &str,
+        context: LoginContext,
+    ) -> Result<(MfaChallenge, LoginContext, LoginIdentifierType), AppError> {
+        let challenge = Self::load_mfa_challenge(mfa_token).await?;
+        let context = LoginContext {
+            domain: challenge.domain.clone(),
+            audience: Audience::from_str(&challenge.audience)
+                .map_err(|_| MfaError::InvalidChallenge)?,
+            login_type: challenge.login_type.clone(),
+            ..context
+        };
+        let identifier_type = LoginIdentifierType::from_str(&challenge.identifier_type)
+            .map_err(|_| MfaError::InvalidChallenge)?;
+
+        self.check_login_security(&challenge.identifier, &context)
+            .await?;
+
+        let config = Self::mfa_config().await;
+        let attempts = cache_helper::incr(
+            &format!("{}{}", MFA_ATTEMPTS_KEY_PREFIX, mfa_token),
+            StdDuration::from_secs(config.challenge_expire),
+        )
+        .await?;
+        if attempts > i64::from(config.max_verify_attempts) {
+            Self::discard_mfa_challenge(mfa_token).await?;
+            return Err(MfaError::TooManyAttempts.into());
+        }
+
+        Ok((challenge, context, identifier_type))
+    }
 
-        let menu_routes_ref = menu_routes.clone();
+    /// 两步验证通过：作废挑战并完成登录
+    async fn finish_mfa_login(
+        &self,
+        mfa_token: &str,
+        challenge: &MfaChallenge,
+        identifier_type: LoginIdentifierType,
+        context: &LoginContext,
+    ) -> Result<AuthOutput, AppError> {
+        Self::discard_mfa_challenge(mfa_token).await?;
 
-        let routes = TreeBuilder::build(
-            menu_routes,
-            |route| route.name.clone(),
-            |route| {
-                if route.pid == "0" {
-                    None
-                } else {
-                    menu_routes_ref
-                        .iter()
-                        .find(|m| m.id.to_string() == route.pid)
-                        .map(|m| m.name.clone())
-                }
-            },
-            |route| route.meta.order,
-            |route, children| {
-                route.children = Some(children);
-            },
-        );
+        // 用户状态或角色可能在挑战期间发生变化，重新加载
+        let db = db_helper::get_db_connection().await?;
+        let user = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&challenge.user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Status.eq(Status::ENABLED))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::InvalidUserStatus))?;
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
 
-        // let home = Self::find_first_valid_route(&routes).unwrap_or_else(|| "/home".to_string());
-        let home = "home".to_string();
+        self.complete_login(
+            &user,
+            role_codes,
+            &challenge.identifier,
+            identifier_type,
+            context,
+        )
+        .await
+    }
 
-        Ok(UserRoute { routes, home })
+    async fn load_mfa_challenge(mfa_token: &str) -> Result<MfaChallenge, AppError> {
+        cache_helper::get(&format!("{}{}", MFA_CHALLENGE_KEY_PREFIX, mfa_token))
+            .await?
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .ok_or_else(|| MfaError::InvalidChallenge.into())
     }
-}
 
-impl SysAuthService {
-    /// 验证用户身份
+    async fn discard_mfa_challenge(mfa_token: &str) -> Result<(), AppError> {
+        cache_helper::del(&format!("{}{}", MFA_CHALLENGE_KEY_PREFIX, mfa_token)).await?;
+        cache_helper::del(&format!("{}{}", MFA_ATTEMPTS_KEY_PREFIX, mfa_token)).await
+    }
+
+    /// 验证用户身份，按登录标识类型匹配用户名、邮箱或手机号
     async fn verify_user(
         &self,
         identifier: &str,
         password: &str,
-        domain: &str,
-    ) -> Result<(UserWithDomainAndOrgOutput, Vec<String>), AppError> {
+        domain: &SysDomainModel,
+        context: &LoginContext,
+    ) -> Result<(UserWithDomainAndOrgOutput, Vec<String>, LoginIdentifierType), AppError> {
+        let Some(identifier_type) =
+            resolve_identifier_type(identifier, domain.login_identifier_types.as_ref())
+        else {
+            self.record_login_failure(
+                "",
+                identifier,
+                None,
+                context,
+                LoginFailureReason::UserNotFound,
+            )
+            .await?;
+            return Err(AppError::from(UserError::UserNotFound));
+        };
+        let identifier_column = match identifier_type {
+            LoginIdentifierType::Username => SysUserColumn::Username,
+            LoginIdentifierType::Email => SysUserColumn::Email,
+            LoginIdentifierType::Phone => SysUserColumn::PhoneNumber,
+        };
+
         let db = db_helper::get_db_connection().await?;
 
-        let user = select_user_with_domain_and_org_info!(SysUser::find())
-            .filter(SysUserColumn::Username.eq(identifier))
-            .filter(SysDomainColumn::Code.eq(domain))
+        // 启用目录认证的域优先通过 LDAP 验证用户名登录
+        if matches!(identifier_type, LoginIdentifierType::Username)
+            && LdapDirectory::enabled_for(&domain.code).await
+        {
+            if let Some((user, role_codes)) = self
+                .verify_directory_user(identifier, password, domain, &db, context)
+                .await?
+            {
+                return Ok((user, role_codes, identifier_type));
+            }
+        }
+
+        // 已禁用的用户按不存在处理，避免管理员禁用后仍可凭密码重新登录
+        let user = match select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(identifier_column.eq(identifier))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Code.eq(&context.domain))
+            .filter(SysDomainColumn::Status.eq(Status::ENABLED))
             .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
             .into_model::<UserWithDomainAndOrgOutput>()
             .one(db.as_ref())
             .await
             .map_err(AppError::from)?
-            .ok_or_else(|| AppError::from(UserError::UserNotFound))?;
+        {
+            Some(user) => user,
+            None => {
+                self.record_login_failure(
+                    "",
+                    identifier,
+                    Some(identifier_type),
+                    context,
+                    LoginFailureReason::UserNotFound,
+                )
+                .await?;
+                return Err(AppError::from(UserError::UserNotFound));
+            },
+        };
 
         // 验证密码
         if !SecureUtil::verify_password(password.as_bytes(), &user.password)
             .map_err(|_| AppError::from(UserError::AuthenticationFailed))?
         {
+            self.record_login_failure(
+                &user.id,
+                identifier,
+                Some(identifier_type),
+                context,
+                LoginFailureReason::WrongPassword,
+            )
+            .await?;
             return Err(AppError::from(UserError::WrongPassword));
         }
 
         // 获取角色
         let role_codes = self.get_user_roles(&user.id, &db).await?;
 
-        Ok((user, role_codes))
+        Ok((user, role_codes, identifier_type))
+    }
+
+    /// 通过 LDAP 目录验证用户
+    ///
+    /// 目录中没有该用户、目录不可用或用户名属于未关联目录的本地账号时返回 `None`，
+    /// 由调用方继续按本地账号验证
+    async fn verify_directory_user(
+        &self,
+        identifier: &str,
+        password: &str,
+        domain: &SysDomainModel,
+        db: &DatabaseConnection,
+        context: &LoginContext,
+    ) -> Result<Option<(UserWithDomainAndOrgOutput, Vec<String>)>, AppError> {
+        let directory_user = match LdapDirectory::authenticate(identifier, password).await {
+            Ok(DirectoryOutcome::Authenticated(user)) => user,
+            Ok(DirectoryOutcome::NotFound) => return Ok(None),
+            Ok(DirectoryOutcome::InvalidCredentials) => {
+                // 同名的本地账号仍可使用本地密码登录
+                if ExternalIdentity::username_exists(db, identifier).await? {
+                    return Ok(None);
+                }
+                self.record_login_failure(
+                    "",
+                    identifier,
+                    Some(LoginIdentifierType::Username),
+                    context,
+                    LoginFailureReason::WrongPassword,
+                )
+                .await?;
+                return Err(UserError::WrongPassword.into());
+            },
+            Err(e) => {
+                project_error!(
+                    "LDAP authentication unavailable, using local accounts: {}",
+                    e
+                );
+                return Ok(None);
+            },
+        };
+
+        let user_id =
+            match ExternalIdentity::linked_user(db, LDAP_PROVIDER, &directory_user.dn).await? {
+                Some(user_id) => user_id,
+                None if ExternalIdentity::username_exists(db, &directory_user.username).await? => {
+                    return Ok(None)
+                },
+                None => {
+                    let profile = ExternalProfile {
+                        provider: LDAP_PROVIDER,
+                        subject: &directory_user.dn,
+                        domain: &domain.code,
+                        username: &directory_user.username,
+                        nick_name: &directory_user.nick_name,
+                        email: directory_user.email.as_deref(),
+                    };
+                    ExternalIdentity::provision(db, &profile, &[]).await?
+                },
+            };
+
+        // 本地已禁用的账号即使目录认证通过也不允许登录，交由本地验证记录失败
+        let Some(user) = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Code.eq(&domain.code))
+            .filter(SysDomainColumn::Status.eq(Status::ENABLED))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+        else {
+            return Ok(None);
+        };
+
+        // 目录组映射的角色与本地分配的角色合并，不写入用户角色表
+        let mut role_codes = self.get_user_roles(&user.id, db).await?;
+        for role in LdapDirectory::group_roles(&domain.code, &directory_user.groups).await {
+            if !role_codes.contains(&role) {
+                role_codes.push(role);
+            }
+        }
+
+        Ok(Some((user, role_codes)))
+    }
+
+    /// 查找外部身份关联的用户，未关联时按配置自动创建用户
+    async fn resolve_identity(
+        db: &DatabaseConnection,
+        provider: &OidcProviderConfig,
+        claims: &IdTokenClaims,
+    ) -> Result<Option<String>, AppError> {
+        if let Some(user_id) =
+            ExternalIdentity::linked_user(db, &provider.name, claims.subject()).await?
+        {
+            return Ok(Some(user_id));
+        }
+
+        if !provider.auto_provision {
+            return Ok(None);
+        }
+
+        let username = Self::unique_username(db, provider, claims).await?;
+        let profile = ExternalProfile {
+            provider: &provider.name,
+            subject: claims.subject(),
+            domain: &provider.domain,
+            username: &username,
+            nick_name: claims.name().unwrap_or(&username),
+            email: claims.email(),
+        };
+        let role_codes: Vec<String> = provider.default_role.iter().cloned().collect();
+
+        ExternalIdentity::provision(db, &profile, &role_codes)
+            .await
+            .map(Some)
+    }
+
+    /// 以配置的声明作为用户名，重名时追加随机后缀
+    async fn unique_username<C: ConnectionTrait>(
+        db: &C,
+        provider: &OidcProviderConfig,
+        claims: &IdTokenClaims,
+    ) -> Result<String, AppError> {
+        let base = claims
+            .claim(&provider.username_claim)
+            .or_else(|| claims.email().and_then(|email| email.split('@').next()))
+            .unwrap_or(claims.subject())
+            .to_string();
+
+        for attempt in 0..5 {
+            let candidate = if attempt == 0 {
+                base.clone()
+            } else {
+                format!("{}_{:04x}", base, rand::random::<u16>())
+            };
+            if !ExternalIdentity::username_exists(db, &candidate).await? {
+                return Ok(candidate);
+            }
+        }
+
+        Err(OidcError::UsernameConflict.into())
     }
 
     /// 获取用户角色
@@ -267,56 +1348,286 @@ impl SysAuthService {
             .map_err(AppError::from)
     }
 
-    async fn send_login_event(
-        &self,
+    /// 写入令牌记录并发送登录事件
+    ///
+    /// 令牌记录在返回令牌前同步写入，保证随后的吊销与并发会话检查能看到该会话，
+    /// 登录日志仍由事件异步写入
+    async fn record_login<C: ConnectionTrait>(
+        db: &C,
         user: &UserWithDomainAndOrgOutput,
         auth_output: &AuthOutput,
+        jti: &str,
+        identifier_type: LoginIdentifierType,
         context: &LoginContext,
-    ) {
+        actor_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        AccessTokenEvent {
+            access_token: auth_output.token.clone(),
+            refresh_token: auth_output.refresh_token.clone(),
+            user_id: user.id.clone(),
+            username: user.username.clone(),
+            domain: user.domain_code.clone(),
+            ip: context.client_ip.clone(),
+            port: context.client_port,
+            address: context.address.clone(),
+            user_agent: context.user_agent.clone(),
+            request_id: context.request_id.clone(),
+            login_type: context.login_type.clone(),
+            family_id: None,
+            jti: jti.to_string(),
+            audience: context.audience.as_str().to_string(),
+            actor_id: actor_id.map(ToString::to_string),
+        }
+        .handle(db)
+        .await?;
+
         let auth_event = AuthEvent {
             user_id: user.id.clone(),
             username: user.username.clone(),
             domain: user.domain_code.clone(),
-            access_token: auth_output.token.clone(),
-            refresh_token: auth_output.refresh_token.clone(),
             client_ip: context.client_ip.clone(),
             client_port: context.client_port,
             address: context.address.clone(),
             user_agent: context.user_agent.clone(),
             request_id: context.request_id.clone(),
             login_type: context.login_type.clone(),
+            identifier_type: identifier_type.to_string(),
         };
-
         global::send_dyn_event("auth_login", Box::new(auth_event));
+
+        Ok(())
     }
 
-    #[allow(dead_code)]
+    /// 将旧令牌标记为已刷新并写入新令牌
+    ///
+    /// 返回 `false` 表示旧令牌已不是活跃状态（被并发请求轮换）
+    async fn rotate_token<C: ConnectionTrait>(
+        db: &C,
+        token: &SysTokensModel,
+        user: &UserWithDomainAndOrgOutput,
+        auth_output: &AuthOutput,
+        jti: &str,
+        context: &LoginContext,
+        family_id: &str,
+    ) -> Result<bool, AppError> {
+        let result = SysTokens::update_many()
+            .col_expr(
+                SysTokensColumn::Status,
+                Expr::value(TokenStatus::Refreshed.to_string()),
+            )
+            .filter(SysTokensColumn::Id.eq(&token.id))
+            .filter(SysTokensColumn::Status.eq(TokenStatus::Active.to_string()))
+            .exec(db)
+            .await
+            .map_err(AppError::from)?;
+
+        if result.rows_affected == 0 {
+            return Ok(false);
+        }
+
+        AccessTokenEvent {
+            access_token: auth_output.token.clone(),
+            refresh_token: auth_output.refresh_token.clone(),
+            user_id: user.id.clone(),
+            username: user.username.clone(),
+            domain: user.domain_code.clone(),
+            ip: context.client_ip.clone(),
+            port: context.client_port,
+            address: context.address.clone(),
+            user_agent: context.user_agent.clone(),
+            request_id: context.request_id.clone(),
+            login_type: token.r#type.clone(),
+            family_id: Some(family_id.to_string()),
+            jti: jti.to_string(),
+            audience: context.audience.as_str().to_string(),
+            actor_id: token.actor_id.clone(),
+        }
+        .handle(db)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// 登录前的安全检查：IP 黑名单、登录时间段、账号锁定与 IP 失败次数
     async fn check_login_security(
         &self,
-        _username: &str,
-        _client_ip: &str,
+        identifier: &str,
+        context: &LoginContext,
     ) -> Result<(), AppError> {
-        // TODO: 实现登录安全检查
-        // 1. 检查登录失败次数
-        // 2. 检查 IP 黑名单
-        // 3. 检查账号是否被锁定
-        // 4. 检查是否在允许的时间范围内
+        if let Some(rejection) =
+            LoginSecurity::check(&context.domain, identifier, &context.client_ip).await?
+        {
+            self.record_login_failure("", identifier, None, context, rejection.failure_reason())
+                .await?;
+            return Err(rejection.into());
+        }
+
         Ok(())
     }
 
-    #[allow(dead_code)]
-    async fn pwd_login_with_security(
+    /// 失败次数达到阈值或配置要求时校验验证码，返回本次请求是否已通过验证码校验
+    async fn check_captcha(
         &self,
-        input: LoginInput,
-        context: LoginContext,
-    ) -> Result<AuthOutput, AppError> {
-        self.check_login_security(&input.identifier, &context.client_ip)
+        input: &LoginInput,
+        context: &LoginContext,
+    ) -> Result<bool, AppError> {
+        let check = SysCaptchaService::check(
+            &context.domain,
+            &input.identifier,
+            &context.client_ip,
+            input.captcha_id.as_deref(),
+            input.captcha_code.as_deref(),
+        )
+        .await?;
+
+        self.handle_captcha_check(check, "", &input.identifier, context)
+            .await
+    }
+
+    async fn handle_captcha_check(
+        &self,
+        check: CaptchaCheck,
+        user_id: &str,
+        identifier: &str,
+        context: &LoginContext,
+    ) -> Result<bool, AppError> {
+        match check {
+            CaptchaCheck::NotRequired => Ok(false),
+            CaptchaCheck::Passed => Ok(true),
+            CaptchaCheck::Rejected(rejection) => {
+                self.record_login_failure(
+                    user_id,
+                    identifier,
+                    None,
+                    context,
+                    rejection.failure_reason(),
+                )
+                .await?;
+                Err(rejection.into())
+            },
+        }
+    }
+
+    /// 检测到异常登录且策略要求额外验证时，校验验证码
+    ///
+    /// 要求两步验证时，已开启两步验证的用户随后会收到验证挑战，未开启的用户改为校验验证码
+    async fn check_login_risk(
+        &self,
+        user: &UserWithDomainAndOrgOutput,
+        identifier: &str,
+        captcha_id: Option<&str>,
+        captcha_code: Option<&str>,
+        captcha_passed: bool,
+        context: &LoginContext,
+    ) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let risk = LoginRisk::evaluate(db.as_ref(), user, context).await?;
+        if !risk.is_suspicious() || captcha_passed {
+            return Ok(());
+        }
+
+        let captcha_required = match risk.action {
+            LoginRiskAction::Captcha => true,
+            LoginRiskAction::Mfa => Self::mfa_methods(db.as_ref(), &user.id).await?.is_empty(),
+            LoginRiskAction::Log | LoginRiskAction::Notify => false,
+        };
+        if !captcha_required {
+            return Ok(());
+        }
+
+        let check = SysCaptchaService::verify(captcha_id, captcha_code).await?;
+        self.handle_captcha_check(check, &user.id, identifier, context)
+            .await
+            .map(|_| ())
+    }
+
+    /// 登录域必须存在且处于启用状态
+    async fn check_login_domain(
+        &self,
+        identifier: &str,
+        context: &LoginContext,
+    ) -> Result<SysDomainModel, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let domain = SysDomain::find()
+            .filter(SysDomainColumn::Code.eq(&context.domain))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let (reason, error) = match domain {
+            Some(domain) if domain.status == Status::ENABLED => return Ok(domain),
+            Some(_) => (
+                LoginFailureReason::DomainDisabled,
+                DomainError::DomainDisabled,
+            ),
+            None => (
+                LoginFailureReason::DomainNotFound,
+                DomainError::DomainNotFound,
+            ),
+        };
+
+        self.record_login_failure("", identifier, None, context, reason)
             .await?;
+        Err(error.into())
+    }
+
+    /// 记录登录失败，凭证错误会计入失败次数
+    async fn record_login_failure(
+        &self,
+        user_id: &str,
+        identifier: &str,
+        identifier_type: Option<LoginIdentifierType>,
+        context: &LoginContext,
+        reason: LoginFailureReason,
+    ) -> Result<(), AppError> {
+        if reason.counts_toward_lockout() {
+            LoginSecurity::record_failure(&context.domain, identifier, &context.client_ip).await?;
+        }
+
+        let login_failed_event = LoginFailedEvent {
+            user_id: user_id.to_string(),
+            username: identifier.to_string(),
+            domain: context.domain.clone(),
+            client_ip: context.client_ip.clone(),
+            client_port: context.client_port,
+            address: context.address.clone(),
+            user_agent: context.user_agent.clone(),
+            request_id: context.request_id.clone(),
+            login_type: context.login_type.clone(),
+            reason: reason.to_string(),
+            identifier_type: identifier_type.map(|t| t.to_string()),
+        };
 
-        self.pwd_login(input, context).await
+        global::send_dyn_event("auth_login", Box::new(login_failed_event));
+
+        Ok(())
     }
 }
 
+/// 解析登录标识类型
+///
+/// 按格式识别为邮箱或手机号，域未允许该类型时回退为用户名；
+/// `allowed` 为空表示允许全部类型，返回 `None` 表示没有可用的类型
+fn resolve_identifier_type(
+    identifier: &str,
+    allowed: Option<&serde_json::Value>,
+) -> Option<LoginIdentifierType> {
+    let is_allowed = |identifier_type: LoginIdentifierType| match allowed {
+        Some(serde_json::Value::Array(types)) => types
+            .iter()
+            .any(|t| t.as_str() == Some(identifier_type.as_ref())),
+        _ => true,
+    };
+
+    [
+        LoginIdentifierType::detect(identifier),
+        LoginIdentifierType::Username,
+    ]
+    .into_iter()
+    .find(|identifier_type| is_allowed(*identifier_type))
+}
+
 #[instrument(skip(sender, auth_event))]
 async fn send_auth_event(
     sender: mpsc::UnboundedSender<Box<dyn std::any::Any + Send>>,
@@ -329,6 +1640,7 @@ async fn send_auth_event(
 }
 
 pub async fn generate_auth_output(
+    jti: String,
     user_id: String,
     username: String,
     role_codes: Vec<String>,
@@ -336,7 +1648,7 @@ pub async fn generate_auth_output(
     organization_name: Option<String>,
     audience: Audience,
 ) -> Result<AuthOutput, JwtError> {
-    let claims = Claims::new(
+    let mut claims = Claims::new(
         user_id,
         audience.as_str().to_string(),
         username,
@@ -344,12 +1656,20 @@ pub async fn generate_auth_output(
         domain_code,
         organization_name,
     );
+    claims.set_jti(jti);
+
+    sign_auth_output(&claims).await
+}
 
-    let token = JwtUtils::generate_token(&claims).await?;
+/// 签发访问令牌并生成新的刷新令牌
+async fn sign_auth_output(claims: &Claims) -> Result<AuthOutput, JwtError> {
+    let token = JwtUtils::generate_token(claims).await?;
 
     Ok(AuthOutput {
         token,
         refresh_token: Ulid::new().to_string(),
+        password_changed_at: None,
+        must_change_password: false,
     })
 }
 
@@ -362,6 +1682,10 @@ pub async fn auth_login_listener(
             if let Err(e) = handle_auth_event(auth_event).await {
                 project_error!("Failed to handle AuthEvent: {:?}", e);
             }
+        } else if let Some(login_failed_event) = event.downcast_ref::<LoginFailedEvent>() {
+            if let Err(e) = handle_login_failed_event(login_failed_event).await {
+                project_error!("Failed to handle LoginFailedEvent: {:?}", e);
+            }
         }
     }
 }
@@ -372,14 +1696,32 @@ async fn handle_auth_event(auth_event: &AuthEvent) -> Result<(), EventError> {
         user_id: auth_event.user_id.clone(),
         username: auth_event.username.clone(),
         domain: auth_event.domain.clone(),
-        access_token: auth_event.access_token.clone(),
-        refresh_token: auth_event.refresh_token.clone(),
         client_ip: auth_event.client_ip.clone(),
         address: auth_event.address.clone(),
         client_port: auth_event.client_port,
         user_agent: auth_event.user_agent.clone(),
         request_id: auth_event.request_id.clone(),
         login_type: auth_event.login_type.clone(),
+        identifier_type: auth_event.identifier_type.clone(),
+    })
+    .await
+    .map_err(|e| EventError::LoginHandlerError(format!("{:?}", e)))
+}
+
+#[instrument(skip(event), fields(username = %event.username, reason = %event.reason))]
+async fn handle_login_failed_event(event: &LoginFailedEvent) -> Result<(), EventError> {
+    AuthEventHandler::handle_login_failure(LoginFailedEvent {
+        user_id: event.user_id.clone(),
+        username: event.username.clone(),
+        domain: event.domain.clone(),
+        client_ip: event.client_ip.clone(),
+        client_port: event.client_port,
+        address: event.address.clone(),
+        user_agent: event.user_agent.clone(),
+        request_id: event.request_id.clone(),
+        login_type: event.login_type.clone(),
+        reason: event.reason.clone(),
+        identifier_type: event.identifier_type.clone(),
     })
     .await
     .map_err(|e| EventError::LoginHandlerError(format!("{:?}", e)))
@@ -392,3 +1734,49 @@ pub async fn jwt_created_listener(mut rx: tokio::sync::mpsc::UnboundedReceiver<S
         // TODO: Consider storing the token into the database
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_identifier_type_allows_all_by_default() {
+        assert_eq!(
+            resolve_identifier_type("soybean@example.com", None),
+            Some(LoginIdentifierType::Email)
+        );
+        assert_eq!(
+            resolve_identifier_type("+8613800000000", None),
+            Some(LoginIdentifierType::Phone)
+        );
+        assert_eq!(
+            resolve_identifier_type("soybean", None),
+            Some(LoginIdentifierType::Username)
+        );
+    }
+
+    #[test]
+    fn test_resolve_identifier_type_falls_back_to_username() {
+        let allowed = json!(["USERNAME", "PHONE"]);
+        assert_eq!(
+            resolve_identifier_type("soybean@example.com", Some(&allowed)),
+            Some(LoginIdentifierType::Username)
+        );
+        assert_eq!(
+            resolve_identifier_type("13800000000", Some(&allowed)),
+            Some(LoginIdentifierType::Phone)
+        );
+    }
+
+    #[test]
+    fn test_resolve_identifier_type_rejects_disallowed() {
+        let allowed = json!(["EMAIL"]);
+        assert_eq!(resolve_identifier_type("soybean", Some(&allowed)), None);
+        assert_eq!(
+            resolve_identifier_type("soybean@example.com", Some(&allowed)),
+            Some(LoginIdentifierType::Email)
+        );
+    }
+}
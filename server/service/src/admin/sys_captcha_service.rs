@@ -0,0 +1,259 @@
+use std::{
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use captcha_rs::CaptchaBuilder;
+use once_cell::sync::Lazy;
+use rand::{seq::SliceRandom, Rng};
+use server_config::{CaptchaConfig, CaptchaType};
+use server_core::web::error::AppError;
+use server_global::global;
+use server_model::admin::output::CaptchaOutput;
+use ulid::Ulid;
+
+use super::{login_security::LoginSecurity, sys_captcha_error::CaptchaError};
+use crate::helper::cache_helper;
+
+const CAPTCHA_KEY_PREFIX: &str = "soybean:captcha:";
+const TEXT_CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const IMAGE_WIDTH: u32 = 130;
+const IMAGE_HEIGHT: u32 = 40;
+
+/// 登录验证码校验结果
+#[derive(Debug)]
+pub(crate) enum CaptchaCheck {
+    /// 本次登录不需要验证码
+    NotRequired,
+    /// 验证码校验通过
+    Passed,
+    Rejected(CaptchaError),
+}
+
+/// 验证码提供方
+///
+/// 内置 [`ImageCaptchaProvider`]，滑块或第三方验证码实现该 trait 后
+/// 通过 [`set_captcha_provider`] 替换
+#[async_trait]
+pub trait CaptchaProvider: Send + Sync {
+    /// 签发验证码
+    async fn generate(&self) -> Result<CaptchaOutput, AppError>;
+
+    /// 校验验证码，无论结果如何验证码都应失效
+    async fn verify(&self, captcha_id: &str, code: &str) -> Result<bool, AppError>;
+}
+
+static CAPTCHA_PROVIDER: Lazy<RwLock<Arc<dyn CaptchaProvider>>> =
+    Lazy::new(|| RwLock::new(Arc::new(ImageCaptchaProvider)));
+
+/// 替换全局验证码提供方，应在服务启动时调用
+pub fn set_captcha_provider(provider: Arc<dyn CaptchaProvider>) {
+    *CAPTCHA_PROVIDER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = provider;
+}
+
+fn captcha_provider() -> Arc<dyn CaptchaProvider> {
+    CAPTCHA_PROVIDER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+async fn captcha_config() -> Arc<CaptchaConfig> {
+    global::get_config::<CaptchaConfig>()
+        .await
+        .unwrap_or_else(|| Arc::new(CaptchaConfig::default()))
+}
+
+/// 内置图片验证码：服务端生成算术题或随机字符的图片，答案存入缓存
+pub struct ImageCaptchaProvider;
+
+impl ImageCaptchaProvider {
+    /// 生成题面与答案
+    fn challenge(captcha_type: CaptchaType, length: usize) -> (String, String) {
+        let mut rng = rand::thread_rng();
+        match captcha_type {
+            CaptchaType::Arithmetic => {
+                let a = rng.gen_range(1..=20);
+                let b = rng.gen_range(1..=20);
+                match rng.gen_range(0..3) {
+                    0 => (format!("{} + {} = ?", a, b), (a + b).to_string()),
+                    1 => {
+                        let (a, b) = (a.max(b), a.min(b));
+                        (format!("{} - {} = ?", a, b), (a - b).to_string())
+                    },
+                    _ => {
+                        let b = b % 9 + 1;
+                        (format!("{} × {} = ?", a, b), (a * b).to_string())
+                    },
+                }
+            },
+            CaptchaType::Text => {
+                let text: String = (0..length.clamp(1, 8))
+                    .map(|_| char::from(*TEXT_CHARSET.choose(&mut rng).unwrap_or(&b'A')))
+                    .collect();
+                (text.clone(), text)
+            },
+        }
+    }
+
+    /// 将题面渲染为图片，返回 `data:image/jpeg;base64,` 形式的 Data URI
+    ///
+    /// 由 captcha-rs 按 TrueType 字体绘制，并叠加波浪扭曲、干扰线、干扰圆与噪点，
+    /// 图片中不包含任何可直接读取的文本。
+    fn render_image(text: &str) -> String {
+        let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        CaptchaBuilder::new()
+            .text(text)
+            .width(IMAGE_WIDTH)
+            .height(IMAGE_HEIGHT)
+            .complexity(4)
+            .distortion(20)
+            .interference_lines(3)
+            .interference_ellipses(2)
+            .build()
+            .to_base64()
+    }
+}
+
+#[async_trait]
+impl CaptchaProvider for ImageCaptchaProvider {
+    async fn generate(&self) -> Result<CaptchaOutput, AppError> {
+        let config = captcha_config().await;
+        let (text, answer) = Self::challenge(config.captcha_type, config.length);
+        let captcha_id = Ulid::new().to_string();
+
+        cache_helper::set_ex(
+            &format!("{}{}", CAPTCHA_KEY_PREFIX, captcha_id),
+            &answer,
+            Duration::from_secs(config.expire),
+        )
+        .await?;
+
+        Ok(CaptchaOutput {
+            captcha_id,
+            image: Self::render_image(&text),
+            expires_in: config.expire,
+        })
+    }
+
+    async fn verify(&self, captcha_id: &str, code: &str) -> Result<bool, AppError> {
+        let key = format!("{}{}", CAPTCHA_KEY_PREFIX, captcha_id);
+        // 原子地取出答案，同一验证码被并发提交时只有一次能通过
+        let Some(answer) = cache_helper::take(&key).await? else {
+            return Ok(false);
+        };
+
+        Ok(answer.eq_ignore_ascii_case(code.trim()))
+    }
+}
+
+#[async_trait]
+pub trait TCaptchaService {
+    async fn generate(&self) -> Result<CaptchaOutput, AppError>;
+}
+
+#[derive(Clone)]
+pub struct SysCaptchaService;
+
+impl SysCaptchaService {
+    /// 登录前校验验证码
+    ///
+    /// 配置要求全部校验，或账号、IP 的失败次数达到阈值时需要验证码
+    pub(crate) async fn check(
+        domain: &str,
+        username: &str,
+        ip: &str,
+        captcha_id: Option<&str>,
+        code: Option<&str>,
+    ) -> Result<CaptchaCheck, AppError> {
+        if !Self::is_required(domain, username, ip).await? {
+            return Ok(CaptchaCheck::NotRequired);
+        }
+
+        Self::verify(captcha_id, code).await
+    }
+
+    /// 强制校验验证码，用于异常登录等需要额外验证的场景
+    pub(crate) async fn verify(
+        captcha_id: Option<&str>,
+        code: Option<&str>,
+    ) -> Result<CaptchaCheck, AppError> {
+        let (Some(captcha_id), Some(code)) = (captcha_id, code) else {
+            return Ok(CaptchaCheck::Rejected(CaptchaError::Required));
+        };
+        if !captcha_provider().verify(captcha_id, code).await? {
+            return Ok(CaptchaCheck::Rejected(CaptchaError::Invalid));
+        }
+
+        Ok(CaptchaCheck::Passed)
+    }
+
+    async fn is_required(domain: &str, username: &str, ip: &str) -> Result<bool, AppError> {
+        let config = captcha_config().await;
+        if config.enabled {
+            return Ok(true);
+        }
+        if config.failure_threshold == 0 {
+            return Ok(false);
+        }
+
+        let (account_failures, ip_failures) =
+            LoginSecurity::failure_counts(domain, username, ip).await?;
+        Ok(account_failures.max(ip_failures) >= config.failure_threshold)
+    }
+}
+
+#[async_trait]
+impl TCaptchaService for SysCaptchaService {
+    async fn generate(&self) -> Result<CaptchaOutput, AppError> {
+        captcha_provider().generate().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    use super::*;
+
+    #[test]
+    fn test_arithmetic_challenge_answer() {
+        for _ in 0..100 {
+            let (text, answer) = ImageCaptchaProvider::challenge(CaptchaType::Arithmetic, 4);
+            let parts: Vec<&str> = text.split_whitespace().collect();
+            let a: i32 = parts[0].parse().unwrap();
+            let b: i32 = parts[2].parse().unwrap();
+            let expected = match parts[1] {
+                "+" => a + b,
+                "-" => a - b,
+                _ => a * b,
+            };
+            assert!(expected >= 0);
+            assert_eq!(answer, expected.to_string());
+        }
+    }
+
+    #[test]
+    fn test_text_challenge_length() {
+        let (text, answer) = ImageCaptchaProvider::challenge(CaptchaType::Text, 5);
+        assert_eq!(text, answer);
+        assert_eq!(answer.len(), 5);
+        assert!(answer.bytes().all(|b| TEXT_CHARSET.contains(&b)));
+    }
+
+    #[test]
+    fn test_render_image_hides_answer() {
+        let (text, answer) = ImageCaptchaProvider::challenge(CaptchaType::Text, 6);
+        let image = ImageCaptchaProvider::render_image(&text);
+
+        let encoded = image.strip_prefix("data:image/jpeg;base64,").unwrap();
+        let data = STANDARD.decode(encoded).unwrap();
+        // JPEG SOI 标记
+        assert!(data.starts_with(&[0xFF, 0xD8]));
+        assert!(!data.windows(answer.len()).any(|w| w == answer.as_bytes()));
+        assert!(!encoded.contains(&answer));
+    }
+}
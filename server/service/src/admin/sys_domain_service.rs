@@ -1,8 +1,12 @@
+use std::str::FromStr;
+
 use async_trait::async_trait;
 use chrono::Local;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, Set,
 };
+use server_config::{LoginRiskConfig, PasswordPolicyConfig};
+use server_constant::definition::consts::LoginIdentifierType;
 use server_core::web::{error::AppError, page::PaginatedData};
 use server_model::admin::{
     entities::{
@@ -70,6 +74,54 @@ impl SysDomainService {
 
         Ok(())
     }
+
+    /// 校验登录标识类型，未配置时允许全部类型
+    fn parse_login_identifier_types(
+        types: Option<Vec<String>>,
+    ) -> Result<Option<serde_json::Value>, AppError> {
+        types
+            .map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        LoginIdentifierType::from_str(t)
+                            .map(|t| t.to_string())
+                            .map_err(|_| DomainError::InvalidLoginIdentifierType(t.clone()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(serde_json::Value::from)
+            })
+            .transpose()
+            .map_err(AppError::from)
+    }
+
+    /// 校验密码策略，保存补全默认值后的完整策略
+    fn parse_password_policy(
+        policy: Option<serde_json::Value>,
+    ) -> Result<Option<serde_json::Value>, AppError> {
+        policy
+            .map(|policy| {
+                serde_json::from_value::<PasswordPolicyConfig>(policy)
+                    .and_then(serde_json::to_value)
+                    .map_err(|e| DomainError::InvalidPasswordPolicy(e.to_string()))
+            })
+            .transpose()
+            .map_err(AppError::from)
+    }
+
+    /// 校验异常登录检测策略，保存补全默认值后的完整策略
+    fn parse_login_risk_policy(
+        policy: Option<serde_json::Value>,
+    ) -> Result<Option<serde_json::Value>, AppError> {
+        policy
+            .map(|policy| {
+                serde_json::from_value::<LoginRiskConfig>(policy)
+                    .and_then(serde_json::to_value)
+                    .map_err(|e| DomainError::InvalidLoginRiskPolicy(e.to_string()))
+            })
+            .transpose()
+            .map_err(AppError::from)
+    }
 }
 
 #[async_trait]
@@ -110,6 +162,10 @@ impl TDomainService for SysDomainService {
         self.check_domain_exists(None, &input.code, &input.name)
             .await?;
 
+        let login_identifier_types =
+            Self::parse_login_identifier_types(input.login_identifier_types)?;
+        let password_policy = Self::parse_password_policy(input.password_policy)?;
+        let login_risk_policy = Self::parse_login_risk_policy(input.login_risk_policy)?;
         let db = db_helper::get_db_connection().await?;
 
         let domain = SysDomainActiveModel {
@@ -117,6 +173,10 @@ impl TDomainService for SysDomainService {
             code: Set(input.code),
             name: Set(input.name),
             description: Set(input.description),
+            mfa_required_roles: Set(input.mfa_required_roles.map(serde_json::Value::from)),
+            login_identifier_types: Set(login_identifier_types),
+            password_policy: Set(password_policy),
+            login_risk_policy: Set(login_risk_policy),
             status: Set(Status::ENABLED),
             created_at: Set(Local::now().naive_local()),
             created_by: Set("TODO".to_string()),
@@ -151,6 +211,15 @@ impl TDomainService for SysDomainService {
         domain.code = Set(input.domain.code);
         domain.name = Set(input.domain.name);
         domain.description = Set(input.domain.description);
+        domain.mfa_required_roles =
+            Set(input.domain.mfa_required_roles.map(serde_json::Value::from));
+        domain.login_identifier_types = Set(Self::parse_login_identifier_types(
+            input.domain.login_identifier_types,
+        )?);
+        domain.password_policy = Set(Self::parse_password_policy(input.domain.password_policy)?);
+        domain.login_risk_policy = Set(Self::parse_login_risk_policy(
+            input.domain.login_risk_policy,
+        )?);
 
         let updated_domain = domain.update(db.as_ref()).await.map_err(AppError::from)?;
         Ok(updated_domain)
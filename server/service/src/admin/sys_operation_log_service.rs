@@ -96,6 +96,7 @@ impl TOperationLogService for SysOperationLogService {
             end_time: Set(event.end_time),
             duration: Set(event.duration),
             created_at: Set(event.created_at),
+            actor_id: Set(event.actor_id.clone()),
         }
         .insert(db.as_ref())
         .await
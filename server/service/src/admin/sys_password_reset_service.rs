@@ -0,0 +1,304 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::Local;
+use rand::RngCore;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, Set};
+use server_config::PasswordResetConfig;
+use server_constant::definition::consts::LoginIdentifierType;
+use server_core::web::error::AppError;
+use server_global::global;
+use server_model::admin::{
+    entities::{prelude::SysUser, sea_orm_active_enums::Status, sys_user::Column as SysUserColumn},
+    input::{ForgotPasswordInput, ResetPasswordInput},
+};
+use sha2::{Digest, Sha256};
+
+use super::{
+    login_security::LoginSecurity,
+    password_policy::PasswordPolicy,
+    sys_password_reset_error::PasswordResetError,
+    sys_token_service::{SysTokenService, TTokenService},
+    sys_user_service::SysUserService,
+};
+use crate::{
+    helper::{cache_helper, db_helper},
+    mailer::{self, MailMessage},
+    project_error, project_info,
+};
+
+const RESET_TOKEN_KEY_PREFIX: &str = "soybean:password_reset:token:";
+const RESET_USER_KEY_PREFIX: &str = "soybean:password_reset:user:";
+const ACCOUNT_REQUESTS_KEY_PREFIX: &str = "soybean:password_reset:requests:user:";
+const IP_REQUESTS_KEY_PREFIX: &str = "soybean:password_reset:requests:ip:";
+
+#[async_trait]
+pub trait TPasswordResetService {
+    async fn forgot_password(
+        &self,
+        input: ForgotPasswordInput,
+        domain: &str,
+        client_ip: &str,
+    ) -> Result<(), AppError>;
+    async fn reset_password(&self, input: ResetPasswordInput) -> Result<(), AppError>;
+}
+
+#[derive(Clone)]
+pub struct SysPasswordResetService;
+
+impl SysPasswordResetService {
+    async fn config() -> Arc<PasswordResetConfig> {
+        global::get_config::<PasswordResetConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(PasswordResetConfig::default()))
+    }
+
+    /// 生成 256 位随机重置令牌
+    fn generate_token() -> String {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        hex::encode(bytes)
+    }
+
+    /// 缓存中只保存令牌的摘要
+    fn hash_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.trim().as_bytes()))
+    }
+
+    /// 按账号与 IP 统计申请次数，超过阈值时拒绝
+    ///
+    /// 账号计数与账号是否存在无关，拒绝结果不会泄露账号信息
+    async fn check_rate_limit(
+        config: &PasswordResetConfig,
+        domain: &str,
+        identifier: &str,
+        ip: &str,
+    ) -> Result<(), AppError> {
+        let window = Duration::from_secs(config.request_window);
+
+        if config.max_ip_requests > 0 {
+            let requests =
+                cache_helper::incr(&format!("{}{}", IP_REQUESTS_KEY_PREFIX, ip), window).await?;
+            if requests > i64::from(config.max_ip_requests) {
+                return Err(PasswordResetError::TooManyRequests.into());
+            }
+        }
+
+        if config.max_account_requests > 0 {
+            let account_key = format!(
+                "{}{}:{}",
+                ACCOUNT_REQUESTS_KEY_PREFIX,
+                domain,
+                identifier.to_lowercase()
+            );
+            let requests = cache_helper::incr(&account_key, window).await?;
+            if requests > i64::from(config.max_account_requests) {
+                return Err(PasswordResetError::TooManyRequests.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 为账号生成重置令牌并发送邮件，账号不存在或未绑定邮箱时直接返回
+    async fn send_reset_mail(
+        config: &PasswordResetConfig,
+        domain: &str,
+        identifier: &str,
+    ) -> Result<(), AppError> {
+        let identifier_column = match LoginIdentifierType::detect(identifier) {
+            LoginIdentifierType::Email => SysUserColumn::Email,
+            LoginIdentifierType::Phone => SysUserColumn::PhoneNumber,
+            LoginIdentifierType::Username => SysUserColumn::Username,
+        };
+
+        let db = db_helper::get_db_connection().await?;
+        let user = SysUser::find()
+            .filter(identifier_column.eq(identifier))
+            .filter(SysUserColumn::Domain.eq(domain))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        let Some((user, email)) = user.and_then(|user| {
+            let email = user.email.clone()?;
+            Some((user, email))
+        }) else {
+            project_info!(
+                "Password reset requested for unknown or email-less account: {}",
+                identifier
+            );
+            return Ok(());
+        };
+
+        let expire = Duration::from_secs(config.token_expire);
+        let token = Self::generate_token();
+        let token_hash = Self::hash_token(&token);
+
+        // 重新申请时作废之前的令牌
+        let user_key = format!("{}{}", RESET_USER_KEY_PREFIX, user.id);
+        if let Some(previous) = cache_helper::get(&user_key).await? {
+            cache_helper::del(&format!("{}{}", RESET_TOKEN_KEY_PREFIX, previous)).await?;
+        }
+        cache_helper::set_ex(
+            &format!("{}{}", RESET_TOKEN_KEY_PREFIX, token_hash),
+            &user.id,
+            expire,
+        )
+        .await?;
+        cache_helper::set_ex(&user_key, &token_hash, expire).await?;
+
+        let message = MailMessage {
+            to: email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hello {},\n\nUse the link below to reset your password. \
+                 The link expires in {} minutes and can only be used once.\n\n{}\n\n\
+                 If you did not request a password reset, you can ignore this email.\n",
+                user.nick_name,
+                config.token_expire / 60,
+                config.reset_url.replace("{token}", &token)
+            ),
+        };
+        if let Err(e) = mailer::mailer().await.send(&message).await {
+            project_error!("Failed to send password reset email: {}", e);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TPasswordResetService for SysPasswordResetService {
+    /// 无论用户是否存在都返回成功，避免泄露账号信息
+    ///
+    /// 查询账号与发送邮件在后台执行，账号存在与否时接口的响应时间一致
+    async fn forgot_password(
+        &self,
+        input: ForgotPasswordInput,
+        domain: &str,
+        client_ip: &str,
+    ) -> Result<(), AppError> {
+        let config = Self::config().await;
+        Self::check_rate_limit(&config, domain, &input.identifier, client_ip).await?;
+
+        let domain = domain.to_string();
+        let identifier = input.identifier;
+        tokio::spawn(async move {
+            if let Err(e) = Self::send_reset_mail(&config, &domain, &identifier).await {
+                project_error!("Failed to process password reset request: {}", e.message);
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn reset_password(&self, input: ResetPasswordInput) -> Result<(), AppError> {
+        let token_key = format!(
+            "{}{}",
+            RESET_TOKEN_KEY_PREFIX,
+            Self::hash_token(&input.token)
+        );
+        let user_id = cache_helper::get(&token_key)
+            .await?
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        let db = db_helper::get_db_connection().await?;
+        let user = SysUser::find_by_id(&user_id)
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        let policy = PasswordPolicy::for_domain(db.as_ref(), &user.domain).await?;
+        PasswordPolicy::validate(
+            db.as_ref(),
+            &policy,
+            Some(&user.id),
+            &user.username,
+            &input.new_password,
+            Some(&user.password),
+        )
+        .await?;
+
+        // 令牌只能使用一次，原子地取出令牌，并发的重置请求只有一个能继续
+        if cache_helper::take(&token_key).await?.as_deref() != Some(user.id.as_str()) {
+            return Err(PasswordResetError::InvalidToken.into());
+        }
+        cache_helper::del(&format!("{}{}", RESET_USER_KEY_PREFIX, user.id)).await?;
+
+        let password_hash = SysUserService::hash_password(&input.new_password)?;
+        let (user_id, domain, username) =
+            (user.id.clone(), user.domain.clone(), user.username.clone());
+        let mut user = user.into_active_model();
+        user.password = Set(password_hash.clone());
+        user.password_changed_at = Set(Some(Local::now().naive_local()));
+        user.must_change_password = Set(false);
+        user.update(db.as_ref()).await.map_err(AppError::from)?;
+
+        PasswordPolicy::record(db.as_ref(), &policy, &user_id, &password_hash).await?;
+        SysTokenService.revoke_user_tokens(&user_id).await?;
+        LoginSecurity::reset(&domain, &username).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_token() {
+        let token = SysPasswordResetService::generate_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, SysPasswordResetService::generate_token());
+    }
+
+    #[test]
+    fn test_hash_token_ignores_surrounding_whitespace() {
+        let token = SysPasswordResetService::generate_token();
+        assert_eq!(
+            SysPasswordResetService::hash_token(&token),
+            SysPasswordResetService::hash_token(&format!(" {}\n", token))
+        );
+        assert_ne!(SysPasswordResetService::hash_token(&token), token);
+    }
+
+    #[test]
+    fn test_rate_limit_per_account_and_ip() {
+        let config = PasswordResetConfig {
+            max_account_requests: 2,
+            max_ip_requests: 3,
+            ..Default::default()
+        };
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(4)
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let domain = SysPasswordResetService::generate_token();
+            let check = |identifier: &'static str, ip: &'static str| {
+                let (config, domain) = (config.clone(), domain.clone());
+                async move {
+                    SysPasswordResetService::check_rate_limit(&config, &domain, identifier, ip)
+                        .await
+                        .is_ok()
+                }
+            };
+            let ip = "203.0.113.7";
+
+            // 同一账号不区分大小写计数
+            assert!(check("alice", "198.51.100.1").await);
+            assert!(check("Alice", "198.51.100.2").await);
+            assert!(!check("ALICE", "198.51.100.3").await);
+
+            // IP 计数与账号无关
+            assert!(check("bob", ip).await);
+            assert!(check("carol", ip).await);
+            assert!(check("dave", ip).await);
+            assert!(!check("erin", ip).await);
+        });
+    }
+}
@@ -0,0 +1,456 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use async_trait::async_trait;
+use chrono::{Duration, Local, NaiveDateTime};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, ConnectionTrait, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder,
+};
+use server_config::{JwtConfig, SessionLimitPolicy};
+use server_constant::definition::consts::TokenStatus;
+use server_core::web::{error::AppError, page::PaginatedData, token_revocation::TokenRevocation};
+use server_global::global;
+use server_model::admin::{
+    entities::{
+        prelude::SysTokens,
+        sys_tokens::{Column as SysTokensColumn, Model as SysTokensModel},
+    },
+    input::SessionPageRequest,
+    output::SessionOutput,
+};
+
+use crate::{admin::sys_token_error::TokenError, helper::db_helper};
+
+/// 与 JWT 校验的 leeway 保持一致，令牌在 exp 之后仍有 60 秒可通过校验
+const TOKEN_VALIDATION_LEEWAY_SECS: i64 = 60;
+
+#[async_trait]
+pub trait TTokenService {
+    async fn revoke_token(&self, jti: &str) -> Result<(), AppError>;
+    async fn revoke_user_tokens(&self, user_id: &str) -> Result<(), AppError>;
+    async fn restore_revoked_tokens(&self) -> Result<(), AppError>;
+
+    async fn find_paginated_sessions(
+        &self,
+        params: SessionPageRequest,
+    ) -> Result<PaginatedData<SessionOutput>, AppError>;
+    async fn find_user_sessions(
+        &self,
+        user_id: &str,
+        current_jti: Option<&str>,
+        params: SessionPageRequest,
+    ) -> Result<PaginatedData<SessionOutput>, AppError>;
+    async fn revoke_session(&self, id: &str) -> Result<(), AppError>;
+    async fn revoke_user_session(&self, user_id: &str, id: &str) -> Result<(), AppError>;
+    async fn cleanup_expired_tokens(&self) -> Result<u64, AppError>;
+}
+
+#[derive(Clone)]
+pub struct SysTokenService;
+
+impl SysTokenService {
+    /// 撤销满足条件的全部令牌，并将仍在有效期内的访问令牌加入吊销列表
+    pub(crate) async fn revoke_tokens_by_condition<C: ConnectionTrait>(
+        db: &C,
+        condition: Condition,
+    ) -> Result<(), AppError> {
+        let condition = condition.add(SysTokensColumn::Status.ne(TokenStatus::Revoked.to_string()));
+
+        let tokens = SysTokens::find()
+            .filter(condition.clone())
+            .all(db)
+            .await
+            .map_err(AppError::from)?;
+
+        if tokens.is_empty() {
+            return Ok(());
+        }
+
+        SysTokens::update_many()
+            .col_expr(
+                SysTokensColumn::Status,
+                Expr::value(TokenStatus::Revoked.to_string()),
+            )
+            .filter(condition)
+            .exec(db)
+            .await
+            .map_err(AppError::from)?;
+
+        Self::push_to_revocation_list(&tokens).await
+    }
+
+    /// 撤销同一令牌族下的全部令牌
+    pub(crate) async fn revoke_token_family<C: ConnectionTrait>(
+        db: &C,
+        family_id: &str,
+    ) -> Result<(), AppError> {
+        Self::revoke_tokens_by_condition(
+            db,
+            Condition::all().add(
+                Condition::any()
+                    .add(SysTokensColumn::FamilyId.eq(family_id))
+                    .add(SysTokensColumn::Id.eq(family_id)),
+            ),
+        )
+        .await
+    }
+
+    /// 撤销用户除当前会话之外的全部令牌
+    ///
+    /// 当前会话按令牌族保留，令牌记录尚未落库时仅排除当前 jti
+    pub(crate) async fn revoke_other_sessions<C: ConnectionTrait>(
+        db: &C,
+        user_id: &str,
+        current_jti: Option<&str>,
+    ) -> Result<(), AppError> {
+        let mut condition = Condition::all().add(SysTokensColumn::UserId.eq(user_id));
+
+        if let Some(jti) = current_jti {
+            let current = SysTokens::find()
+                .filter(SysTokensColumn::Jti.eq(jti))
+                .one(db)
+                .await
+                .map_err(AppError::from)?;
+
+            condition = condition.add(
+                Condition::any()
+                    .add(SysTokensColumn::Jti.is_null())
+                    .add(SysTokensColumn::Jti.ne(jti)),
+            );
+            if let Some(current) = current {
+                let family_id = current.family_id.unwrap_or(current.id);
+                condition = condition
+                    .add(SysTokensColumn::Id.ne(family_id.as_str()))
+                    .add(
+                        Condition::any()
+                            .add(SysTokensColumn::FamilyId.is_null())
+                            .add(SysTokensColumn::FamilyId.ne(family_id.as_str())),
+                    );
+            }
+        }
+
+        Self::revoke_tokens_by_condition(db, condition).await
+    }
+
+    /// 登录前检查用户在该受众下的并发会话数
+    ///
+    /// 达到上限时按策略拒绝新的登录，或吊销最早的会话为新会话腾出位置，
+    /// 模拟登录的会话不计入限制
+    pub(crate) async fn enforce_session_limit<C: ConnectionTrait>(
+        db: &C,
+        user_id: &str,
+        audience: &str,
+    ) -> Result<(), AppError> {
+        let config = Self::jwt_config().await?;
+        let Some((max_sessions, policy)) = config.session_limit_for(audience) else {
+            return Ok(());
+        };
+
+        let sessions = SysTokens::find()
+            .filter(Self::active_session_condition().await?)
+            .filter(SysTokensColumn::UserId.eq(user_id))
+            .filter(SysTokensColumn::Audience.eq(audience))
+            .filter(SysTokensColumn::ActorId.is_null())
+            .order_by_asc(SysTokensColumn::CreatedAt)
+            .all(db)
+            .await
+            .map_err(AppError::from)?;
+
+        let excess = Self::excess_sessions(sessions.len(), max_sessions);
+        if excess == 0 {
+            return Ok(());
+        }
+
+        match policy {
+            SessionLimitPolicy::RejectNew => Err(TokenError::SessionLimitExceeded.into()),
+            SessionLimitPolicy::RevokeOldest => {
+                for session in sessions.into_iter().take(excess) {
+                    let family_id = session.family_id.unwrap_or(session.id);
+                    Self::revoke_token_family(db, &family_id).await?;
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// 新会话加入后超出上限的会话数
+    fn excess_sessions(active: usize, max_sessions: u64) -> usize {
+        (active + 1).saturating_sub(max_sessions as usize)
+    }
+
+    async fn push_to_revocation_list(tokens: &[SysTokensModel]) -> Result<(), AppError> {
+        let config = Self::jwt_config().await?;
+        let now = Local::now().naive_local();
+
+        for token in tokens {
+            if let Some(jti) = &token.jti {
+                let expire = Duration::seconds(
+                    config.expire_for(token.audience.as_deref().unwrap_or_default()),
+                );
+                if let Some(ttl) = Self::remaining_lifetime(token.created_at, expire, now) {
+                    TokenRevocation::revoke(jti, ttl).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 计算访问令牌剩余的有效时间，已过期返回 `None`
+    fn remaining_lifetime(
+        created_at: NaiveDateTime,
+        expire: Duration,
+        now: NaiveDateTime,
+    ) -> Option<StdDuration> {
+        (created_at + expire + Duration::seconds(TOKEN_VALIDATION_LEEWAY_SECS) - now)
+            .to_std()
+            .ok()
+            .filter(|ttl| !ttl.is_zero())
+    }
+
+    /// 所有受众中最长的访问令牌有效期，用于无法确定令牌受众的场景
+    async fn max_access_token_expire() -> Result<Duration, AppError> {
+        Self::jwt_config()
+            .await
+            .map(|config| Duration::seconds(config.max_expire().0))
+    }
+
+    async fn jwt_config() -> Result<Arc<JwtConfig>, AppError> {
+        global::get_config::<JwtConfig>()
+            .await
+            .ok_or_else(|| AppError {
+                code: 500,
+                message: "JWT config not initialized".to_string(),
+            })
+    }
+
+    /// 在线会话：令牌族中处于活跃状态、且刷新令牌尚未过期的记录
+    ///
+    /// 刷新令牌有效期按受众区分，未单独配置的受众与没有受众的旧记录使用全局有效期
+    async fn active_session_condition() -> Result<Condition, AppError> {
+        let config = Self::jwt_config().await?;
+        let now = Local::now().naive_local();
+
+        let mut within_lifetime = Condition::any().add(
+            Condition::all()
+                .add(
+                    Condition::any()
+                        .add(SysTokensColumn::Audience.is_null())
+                        .add(SysTokensColumn::Audience.is_not_in(config.audiences.keys())),
+                )
+                .add(SysTokensColumn::CreatedAt.gt(now - Duration::seconds(config.refresh_expire))),
+        );
+        for audience in config.audiences.keys() {
+            within_lifetime = within_lifetime.add(
+                Condition::all()
+                    .add(SysTokensColumn::Audience.eq(audience))
+                    .add(
+                        SysTokensColumn::CreatedAt
+                            .gt(now - Duration::seconds(config.refresh_expire_for(audience))),
+                    ),
+            );
+        }
+
+        Ok(Condition::all()
+            .add(SysTokensColumn::Status.eq(TokenStatus::Active.to_string()))
+            .add(within_lifetime))
+    }
+
+    async fn paginate_sessions(
+        condition: Condition,
+        params: SessionPageRequest,
+        current_jti: Option<&str>,
+    ) -> Result<PaginatedData<SessionOutput>, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let mut condition = condition.add(Self::active_session_condition().await?);
+
+        if let Some(ref keywords) = params.keywords {
+            condition = condition.add(
+                Condition::any()
+                    .add(SysTokensColumn::Username.contains(keywords))
+                    .add(SysTokensColumn::Ip.contains(keywords))
+                    .add(SysTokensColumn::Address.contains(keywords))
+                    .add(SysTokensColumn::UserAgent.contains(keywords)),
+            );
+        }
+        if let Some(ref domain) = params.domain {
+            condition = condition.add(SysTokensColumn::Domain.eq(domain));
+        }
+        if let Some(ref login_type) = params.login_type {
+            condition = condition.add(SysTokensColumn::Type.eq(login_type));
+        }
+
+        let query = SysTokens::find()
+            .filter(condition)
+            .order_by_desc(SysTokensColumn::CreatedAt);
+
+        let total = query
+            .clone()
+            .count(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let paginator = query.paginate(db.as_ref(), params.page_details.size);
+        let records = paginator
+            .fetch_page(params.page_details.current - 1)
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|token| {
+                let current = current_jti.is_some() && token.jti.as_deref() == current_jti;
+                SessionOutput {
+                    current,
+                    ..SessionOutput::from(token)
+                }
+            })
+            .collect();
+
+        Ok(PaginatedData {
+            current: params.page_details.current,
+            size: params.page_details.size,
+            total,
+            records,
+        })
+    }
+
+    /// 强制下线会话：撤销会话所在的整个令牌族
+    async fn revoke_session_by_condition(condition: Condition) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let token = SysTokens::find()
+            .filter(condition)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or(TokenError::SessionNotFound)?;
+
+        let family_id = token.family_id.unwrap_or(token.id);
+        Self::revoke_token_family(db.as_ref(), &family_id).await
+    }
+}
+
+#[async_trait]
+impl TTokenService for SysTokenService {
+    async fn revoke_token(&self, jti: &str) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        Self::revoke_tokens_by_condition(
+            db.as_ref(),
+            Condition::all().add(SysTokensColumn::Jti.eq(jti)),
+        )
+        .await?;
+
+        // 登录事件异步落库，令牌记录可能尚未写入，按最长有效期加入吊销列表
+        let ttl = (Self::max_access_token_expire().await?
+            + Duration::seconds(TOKEN_VALIDATION_LEEWAY_SECS))
+        .to_std()
+        .unwrap_or_default();
+        TokenRevocation::revoke(jti, ttl).await
+    }
+
+    async fn revoke_user_tokens(&self, user_id: &str) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        Self::revoke_tokens_by_condition(
+            db.as_ref(),
+            Condition::all().add(SysTokensColumn::UserId.eq(user_id)),
+        )
+        .await
+    }
+
+    /// 启动时将仍在有效期内的已吊销令牌重新加入吊销列表
+    ///
+    /// 使用进程内缓存时重启会丢失吊销列表，需要从数据库恢复
+    async fn restore_revoked_tokens(&self) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let expire = Self::max_access_token_expire().await?;
+        let now = Local::now().naive_local();
+
+        let tokens = SysTokens::find()
+            .filter(SysTokensColumn::Status.eq(TokenStatus::Revoked.to_string()))
+            .filter(SysTokensColumn::Jti.is_not_null())
+            .filter(
+                SysTokensColumn::CreatedAt
+                    .gt(now - expire - Duration::seconds(TOKEN_VALIDATION_LEEWAY_SECS)),
+            )
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        Self::push_to_revocation_list(&tokens).await
+    }
+
+    async fn find_paginated_sessions(
+        &self,
+        params: SessionPageRequest,
+    ) -> Result<PaginatedData<SessionOutput>, AppError> {
+        let mut condition = Condition::all();
+        if let Some(ref user_id) = params.user_id {
+            condition = condition.add(SysTokensColumn::UserId.eq(user_id));
+        }
+
+        Self::paginate_sessions(condition, params, None).await
+    }
+
+    async fn find_user_sessions(
+        &self,
+        user_id: &str,
+        current_jti: Option<&str>,
+        params: SessionPageRequest,
+    ) -> Result<PaginatedData<SessionOutput>, AppError> {
+        Self::paginate_sessions(
+            Condition::all().add(SysTokensColumn::UserId.eq(user_id)),
+            params,
+            current_jti,
+        )
+        .await
+    }
+
+    async fn revoke_session(&self, id: &str) -> Result<(), AppError> {
+        Self::revoke_session_by_condition(Condition::all().add(SysTokensColumn::Id.eq(id))).await
+    }
+
+    async fn revoke_user_session(&self, user_id: &str, id: &str) -> Result<(), AppError> {
+        Self::revoke_session_by_condition(
+            Condition::all()
+                .add(SysTokensColumn::Id.eq(id))
+                .add(SysTokensColumn::UserId.eq(user_id)),
+        )
+        .await
+    }
+
+    /// 删除刷新令牌已过期的记录
+    ///
+    /// 保留时间取访问令牌与刷新令牌有效期中的较大值，
+    /// 确保吊销列表恢复与刷新令牌重放检测仍能查到所需记录
+    async fn cleanup_expired_tokens(&self) -> Result<u64, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let config = Self::jwt_config().await?;
+        let (expire, refresh_expire) = config.max_expire();
+        let retention = Duration::seconds(refresh_expire.max(expire))
+            + Duration::seconds(TOKEN_VALIDATION_LEEWAY_SECS);
+
+        let result = SysTokens::delete_many()
+            .filter(SysTokensColumn::CreatedAt.lt(Local::now().naive_local() - retention))
+            .exec(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excess_sessions() {
+        assert_eq!(SysTokenService::excess_sessions(0, 1), 0);
+        assert_eq!(SysTokenService::excess_sessions(1, 1), 1);
+        assert_eq!(SysTokenService::excess_sessions(2, 3), 0);
+        assert_eq!(SysTokenService::excess_sessions(3, 3), 1);
+        // 调低上限后一次吊销全部多出的会话
+        assert_eq!(SysTokenService::excess_sessions(5, 2), 4);
+    }
+}
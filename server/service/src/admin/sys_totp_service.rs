@@ -0,0 +1,424 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::Local;
+use rand::RngCore;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QuerySelect, Set, TransactionTrait,
+};
+use server_config::MfaConfig;
+use server_core::web::error::AppError;
+use server_global::global;
+use server_model::admin::{
+    entities::{
+        prelude::{SysDomain, SysUserTotp},
+        sys_domain::Column as SysDomainColumn,
+        sys_user_totp::{ActiveModel as SysUserTotpActiveModel, Model as SysUserTotpModel},
+    },
+    input::TotpCodeInput,
+    output::{TotpEnrollOutput, TotpRecoveryCodesOutput},
+};
+use server_utils::SecureUtil;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::{
+    admin::{sys_mfa_error::MfaError, sys_user_service::SysUserService},
+    helper::{cache_helper, db_helper},
+};
+
+const USED_CODE_KEY_PREFIX: &str = "soybean:mfa:used:";
+/// 校验时允许前后各偏移一个时间步，已使用的验证码需要保留到整个可用区间结束
+const USED_CODE_TTL: Duration = Duration::from_secs(90);
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP: u64 = 30;
+/// 恢复码由 10 个随机字节（80 位）编码为 20 个十六进制字符
+const RECOVERY_CODE_BYTES: usize = 10;
+const RECOVERY_CODE_GROUP: usize = 5;
+
+#[async_trait]
+pub trait TTotpService {
+    async fn enroll(&self, user_id: &str, username: &str) -> Result<TotpEnrollOutput, AppError>;
+
+    async fn confirm(
+        &self,
+        user_id: &str,
+        input: TotpCodeInput,
+    ) -> Result<TotpRecoveryCodesOutput, AppError>;
+
+    async fn disable(
+        &self,
+        user_id: &str,
+        domain: &str,
+        role_codes: &[String],
+        input: TotpCodeInput,
+    ) -> Result<(), AppError>;
+
+    async fn regenerate_recovery_codes(
+        &self,
+        user_id: &str,
+        input: TotpCodeInput,
+    ) -> Result<TotpRecoveryCodesOutput, AppError>;
+}
+
+#[derive(Clone)]
+pub struct SysTotpService;
+
+impl SysTotpService {
+    async fn config() -> Arc<MfaConfig> {
+        global::get_config::<MfaConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(MfaConfig::default()))
+    }
+
+    pub(crate) async fn find_by_user<C: ConnectionTrait>(
+        db: &C,
+        user_id: &str,
+    ) -> Result<Option<SysUserTotpModel>, AppError> {
+        SysUserTotp::find_by_id(user_id)
+            .one(db)
+            .await
+            .map_err(AppError::from)
+    }
+
+    async fn find_enabled<C: ConnectionTrait>(
+        db: &C,
+        user_id: &str,
+    ) -> Result<SysUserTotpModel, AppError> {
+        Self::find_by_user(db, user_id)
+            .await?
+            .filter(|totp| totp.enabled)
+            .ok_or_else(|| MfaError::NotEnabled.into())
+    }
+
+    /// 域是否要求持有任一角色的用户开启两步验证
+    pub(crate) async fn is_required<C: ConnectionTrait>(
+        db: &C,
+        domain: &str,
+        role_codes: &[String],
+    ) -> Result<bool, AppError> {
+        let required_roles = SysDomain::find()
+            .filter(SysDomainColumn::Code.eq(domain))
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+            .and_then(|domain| domain.mfa_required_roles)
+            .and_then(|roles| serde_json::from_value::<Vec<String>>(roles).ok())
+            .unwrap_or_default();
+
+        Ok(role_codes.iter().any(|code| required_roles.contains(code)))
+    }
+
+    /// 生成新的密钥，覆盖尚未确认的绑定
+    pub(crate) async fn start_enrollment<C: ConnectionTrait>(
+        db: &C,
+        user_id: &str,
+        username: &str,
+    ) -> Result<TotpEnrollOutput, AppError> {
+        let existing = Self::find_by_user(db, user_id).await?;
+        if existing.as_ref().is_some_and(|totp| totp.enabled) {
+            return Err(MfaError::AlreadyEnabled.into());
+        }
+
+        let config = Self::config().await;
+        let secret = Secret::generate_secret()
+            .to_bytes()
+            .map_err(|_| MfaError::SecretGenerationFailed)?;
+        // otpauth 标签中的冒号用于分隔签发方与账号
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            TOTP_DIGITS,
+            1,
+            TOTP_STEP,
+            secret,
+            Some(config.issuer.clone()),
+            username.replace(':', "_"),
+        )
+        .map_err(|_| MfaError::SecretGenerationFailed)?;
+        let encoded_secret = totp.get_secret_base32();
+        let now = Local::now().naive_local();
+
+        match existing {
+            Some(existing) => {
+                let mut model = existing.into_active_model();
+                model.secret = Set(encoded_secret.clone());
+                model.recovery_codes = Set(None);
+                model.confirmed_at = Set(None);
+                model.updated_at = Set(Some(now));
+                model.update(db).await.map_err(AppError::from)?;
+            },
+            None => {
+                SysUserTotpActiveModel {
+                    user_id: Set(user_id.to_string()),
+                    secret: Set(encoded_secret.clone()),
+                    enabled: Set(false),
+                    recovery_codes: Set(None),
+                    confirmed_at: Set(None),
+                    created_at: Set(now),
+                    updated_at: Set(None),
+                }
+                .insert(db)
+                .await
+                .map_err(AppError::from)?;
+            },
+        }
+
+        Ok(TotpEnrollOutput {
+            secret: encoded_secret,
+            otpauth_url: totp.get_url(),
+        })
+    }
+
+    /// 确认绑定，开启两步验证
+    pub(crate) async fn activate<C: ConnectionTrait>(
+        db: &C,
+        totp: SysUserTotpModel,
+    ) -> Result<SysUserTotpModel, AppError> {
+        let now = Local::now().naive_local();
+        let mut model = totp.into_active_model();
+        model.enabled = Set(true);
+        model.confirmed_at = Set(Some(now));
+        model.updated_at = Set(Some(now));
+        model.update(db).await.map_err(AppError::from)
+    }
+
+    /// 校验动态口令或恢复码
+    ///
+    /// 动态口令在有效期内只能使用一次，恢复码使用后即失效。
+    /// 未开启两步验证（绑定尚未确认）时只接受动态口令。
+    pub(crate) async fn verify_code<C: ConnectionTrait + TransactionTrait>(
+        db: &C,
+        totp: &SysUserTotpModel,
+        code: &str,
+    ) -> Result<bool, AppError> {
+        let code = code.trim();
+
+        if code.len() == TOTP_DIGITS && code.chars().all(|c| c.is_ascii_digit()) {
+            return Self::verify_totp(totp, code).await;
+        }
+
+        if !totp.enabled {
+            return Ok(false);
+        }
+
+        Self::consume_recovery_code(db, totp, code).await
+    }
+
+    async fn verify_totp(totp: &SysUserTotpModel, code: &str) -> Result<bool, AppError> {
+        let secret = Secret::Encoded(totp.secret.clone())
+            .to_bytes()
+            .map_err(|_| MfaError::InvalidCode)?;
+        let generator = TOTP::new_unchecked(
+            Algorithm::SHA1,
+            TOTP_DIGITS,
+            1,
+            TOTP_STEP,
+            secret,
+            None,
+            String::new(),
+        );
+
+        if !generator.check_current(code).unwrap_or(false) {
+            return Ok(false);
+        }
+
+        // 原子地标记为已使用，并发提交同一验证码时只有一次能通过
+        let used_key = format!("{}{}:{}", USED_CODE_KEY_PREFIX, totp.user_id, code);
+        cache_helper::set_nx_ex(&used_key, "1", USED_CODE_TTL).await
+    }
+
+    /// 在事务中锁定记录后重新读取恢复码，并发请求不能重复使用同一恢复码
+    async fn consume_recovery_code<C: ConnectionTrait + TransactionTrait>(
+        db: &C,
+        totp: &SysUserTotpModel,
+        code: &str,
+    ) -> Result<bool, AppError> {
+        let normalized = Self::normalize_recovery_code(code);
+        let txn = db.begin().await.map_err(AppError::from)?;
+
+        let Some(current) = SysUserTotp::find_by_id(totp.user_id.clone())
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(AppError::from)?
+        else {
+            return Ok(false);
+        };
+        let mut hashes = Self::recovery_code_hashes(&current);
+        let Some(index) = hashes.iter().position(|hash| {
+            matches!(
+                SecureUtil::verify_password(normalized.as_bytes(), hash),
+                Ok(true)
+            )
+        }) else {
+            return Ok(false);
+        };
+        hashes.remove(index);
+
+        let mut model = current.into_active_model();
+        model.recovery_codes = Set(Some(serde_json::Value::from(hashes)));
+        model.updated_at = Set(Some(Local::now().naive_local()));
+        model.update(&txn).await.map_err(AppError::from)?;
+        txn.commit().await.map_err(AppError::from)?;
+
+        Ok(true)
+    }
+
+    /// 生成新的恢复码并替换旧的恢复码，返回明文恢复码
+    async fn replace_recovery_codes<C: ConnectionTrait>(
+        db: &C,
+        totp: SysUserTotpModel,
+    ) -> Result<Vec<String>, AppError> {
+        let config = Self::config().await;
+        let codes = (0..config.recovery_code_count)
+            .map(|_| Self::generate_recovery_code())
+            .collect::<Result<Vec<_>, _>>()?;
+        let hashes = codes
+            .iter()
+            .map(|code| SysUserService::hash_password(&Self::normalize_recovery_code(code)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut model = totp.into_active_model();
+        model.recovery_codes = Set(Some(serde_json::Value::from(hashes)));
+        model.updated_at = Set(Some(Local::now().naive_local()));
+        model.update(db).await.map_err(AppError::from)?;
+
+        Ok(codes)
+    }
+
+    fn generate_recovery_code() -> Result<String, AppError> {
+        let mut bytes = [0u8; RECOVERY_CODE_BYTES];
+        rand::thread_rng()
+            .try_fill_bytes(&mut bytes)
+            .map_err(|_| MfaError::SecretGenerationFailed)?;
+        let code = hex::encode_upper(bytes);
+        let groups: Vec<&str> = code
+            .as_bytes()
+            .chunks(RECOVERY_CODE_GROUP)
+            .map(|group| std::str::from_utf8(group).unwrap_or_default())
+            .collect();
+        Ok(groups.join("-"))
+    }
+
+    /// 恢复码与密码一样以加盐的慢哈希保存，比较时忽略大小写与分隔符
+    fn normalize_recovery_code(code: &str) -> String {
+        code.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect()
+    }
+
+    fn recovery_code_hashes(totp: &SysUserTotpModel) -> Vec<String> {
+        totp.recovery_codes
+            .clone()
+            .and_then(|codes| serde_json::from_value(codes).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl TTotpService for SysTotpService {
+    async fn enroll(&self, user_id: &str, username: &str) -> Result<TotpEnrollOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        Self::start_enrollment(db.as_ref(), user_id, username).await
+    }
+
+    async fn confirm(
+        &self,
+        user_id: &str,
+        input: TotpCodeInput,
+    ) -> Result<TotpRecoveryCodesOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let totp = Self::find_by_user(db.as_ref(), user_id)
+            .await?
+            .ok_or(MfaError::NotEnrolled)?;
+        if totp.enabled {
+            return Err(MfaError::AlreadyEnabled.into());
+        }
+        if !Self::verify_code(db.as_ref(), &totp, &input.code).await? {
+            return Err(MfaError::InvalidCode.into());
+        }
+
+        let totp = Self::activate(db.as_ref(), totp).await?;
+        let recovery_codes = Self::replace_recovery_codes(db.as_ref(), totp).await?;
+
+        Ok(TotpRecoveryCodesOutput { recovery_codes })
+    }
+
+    async fn disable(
+        &self,
+        user_id: &str,
+        domain: &str,
+        role_codes: &[String],
+        input: TotpCodeInput,
+    ) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let totp = Self::find_enabled(db.as_ref(), user_id).await?;
+        if Self::is_required(db.as_ref(), domain, role_codes).await? {
+            return Err(MfaError::Required.into());
+        }
+        if !Self::verify_code(db.as_ref(), &totp, &input.code).await? {
+            return Err(MfaError::InvalidCode.into());
+        }
+
+        SysUserTotp::delete_by_id(user_id)
+            .exec(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(())
+    }
+
+    async fn regenerate_recovery_codes(
+        &self,
+        user_id: &str,
+        input: TotpCodeInput,
+    ) -> Result<TotpRecoveryCodesOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let totp = Self::find_enabled(db.as_ref(), user_id).await?;
+        if !Self::verify_code(db.as_ref(), &totp, &input.code).await? {
+            return Err(MfaError::InvalidCode.into());
+        }
+
+        // 校验时可能消耗了恢复码，重新读取避免覆盖
+        let totp = Self::find_enabled(db.as_ref(), user_id).await?;
+        let recovery_codes = Self::replace_recovery_codes(db.as_ref(), totp).await?;
+
+        Ok(TotpRecoveryCodesOutput { recovery_codes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_code_format() {
+        let code = SysTotpService::generate_recovery_code().unwrap();
+        let groups: Vec<&str> = code.split('-').collect();
+        assert_eq!(groups.len(), RECOVERY_CODE_BYTES * 2 / RECOVERY_CODE_GROUP);
+        assert!(groups.iter().all(|group| group.len() == RECOVERY_CODE_GROUP
+            && group.chars().all(|c| c.is_ascii_hexdigit())));
+    }
+
+    #[test]
+    fn test_recovery_code_hash_ignores_case_and_separator() {
+        let hash =
+            SysUserService::hash_password(&SysTotpService::normalize_recovery_code("ABCDE-12345"))
+                .unwrap();
+        let verify = |code: &str| {
+            matches!(
+                SecureUtil::verify_password(
+                    SysTotpService::normalize_recovery_code(code).as_bytes(),
+                    &hash
+                ),
+                Ok(true)
+            )
+        };
+        assert!(verify("abcde12345"));
+        assert!(!verify("ABCDE-12346"));
+    }
+}
@@ -4,21 +4,29 @@ use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, PaginatorTrait,
     QueryFilter, Set,
 };
-use server_core::web::{error::AppError, page::PaginatedData};
+use server_core::web::{auth::User, error::AppError, page::PaginatedData};
 use server_model::admin::{
     entities::{
         prelude::SysUser,
+        sea_orm_active_enums::Status,
         sys_user::{
             ActiveModel as SysUserActiveModel, Column as SysUserColumn, Model as SysUserModel,
         },
     },
-    input::{CreateUserInput, UpdateUserInput, UserPageRequest},
+    input::{
+        ChangePasswordInput, CreateUserInput, UpdateProfileInput, UpdateUserInput, UserPageRequest,
+    },
     output::UserWithoutPassword,
 };
 use server_utils::SecureUtil;
 use ulid::Ulid;
 
-use super::sys_user_error::UserError;
+use super::{
+    password_policy::PasswordPolicy,
+    sys_impersonation_error::ImpersonationError,
+    sys_token_service::{SysTokenService, TTokenService},
+    sys_user_error::UserError,
+};
 use crate::helper::db_helper;
 
 #[async_trait]
@@ -33,6 +41,18 @@ pub trait TUserService {
     async fn get_user(&self, id: &str) -> Result<UserWithoutPassword, AppError>;
     async fn update_user(&self, input: UpdateUserInput) -> Result<UserWithoutPassword, AppError>;
     async fn delete_user(&self, id: &str) -> Result<(), AppError>;
+
+    async fn get_profile(&self, user: &User) -> Result<UserWithoutPassword, AppError>;
+    async fn update_profile(
+        &self,
+        user: &User,
+        input: UpdateProfileInput,
+    ) -> Result<UserWithoutPassword, AppError>;
+    async fn change_password(
+        &self,
+        user: &User,
+        input: ChangePasswordInput,
+    ) -> Result<(), AppError>;
 }
 
 #[derive(Clone)]
@@ -53,6 +73,64 @@ impl SysUserService {
         Ok(())
     }
 
+    pub(crate) fn hash_password(password: &str) -> Result<String, AppError> {
+        SecureUtil::hash_password(password.as_bytes()).map_err(|e| AppError {
+            code: 500,
+            message: format!("Failed to hash password: {}", e),
+        })
+    }
+
+    /// 检查邮箱与手机号未被其他用户占用
+    async fn check_contact_unique(
+        &self,
+        user_id: &str,
+        email: Option<&str>,
+        phone_number: Option<&str>,
+    ) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        if let Some(email) = email {
+            let existing = SysUser::find()
+                .filter(SysUserColumn::Email.eq(email))
+                .filter(SysUserColumn::Id.ne(user_id))
+                .one(db.as_ref())
+                .await
+                .map_err(AppError::from)?;
+            if existing.is_some() {
+                return Err(UserError::EmailAlreadyExists.into());
+            }
+        }
+
+        if let Some(phone_number) = phone_number {
+            let existing = SysUser::find()
+                .filter(SysUserColumn::PhoneNumber.eq(phone_number))
+                .filter(SysUserColumn::Id.ne(user_id))
+                .one(db.as_ref())
+                .await
+                .map_err(AppError::from)?;
+            if existing.is_some() {
+                return Err(UserError::PhoneNumberAlreadyExists.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 模拟登录的会话只能查看资料，不能代替用户修改
+    fn ensure_not_impersonating(user: &User) -> Result<(), AppError> {
+        if user.actor_id().is_some() {
+            return Err(ImpersonationError::ActionNotAllowed.into());
+        }
+        Ok(())
+    }
+
+    /// 空字符串视为清空该字段
+    fn normalize_optional(value: Option<String>) -> Option<String> {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
     async fn get_user_by_id(&self, id: String) -> Result<SysUserModel, AppError> {
         let db = db_helper::get_db_connection().await?;
         SysUser::find_by_id(id)
@@ -113,23 +191,39 @@ impl TUserService for SysUserService {
         self.check_username_unique(&input.username).await?;
 
         let db = db_helper::get_db_connection().await?;
+        let policy = PasswordPolicy::for_domain(db.as_ref(), &input.domain).await?;
+        PasswordPolicy::validate(
+            db.as_ref(),
+            &policy,
+            None,
+            &input.username,
+            &input.password,
+            None,
+        )
+        .await?;
+
+        let password_hash = Self::hash_password(&input.password)?;
+        let now = Local::now().naive_local();
         let user = SysUserActiveModel {
             id: Set(Ulid::new().to_string()),
             domain: Set(input.domain),
             username: Set(input.username),
-            password: Set(SecureUtil::hash_password(input.password.as_bytes()).unwrap()),
+            password: Set(password_hash.clone()),
+            password_changed_at: Set(Some(now)),
+            must_change_password: Set(input.must_change_password.unwrap_or(false)),
             built_in: Set(false),
             nick_name: Set(input.nick_name),
             avatar: Set(input.avatar),
             email: Set(input.email),
             phone_number: Set(input.phone_number),
             status: Set(input.status),
-            created_at: Set(Local::now().naive_local()),
+            created_at: Set(now),
             created_by: Set("TODO".to_string()),
             ..Default::default()
         };
 
         let user_model = user.insert(db.as_ref()).await.map_err(AppError::from)?;
+        PasswordPolicy::record(db.as_ref(), &policy, &user_model.id, &password_hash).await?;
         Ok(UserWithoutPassword::from(user_model))
     }
 
@@ -144,23 +238,60 @@ impl TUserService for SysUserService {
     }
 
     async fn update_user(&self, input: UpdateUserInput) -> Result<UserWithoutPassword, AppError> {
-        let mut user = self.get_user_by_id(input.id).await?.into_active_model();
+        let existing_user = self.get_user_by_id(input.id).await?;
 
-        if input.user.username != *user.username.as_ref() {
+        if input.user.username != existing_user.username {
             self.check_username_unique(&input.user.username).await?;
         }
 
+        let db = db_helper::get_db_connection().await?;
+        // 提交的密码与当前密码一致时视为未修改
+        let password_changed = !matches!(
+            SecureUtil::verify_password(input.user.password.as_bytes(), &existing_user.password),
+            Ok(true)
+        );
+        let new_password = if password_changed {
+            let policy = PasswordPolicy::for_domain(db.as_ref(), &input.user.domain).await?;
+            PasswordPolicy::validate(
+                db.as_ref(),
+                &policy,
+                Some(&existing_user.id),
+                &input.user.username,
+                &input.user.password,
+                Some(&existing_user.password),
+            )
+            .await?;
+            Some((policy, Self::hash_password(&input.user.password)?))
+        } else {
+            None
+        };
+
+        let mut user = existing_user.into_active_model();
         user.domain = Set(input.user.domain);
         user.username = Set(input.user.username);
-        user.password = Set(input.user.password); // TODO: Note: In a real application, you should hash the password
+        if let Some((_, password_hash)) = &new_password {
+            user.password = Set(password_hash.clone());
+            user.password_changed_at = Set(Some(Local::now().naive_local()));
+        }
+        if let Some(must_change_password) = input.user.must_change_password {
+            user.must_change_password = Set(must_change_password);
+        }
         user.nick_name = Set(input.user.nick_name);
         user.avatar = Set(input.user.avatar);
         user.email = Set(input.user.email);
         user.phone_number = Set(input.user.phone_number);
         user.status = Set(input.user.status);
 
-        let db = db_helper::get_db_connection().await?;
         let updated_user = user.update(db.as_ref()).await.map_err(AppError::from)?;
+        if let Some((policy, password_hash)) = &new_password {
+            PasswordPolicy::record(db.as_ref(), policy, &updated_user.id, password_hash).await?;
+        }
+
+        // 账号被禁用或封禁后立即吊销其全部令牌
+        if updated_user.status != Status::ENABLED {
+            SysTokenService.revoke_user_tokens(&updated_user.id).await?;
+        }
+
         Ok(UserWithoutPassword::from(updated_user))
     }
 
@@ -176,6 +307,102 @@ impl TUserService for SysUserService {
             return Err(UserError::UserNotFound.into());
         }
 
+        SysTokenService.revoke_user_tokens(id).await?;
+
         Ok(())
     }
+
+    async fn get_profile(&self, user: &User) -> Result<UserWithoutPassword, AppError> {
+        self.get_user(&user.user_id()).await
+    }
+
+    async fn update_profile(
+        &self,
+        user: &User,
+        input: UpdateProfileInput,
+    ) -> Result<UserWithoutPassword, AppError> {
+        Self::ensure_not_impersonating(user)?;
+
+        let existing_user = self.get_user_by_id(user.user_id()).await?;
+        let email = Self::normalize_optional(input.email);
+        let phone_number = Self::normalize_optional(input.phone_number);
+        self.check_contact_unique(&existing_user.id, email.as_deref(), phone_number.as_deref())
+            .await?;
+
+        let db = db_helper::get_db_connection().await?;
+        let mut profile = existing_user.into_active_model();
+        profile.nick_name = Set(input.nick_name);
+        profile.email = Set(email);
+        profile.phone_number = Set(phone_number);
+        profile.updated_at = Set(Some(Local::now().naive_local()));
+        profile.updated_by = Set(Some(user.user_id()));
+
+        profile
+            .update(db.as_ref())
+            .await
+            .map(UserWithoutPassword::from)
+            .map_err(AppError::from)
+    }
+
+    async fn change_password(
+        &self,
+        user: &User,
+        input: ChangePasswordInput,
+    ) -> Result<(), AppError> {
+        Self::ensure_not_impersonating(user)?;
+
+        let existing_user = self.get_user_by_id(user.user_id()).await?;
+        if !matches!(
+            SecureUtil::verify_password(input.old_password.as_bytes(), &existing_user.password),
+            Ok(true)
+        ) {
+            return Err(UserError::WrongPassword.into());
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let policy = PasswordPolicy::for_domain(db.as_ref(), &existing_user.domain).await?;
+        PasswordPolicy::validate(
+            db.as_ref(),
+            &policy,
+            Some(&existing_user.id),
+            &existing_user.username,
+            &input.new_password,
+            Some(&existing_user.password),
+        )
+        .await?;
+
+        let password_hash = Self::hash_password(&input.new_password)?;
+        let now = Local::now().naive_local();
+        let mut account = existing_user.into_active_model();
+        account.password = Set(password_hash.clone());
+        account.password_changed_at = Set(Some(now));
+        account.must_change_password = Set(false);
+        account.updated_at = Set(Some(now));
+        account.updated_by = Set(Some(user.user_id()));
+
+        let updated_user = account.update(db.as_ref()).await.map_err(AppError::from)?;
+        PasswordPolicy::record(db.as_ref(), &policy, &updated_user.id, &password_hash).await?;
+
+        // 修改密码后其他设备上的会话全部失效，仅保留当前会话
+        SysTokenService::revoke_other_sessions(db.as_ref(), &updated_user.id, user.jti().as_deref())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_optional() {
+        assert_eq!(SysUserService::normalize_optional(None), None);
+        assert_eq!(
+            SysUserService::normalize_optional(Some("  ".to_string())),
+            None
+        );
+        assert_eq!(
+            SysUserService::normalize_optional(Some(" 13800000000 ".to_string())),
+            Some("13800000000".to_string())
+        );
+    }
 }
@@ -0,0 +1,404 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::Local;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use server_config::WebAuthnConfig;
+use server_core::web::{auth::User, error::AppError};
+use server_model::admin::{
+    entities::{
+        prelude::{SysUser, SysUserCredential},
+        sys_user_credential::{
+            ActiveModel as SysUserCredentialActiveModel, Column as SysUserCredentialColumn,
+            Model as SysUserCredentialModel,
+        },
+    },
+    input::{PasskeyRegisterInput, RenamePasskeyInput},
+    output::{PasskeyCreationOptionsOutput, PasskeyCredentialOutput, PasskeyRequestOptionsOutput},
+};
+use ulid::Ulid;
+use webauthn_rs::prelude::{
+    DiscoverableAuthentication, DiscoverableKey, Passkey, PublicKeyCredential,
+};
+use webauthn_rs_core::proto::{AllowCredentials, AuthenticatorTransport, ResidentKeyRequirement};
+
+use super::{
+    sys_impersonation_error::ImpersonationError,
+    sys_user_error::UserError,
+    sys_webauthn_error::WebAuthnError,
+    webauthn::{self, CeremonyState, WebAuthn},
+};
+use crate::helper::db_helper;
+
+const PUBLIC_KEY_CREDENTIAL_TYPE: &str = "public-key";
+
+#[async_trait]
+pub trait TWebAuthnService {
+    async fn registration_options(
+        &self,
+        user: &User,
+    ) -> Result<PasskeyCreationOptionsOutput, AppError>;
+
+    async fn register(
+        &self,
+        user: &User,
+        input: PasskeyRegisterInput,
+    ) -> Result<PasskeyCredentialOutput, AppError>;
+
+    async fn find_credentials(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<PasskeyCredentialOutput>, AppError>;
+
+    async fn rename_credential(
+        &self,
+        user_id: &str,
+        id: &str,
+        input: RenamePasskeyInput,
+    ) -> Result<PasskeyCredentialOutput, AppError>;
+
+    async fn delete_credential(&self, user: &User, id: &str) -> Result<(), AppError>;
+}
+
+/// 已通过挑战校验、等待验证签名的认证响应
+pub(crate) struct PendingAssertion {
+    pub credential: SysUserCredentialModel,
+    /// 作为两步验证时对应的登录挑战
+    pub mfa_token: Option<String>,
+    state: DiscoverableAuthentication,
+    response: PublicKeyCredential,
+}
+
+#[derive(Clone)]
+pub struct SysWebAuthnService;
+
+impl SysWebAuthnService {
+    pub(crate) async fn find_by_user<C: ConnectionTrait>(
+        db: &C,
+        user_id: &str,
+    ) -> Result<Vec<SysUserCredentialModel>, AppError> {
+        SysUserCredential::find()
+            .filter(SysUserCredentialColumn::UserId.eq(user_id))
+            .order_by_asc(SysUserCredentialColumn::CreatedAt)
+            .all(db)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// 用户是否注册了通行密钥，未启用通行密钥时视为没有
+    pub(crate) async fn has_credentials<C: ConnectionTrait>(
+        db: &C,
+        user_id: &str,
+    ) -> Result<bool, AppError> {
+        if WebAuthn::config().await.is_err() {
+            return Ok(false);
+        }
+        let credential = SysUserCredential::find()
+            .filter(SysUserCredentialColumn::UserId.eq(user_id))
+            .one(db)
+            .await
+            .map_err(AppError::from)?;
+        Ok(credential.is_some())
+    }
+
+    fn passkey(credential: &SysUserCredentialModel) -> Result<Passkey, AppError> {
+        serde_json::from_str(&credential.passkey).map_err(|e| AppError {
+            code: 500,
+            message: format!("Failed to deserialize passkey {}: {}", credential.id, e),
+        })
+    }
+
+    fn serialize_passkey(passkey: &Passkey) -> Result<String, AppError> {
+        serde_json::to_string(passkey).map_err(|e| AppError {
+            code: 500,
+            message: format!("Failed to serialize passkey: {}", e),
+        })
+    }
+
+    fn allow_credential(credential: &SysUserCredentialModel) -> Result<AllowCredentials, AppError> {
+        let transports = credential.transports.as_deref().map(|transports| {
+            transports
+                .split(',')
+                .filter_map(|transport| AuthenticatorTransport::from_str(transport).ok())
+                .collect()
+        });
+        Ok(AllowCredentials {
+            type_: PUBLIC_KEY_CREDENTIAL_TYPE.to_string(),
+            id: Self::passkey(credential)?.cred_id().to_vec().into(),
+            transports,
+        })
+    }
+
+    /// 生成认证挑战
+    ///
+    /// 指定用户时只允许其已注册的凭证，否则由认证器选择可发现凭证
+    pub(crate) async fn authentication_options<C: ConnectionTrait>(
+        db: &C,
+        config: &WebAuthnConfig,
+        user_id: Option<&str>,
+        mfa_token: Option<String>,
+    ) -> Result<PasskeyRequestOptionsOutput, AppError> {
+        let (mut options, state) = WebAuthn::relying_party(config)?
+            .start_discoverable_authentication()
+            .map_err(WebAuthnError::from)?;
+        // 由用户主动发起认证，不使用浏览器的条件式界面（自动填充）
+        options.mediation = None;
+        if let Some(user_id) = user_id {
+            options.public_key.allow_credentials = Self::find_by_user(db, user_id)
+                .await?
+                .iter()
+                .map(Self::allow_credential)
+                .collect::<Result<_, _>>()?;
+        }
+
+        let state = CeremonyState::Authentication {
+            user_id: user_id.map(str::to_string),
+            mfa_token,
+            state,
+        };
+        WebAuthn::start(config, &options.public_key.challenge, &state).await?;
+        Ok(options)
+    }
+
+    /// 取出认证挑战并查找凭证，签名由 [`Self::finish_authentication`] 验证
+    pub(crate) async fn begin_authentication<C: ConnectionTrait>(
+        db: &C,
+        response: PublicKeyCredential,
+    ) -> Result<PendingAssertion, AppError> {
+        let CeremonyState::Authentication {
+            user_id,
+            mfa_token,
+            state,
+        } = WebAuthn::take(response.response.client_data_json.as_ref()).await?
+        else {
+            return Err(WebAuthnError::InvalidChallenge.into());
+        };
+
+        let credential = SysUserCredential::find()
+            .filter(
+                SysUserCredentialColumn::CredentialId
+                    .eq(webauthn::encode_base64url(response.get_credential_id())),
+            )
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+            .ok_or(WebAuthnError::CredentialNotFound)?;
+        if user_id
+            .as_ref()
+            .is_some_and(|user_id| user_id != &credential.user_id)
+        {
+            return Err(WebAuthnError::CredentialNotFound.into());
+        }
+        // 认证器返回的用户句柄必须属于凭证的所有者
+        let user_handle = WebAuthn::user_unique_id(&credential.user_id);
+        if response
+            .get_user_unique_id()
+            .is_some_and(|handle| handle != user_handle.as_bytes())
+        {
+            return Err(WebAuthnError::CredentialNotFound.into());
+        }
+
+        Ok(PendingAssertion {
+            credential,
+            mfa_token,
+            state,
+            response,
+        })
+    }
+
+    /// 验证签名并更新签名计数与最近使用时间
+    pub(crate) async fn finish_authentication<C: ConnectionTrait>(
+        db: &C,
+        pending: PendingAssertion,
+    ) -> Result<SysUserCredentialModel, AppError> {
+        let config = WebAuthn::config().await?;
+        let mut passkey = Self::passkey(&pending.credential)?;
+        let result = WebAuthn::relying_party(&config)?
+            .finish_discoverable_authentication(
+                &pending.response,
+                pending.state,
+                &[DiscoverableKey::from(&passkey)],
+            )
+            .map_err(WebAuthnError::from)?;
+        passkey.update_credential(&result);
+
+        let mut credential = pending.credential.into_active_model();
+        credential.passkey = Set(Self::serialize_passkey(&passkey)?);
+        credential.last_used_at = Set(Some(Local::now().naive_local()));
+        credential.update(db).await.map_err(AppError::from)
+    }
+
+    async fn find_owned_credential<C: ConnectionTrait>(
+        db: &C,
+        user_id: &str,
+        id: &str,
+    ) -> Result<SysUserCredentialModel, AppError> {
+        SysUserCredential::find_by_id(id)
+            .filter(SysUserCredentialColumn::UserId.eq(user_id))
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| WebAuthnError::CredentialNotFound.into())
+    }
+
+    /// 模拟登录的会话不能替用户注册或删除通行密钥
+    fn ensure_not_impersonating(user: &User) -> Result<(), AppError> {
+        if user.actor_id().is_some() {
+            return Err(ImpersonationError::ActionNotAllowed.into());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TWebAuthnService for SysWebAuthnService {
+    async fn registration_options(
+        &self,
+        user: &User,
+    ) -> Result<PasskeyCreationOptionsOutput, AppError> {
+        Self::ensure_not_impersonating(user)?;
+        let config = WebAuthn::config().await?;
+
+        let db = db_helper::get_db_connection().await?;
+        let account = SysUser::find_by_id(user.user_id())
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or(UserError::UserNotFound)?;
+        let exclude_credentials = Self::find_by_user(db.as_ref(), &account.id)
+            .await?
+            .iter()
+            .map(|credential| Self::passkey(credential).map(|passkey| passkey.cred_id().clone()))
+            .collect::<Result<_, _>>()?;
+
+        let (mut options, state) = WebAuthn::relying_party(&config)?
+            .start_passkey_registration(
+                WebAuthn::user_unique_id(&account.id),
+                &account.username,
+                &account.nick_name,
+                Some(exclude_credentials),
+            )
+            .map_err(WebAuthnError::from)?;
+        // 尽量创建可发现凭证，以便登录时无需输入用户名
+        if let Some(selection) = options.public_key.authenticator_selection.as_mut() {
+            selection.resident_key = Some(ResidentKeyRequirement::Preferred);
+        }
+
+        let state = CeremonyState::Registration {
+            user_id: account.id,
+            state,
+        };
+        WebAuthn::start(&config, &options.public_key.challenge, &state).await?;
+        Ok(options)
+    }
+
+    async fn register(
+        &self,
+        user: &User,
+        input: PasskeyRegisterInput,
+    ) -> Result<PasskeyCredentialOutput, AppError> {
+        Self::ensure_not_impersonating(user)?;
+        let config = WebAuthn::config().await?;
+
+        let CeremonyState::Registration { user_id, state } =
+            WebAuthn::take(input.credential.response.client_data_json.as_ref()).await?
+        else {
+            return Err(WebAuthnError::InvalidChallenge.into());
+        };
+        if user_id != user.user_id() {
+            return Err(WebAuthnError::InvalidChallenge.into());
+        }
+
+        let passkey = WebAuthn::relying_party(&config)?
+            .finish_passkey_registration(&input.credential, &state)
+            .map_err(WebAuthnError::from)?;
+        let aaguid = WebAuthn::aaguid(&input.credential)?;
+        let credential_id = webauthn::encode_base64url(passkey.cred_id());
+
+        let db = db_helper::get_db_connection().await?;
+        let existing = SysUserCredential::find()
+            .filter(SysUserCredentialColumn::CredentialId.eq(&credential_id))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        if existing.is_some() {
+            return Err(WebAuthnError::CredentialAlreadyRegistered.into());
+        }
+
+        let transports = input
+            .credential
+            .response
+            .transports
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let credential = SysUserCredentialActiveModel {
+            id: Set(Ulid::new().to_string()),
+            user_id: Set(user.user_id()),
+            credential_id: Set(credential_id),
+            passkey: Set(Self::serialize_passkey(&passkey)?),
+            aaguid: Set(aaguid),
+            transports: Set(Some(transports).filter(|transports| !transports.is_empty())),
+            name: Set(input.name),
+            created_at: Set(Local::now().naive_local()),
+            last_used_at: Set(None),
+        };
+
+        credential
+            .insert(db.as_ref())
+            .await
+            .map(PasskeyCredentialOutput::from)
+            .map_err(AppError::from)
+    }
+
+    async fn find_credentials(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<PasskeyCredentialOutput>, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        Self::find_by_user(db.as_ref(), user_id)
+            .await
+            .map(|credentials| {
+                credentials
+                    .into_iter()
+                    .map(PasskeyCredentialOutput::from)
+                    .collect()
+            })
+    }
+
+    async fn rename_credential(
+        &self,
+        user_id: &str,
+        id: &str,
+        input: RenamePasskeyInput,
+    ) -> Result<PasskeyCredentialOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let mut credential = Self::find_owned_credential(db.as_ref(), user_id, id)
+            .await?
+            .into_active_model();
+        credential.name = Set(input.name);
+
+        credential
+            .update(db.as_ref())
+            .await
+            .map(PasskeyCredentialOutput::from)
+            .map_err(AppError::from)
+    }
+
+    async fn delete_credential(&self, user: &User, id: &str) -> Result<(), AppError> {
+        Self::ensure_not_impersonating(user)?;
+
+        let db = db_helper::get_db_connection().await?;
+        let credential = Self::find_owned_credential(db.as_ref(), &user.user_id(), id).await?;
+        SysUserCredential::delete_by_id(credential.id)
+            .exec(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+//! WebAuthn 协议校验
+//!
+//! 注册与认证响应交给 webauthn-rs 校验，注册时只请求 `none` 证明，通行密钥始终要求用户验证。
+//! 挑战一次性使用，按挑战值暂存仪式状态，完成仪式时从客户端数据中取出挑战再查找。
+use std::{sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_cbor_2::Value;
+use server_config::WebAuthnConfig;
+use server_core::web::error::AppError;
+use server_global::global;
+use sha2::{Digest, Sha256};
+use webauthn_rs::prelude::{
+    Base64UrlSafeData, DiscoverableAuthentication, PasskeyRegistration,
+    RegisterPublicKeyCredential, Url, Uuid, Webauthn, WebauthnBuilder,
+};
+use webauthn_rs_core::{internals::AuthenticatorData, proto::Registration};
+
+use super::sys_webauthn_error::WebAuthnError;
+use crate::helper::cache_helper;
+
+const CHALLENGE_KEY_PREFIX: &str = "soybean:webauthn:challenge:";
+
+/// 发起注册或认证时暂存的仪式状态
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum CeremonyState {
+    Registration {
+        /// 注册的用户
+        user_id: String,
+        state: PasskeyRegistration,
+    },
+    Authentication {
+        /// 认证时限定的用户，未指定时由认证器选择可发现凭证
+        user_id: Option<String>,
+        /// 作为两步验证时对应的登录挑战
+        mfa_token: Option<String>,
+        state: DiscoverableAuthentication,
+    },
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    challenge: String,
+}
+
+pub(crate) struct WebAuthn;
+
+impl WebAuthn {
+    pub async fn config() -> Result<Arc<WebAuthnConfig>, AppError> {
+        let config = global::get_config::<WebAuthnConfig>()
+            .await
+            .unwrap_or_else(|| Arc::new(WebAuthnConfig::default()));
+        if !config.enabled {
+            return Err(WebAuthnError::Disabled.into());
+        }
+        Ok(config)
+    }
+
+    /// 按配置构建依赖方
+    pub fn relying_party(config: &WebAuthnConfig) -> Result<Webauthn, AppError> {
+        let invalid = |message: String| AppError {
+            code: 500,
+            message: format!("Invalid WebAuthn config: {}", message),
+        };
+        let mut origins = config.origins.iter().map(|origin| {
+            Url::parse(origin).map_err(|e| invalid(format!("origin {}: {}", origin, e)))
+        });
+        let first = origins
+            .next()
+            .ok_or_else(|| invalid("at least one origin is required".to_string()))??;
+
+        let mut builder = WebauthnBuilder::new(&config.rp_id, &first)
+            .map_err(|e| invalid(e.to_string()))?
+            .rp_name(&config.rp_name)
+            .timeout(Duration::from_secs(config.challenge_expire));
+        for origin in origins {
+            builder = builder.append_allowed_origin(&origin?);
+        }
+        builder.build().map_err(|e| invalid(e.to_string()))
+    }
+
+    /// 暂存挑战对应的仪式状态
+    pub async fn start(
+        config: &WebAuthnConfig,
+        challenge: &Base64UrlSafeData,
+        state: &CeremonyState,
+    ) -> Result<(), AppError> {
+        let value = serde_json::to_string(state).map_err(|e| AppError {
+            code: 500,
+            message: format!("Failed to serialize WebAuthn ceremony: {}", e),
+        })?;
+        cache_helper::set_ex(
+            &format!(
+                "{}{}",
+                CHALLENGE_KEY_PREFIX,
+                encode_base64url(challenge.as_ref())
+            ),
+            &value,
+            Duration::from_secs(config.challenge_expire),
+        )
+        .await
+    }
+
+    /// 取出客户端数据中的挑战对应的仪式状态，挑战只能使用一次
+    pub async fn take(client_data_json: &[u8]) -> Result<CeremonyState, AppError> {
+        let client_data: ClientData = serde_json::from_slice(client_data_json)
+            .map_err(|_| WebAuthnError::InvalidResponse("malformed client data".to_string()))?;
+        let key = format!("{}{}", CHALLENGE_KEY_PREFIX, client_data.challenge);
+        // 原子地取出并删除，并发提交同一挑战时只有一个请求能拿到仪式状态
+        let state = cache_helper::take(&key)
+            .await?
+            .and_then(|value| serde_json::from_str::<CeremonyState>(&value).ok())
+            .ok_or(WebAuthnError::InvalidChallenge)?;
+        Ok(state)
+    }
+
+    /// 用户在认证器中的句柄，由用户 ID 派生，避免向认证器暴露内部 ID
+    pub fn user_unique_id(user_id: &str) -> Uuid {
+        let digest = Sha256::digest(user_id.as_bytes());
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        Uuid::from_bytes(bytes)
+    }
+
+    /// 从注册响应的证明对象中取出认证器型号（AAGUID）
+    pub fn aaguid(credential: &RegisterPublicKeyCredential) -> Result<String, WebAuthnError> {
+        let invalid = || WebAuthnError::InvalidResponse("malformed attestation object".to_string());
+        let attestation: Value =
+            serde_cbor_2::from_slice(credential.response.attestation_object.as_ref())
+                .map_err(|_| invalid())?;
+        let Value::Map(attestation) = attestation else {
+            return Err(invalid());
+        };
+        let Some(Value::Bytes(auth_data)) = attestation.get(&Value::Text("authData".to_string()))
+        else {
+            return Err(invalid());
+        };
+
+        let auth_data = AuthenticatorData::<Registration>::try_from(auth_data.as_slice())?;
+        let aaguid = auth_data.acd.ok_or_else(invalid)?.aaguid;
+        Ok(Uuid::from_bytes(aaguid).hyphenated().to_string())
+    }
+}
+
+pub(crate) fn encode_base64url(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use webauthn_rs::prelude::CreationChallengeResponse;
+
+    use super::*;
+
+    fn config() -> WebAuthnConfig {
+        WebAuthnConfig {
+            enabled: true,
+            rp_id: "example.com".to_string(),
+            rp_name: "Example".to_string(),
+            origins: vec!["https://example.com".to_string()],
+            challenge_expire: 60,
+        }
+    }
+
+    fn registration_options() -> (CreationChallengeResponse, PasskeyRegistration) {
+        WebAuthn::relying_party(&config())
+            .unwrap()
+            .start_passkey_registration(WebAuthn::user_unique_id("user-1"), "alice", "Alice", None)
+            .unwrap()
+    }
+
+    fn client_data(challenge: &Base64UrlSafeData) -> Vec<u8> {
+        serde_json::json!({
+            "type": "webauthn.create",
+            "challenge": encode_base64url(challenge.as_ref()),
+            "origin": "https://example.com",
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_relying_party_requires_valid_origins() {
+        assert!(WebAuthn::relying_party(&config()).is_ok());
+
+        let mut config = config();
+        config.origins = vec![];
+        assert!(WebAuthn::relying_party(&config).is_err());
+
+        config.origins = vec!["not a url".to_string()];
+        assert!(WebAuthn::relying_party(&config).is_err());
+
+        // 来源与依赖方 ID 不匹配时拒绝
+        config.origins = vec!["https://other.com".to_string()];
+        assert!(WebAuthn::relying_party(&config).is_err());
+    }
+
+    #[test]
+    fn test_user_unique_id_is_stable() {
+        assert_eq!(
+            WebAuthn::user_unique_id("user-1"),
+            WebAuthn::user_unique_id("user-1")
+        );
+        assert_ne!(
+            WebAuthn::user_unique_id("user-1"),
+            WebAuthn::user_unique_id("user-2")
+        );
+    }
+
+    #[test]
+    fn test_challenge_can_only_be_taken_once() {
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(4)
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let config = config();
+            let (options, state) = registration_options();
+            let challenge = options.public_key.challenge;
+            WebAuthn::start(
+                &config,
+                &challenge,
+                &CeremonyState::Registration {
+                    user_id: "user-1".to_string(),
+                    state,
+                },
+            )
+            .await
+            .unwrap();
+
+            let state = WebAuthn::take(&client_data(&challenge)).await.unwrap();
+            assert!(matches!(
+                state,
+                CeremonyState::Registration { ref user_id, .. } if user_id == "user-1"
+            ));
+            assert!(WebAuthn::take(&client_data(&challenge)).await.is_err());
+            assert!(WebAuthn::take(b"not json").await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_parse_aaguid() {
+        let aaguid = [
+            0xad, 0xce, 0x00, 0x02, 0x35, 0xbc, 0xc6, 0x0a, 0x64, 0x8b, 0x0b, 0x25, 0xf1, 0xf0,
+            0x55, 0x03,
+        ];
+        let cose_key = Value::Map(BTreeMap::from([
+            (Value::Integer(1), Value::Integer(2)),
+            (Value::Integer(3), Value::Integer(-7)),
+            (Value::Integer(-1), Value::Integer(1)),
+            (Value::Integer(-2), Value::Bytes(vec![1; 32])),
+            (Value::Integer(-3), Value::Bytes(vec![2; 32])),
+        ]));
+
+        let mut auth_data = Sha256::digest(b"example.com").to_vec();
+        auth_data.push(0x45);
+        auth_data.extend_from_slice(&0u32.to_be_bytes());
+        auth_data.extend_from_slice(&aaguid);
+        auth_data.extend_from_slice(&4u16.to_be_bytes());
+        auth_data.extend_from_slice(&[9; 4]);
+        auth_data.extend_from_slice(&serde_cbor_2::to_vec(&cose_key).unwrap());
+        let attestation = Value::Map(BTreeMap::from([
+            (
+                Value::Text("fmt".to_string()),
+                Value::Text("none".to_string()),
+            ),
+            (
+                Value::Text("attStmt".to_string()),
+                Value::Map(BTreeMap::new()),
+            ),
+            (Value::Text("authData".to_string()), Value::Bytes(auth_data)),
+        ]));
+
+        let credential: RegisterPublicKeyCredential = serde_json::from_value(serde_json::json!({
+            "id": "CQkJCQ",
+            "rawId": "CQkJCQ",
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": "e30",
+                "attestationObject": encode_base64url(&serde_cbor_2::to_vec(&attestation).unwrap()),
+            },
+        }))
+        .unwrap();
+        assert_eq!(
+            WebAuthn::aaguid(&credential).unwrap(),
+            "adce0002-35bc-c60a-648b-0b25f1f05503"
+        );
+
+        let mut malformed = credential;
+        malformed.response.attestation_object = vec![0xa0].into();
+        assert!(WebAuthn::aaguid(&malformed).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+#![allow(dead_code)]
+//! 短期键值缓存
+//!
+//! 配置了主 Redis 时读写主 Redis，否则退化为进程内的 moka 缓存。
+//! 适用于登录失败计数、临时锁定等只需在有效期内保留的数据。
+use std::time::{Duration, Instant};
+
+use moka::{sync::Cache, Expiry};
+use once_cell::sync::Lazy;
+use redis::{
+    aio::{ConnectionLike, MultiplexedConnection},
+    cluster_async::ClusterConnection,
+    AsyncCommands, Cmd, ExistenceCheck, Pipeline, RedisFuture, Script, SetExpiry, SetOptions,
+    Value,
+};
+use server_core::web::error::AppError;
+use server_global::global::{RedisConnection, GLOBAL_PRIMARY_REDIS};
+
+#[derive(Clone)]
+struct CacheEntry {
+    value: String,
+    expires_at: Instant,
+}
+
+struct CacheEntryExpiry;
+
+impl Expiry<String, CacheEntry> for CacheEntryExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        value: &CacheEntry,
+        created_at: Instant,
+    ) -> Option<Duration> {
+        Some(value.expires_at.saturating_duration_since(created_at))
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        value: &CacheEntry,
+        updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(value.expires_at.saturating_duration_since(updated_at))
+    }
+}
+
+static MEMORY_CACHE: Lazy<Cache<String, CacheEntry>> =
+    Lazy::new(|| Cache::builder().expire_after(CacheEntryExpiry).build());
+
+/// 读取并删除键，兼容不支持 GETDEL 的 Redis 版本
+static TAKE_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r"
+        local value = redis.call('GET', KEYS[1])
+        if value then
+            redis.call('DEL', KEYS[1])
+        end
+        return value
+        ",
+    )
+});
+
+/// 主 Redis 连接，统一单机与集群模式的命令调用
+enum PrimaryConnection {
+    Single(MultiplexedConnection),
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for PrimaryConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            PrimaryConnection::Single(conn) => conn.req_packed_command(cmd),
+            PrimaryConnection::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            PrimaryConnection::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            PrimaryConnection::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            PrimaryConnection::Single(conn) => conn.get_db(),
+            PrimaryConnection::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
+/// 获取主 Redis 连接，未配置主 Redis 时返回 `None`
+async fn primary_connection() -> Result<Option<PrimaryConnection>, AppError> {
+    match GLOBAL_PRIMARY_REDIS.read().await.clone() {
+        Some(RedisConnection::Single(client)) => Ok(Some(PrimaryConnection::Single(
+            client.get_multiplexed_async_connection().await?,
+        ))),
+        Some(RedisConnection::Cluster(client)) => Ok(Some(PrimaryConnection::Cluster(
+            client.get_async_connection().await?,
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Redis 过期时间以秒为单位，向上取整避免提前失效
+fn ttl_secs(ttl: Duration) -> u64 {
+    (ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0)).max(1)
+}
+
+/// 写入键值并设置过期时间
+pub async fn set_ex(key: &str, value: &str, ttl: Duration) -> Result<(), AppError> {
+    match primary_connection().await? {
+        Some(mut conn) => {
+            conn.set_ex::<_, _, ()>(key, value, ttl_secs(ttl)).await?;
+        },
+        None => MEMORY_CACHE.insert(
+            key.to_string(),
+            CacheEntry {
+                value: value.to_string(),
+                expires_at: Instant::now() + ttl,
+            },
+        ),
+    }
+    Ok(())
+}
+
+/// 键不存在时写入键值并设置过期时间，返回是否写入
+///
+/// 检查与写入是一个原子操作，并发调用时只有一个调用方能写入成功。
+pub async fn set_nx_ex(key: &str, value: &str, ttl: Duration) -> Result<bool, AppError> {
+    match primary_connection().await? {
+        Some(mut conn) => {
+            let options = SetOptions::default()
+                .conditional_set(ExistenceCheck::NX)
+                .with_expiration(SetExpiry::EX(ttl_secs(ttl)));
+            // 键已存在时返回 nil
+            let reply: Option<String> = conn.set_options(key, value, options).await?;
+            Ok(reply.is_some())
+        },
+        None => Ok(MEMORY_CACHE
+            .entry(key.to_string())
+            .or_insert_with(|| CacheEntry {
+                value: value.to_string(),
+                expires_at: Instant::now() + ttl,
+            })
+            .is_fresh()),
+    }
+}
+
+/// 读取键值
+pub async fn get(key: &str) -> Result<Option<String>, AppError> {
+    match primary_connection().await? {
+        Some(mut conn) => Ok(conn.get(key).await?),
+        None => Ok(MEMORY_CACHE.get(key).map(|entry| entry.value)),
+    }
+}
+
+/// 原子地读取并删除键
+///
+/// 并发调用时只有真正删除了键的调用方能取到值，用于验证码、一次性令牌等只能使用一次的数据。
+pub async fn take(key: &str) -> Result<Option<String>, AppError> {
+    match primary_connection().await? {
+        Some(mut conn) => Ok(TAKE_SCRIPT.key(key).invoke_async(&mut conn).await?),
+        None => Ok(MEMORY_CACHE
+            .remove(key)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.value)),
+    }
+}
+
+/// 计数器自增，首次创建时设置过期时间，之后自增不会延长过期时间
+pub async fn incr(key: &str, ttl: Duration) -> Result<i64, AppError> {
+    match primary_connection().await? {
+        Some(mut conn) => {
+            let count: i64 = conn.incr(key, 1).await?;
+            if count == 1 {
+                conn.expire::<_, ()>(key, ttl_secs(ttl) as i64).await?;
+            }
+            Ok(count)
+        },
+        None => {
+            let entry = MEMORY_CACHE
+                .entry(key.to_string())
+                .and_upsert_with(|existing| match existing.map(|entry| entry.into_value()) {
+                    Some(entry) => CacheEntry {
+                        value: (entry.value.parse::<i64>().unwrap_or(0) + 1).to_string(),
+                        expires_at: entry.expires_at,
+                    },
+                    None => CacheEntry {
+                        value: "1".to_string(),
+                        expires_at: Instant::now() + ttl,
+                    },
+                });
+            Ok(entry.into_value().value.parse().unwrap_or(1))
+        },
+    }
+}
+
+/// 获取键的剩余有效时间，键不存在时返回 `None`
+pub async fn ttl(key: &str) -> Result<Option<Duration>, AppError> {
+    match primary_connection().await? {
+        Some(mut conn) => {
+            // -2 表示键不存在，-1 表示未设置过期时间
+            let secs: i64 = conn.ttl(key).await?;
+            Ok(u64::try_from(secs).ok().map(Duration::from_secs))
+        },
+        None => Ok(MEMORY_CACHE
+            .get(key)
+            .map(|entry| entry.expires_at.saturating_duration_since(Instant::now()))),
+    }
+}
+
+/// 删除键
+pub async fn del(key: &str) -> Result<(), AppError> {
+    match primary_connection().await? {
+        Some(mut conn) => {
+            conn.del::<_, ()>(key).await?;
+        },
+        None => MEMORY_CACHE.invalidate(key),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_consumes_once() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(4)
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let key = "soybean:test:take";
+            set_ex(key, "answer", Duration::from_secs(60))
+                .await
+                .unwrap();
+
+            let handles: Vec<_> = (0..16).map(|_| tokio::spawn(take(key))).collect();
+            let mut taken = Vec::new();
+            for handle in handles {
+                if let Some(value) = handle.await.unwrap().unwrap() {
+                    taken.push(value);
+                }
+            }
+
+            assert_eq!(taken, vec!["answer".to_string()]);
+            assert_eq!(get(key).await.unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn test_set_nx_ex_sets_once() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(4)
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let key = "soybean:test:set_nx_ex";
+            let handles: Vec<_> = (0..16)
+                .map(|i| {
+                    tokio::spawn(async move {
+                        set_nx_ex(key, &i.to_string(), Duration::from_secs(60)).await
+                    })
+                })
+                .collect();
+            let mut stored = 0;
+            for handle in handles {
+                if handle.await.unwrap().unwrap() {
+                    stored += 1;
+                }
+            }
+
+            assert_eq!(stored, 1);
+            assert!(get(key).await.unwrap().is_some());
+        });
+    }
+}
@@ -0,0 +1,144 @@
+//! 出站 HTTP 请求
+//!
+//! 基于 reqwest 的 HTTP 客户端，TLS 与邮件、LDAP 客户端一样使用系统原生实现，
+//! 适用于调用身份提供方等低频接口，响应体按 JSON 解析。
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use reqwest::{header::ACCEPT, Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+static CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .user_agent("soybean-admin-rust")
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+#[derive(Error, Debug)]
+pub enum HttpError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Unexpected status {0}: {1}")]
+    Status(u16, String),
+    #[error("Invalid JSON response: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub async fn get_json<T: DeserializeOwned>(url: &str) -> Result<T, HttpError> {
+    send(CLIENT.get(url)).await
+}
+
+/// 以 `application/x-www-form-urlencoded` 提交表单
+pub async fn post_form<T: DeserializeOwned>(
+    url: &str,
+    form: &[(&str, &str)],
+) -> Result<T, HttpError> {
+    send(CLIENT.post(url).form(form)).await
+}
+
+async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, HttpError> {
+    let response = request.header(ACCEPT, "application/json").send().await?;
+    let status = response.status();
+    let body = response.bytes().await?;
+    if !status.is_success() {
+        return Err(HttpError::Status(
+            status.as_u16(),
+            String::from_utf8_lossy(&body).into_owned(),
+        ));
+    }
+
+    Ok(serde_json::from_slice(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Echo {
+        ok: bool,
+    }
+
+    /// 返回固定响应的 HTTP 服务器，返回收到的完整请求
+    async fn serve_once(listener: TcpListener, response: &'static str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).into_owned();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length: "))
+                    .map_or(0, |length| length.parse().unwrap());
+                if n == 0 || body.len() >= length {
+                    break;
+                }
+            }
+        }
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8_lossy(&request).into_owned()
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_post_form() {
+        runtime().block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let server = tokio::spawn(serve_once(
+                listener,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 11\r\n\r\n{\"ok\":true}",
+            ));
+
+            let echo: Echo = post_form(
+                &format!("http://127.0.0.1:{}/token?x=1", port),
+                &[("code", "a b"), ("grant_type", "authorization_code")],
+            )
+            .await
+            .unwrap();
+            assert!(echo.ok);
+
+            let request = server.await.unwrap();
+            assert!(request.starts_with("POST /token?x=1 HTTP/1.1\r\n"));
+            assert!(request.contains(&format!("host: 127.0.0.1:{}", port)));
+            assert!(request.ends_with("code=a+b&grant_type=authorization_code"));
+        });
+    }
+
+    #[test]
+    fn test_error_status() {
+        runtime().block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            tokio::spawn(serve_once(
+                listener,
+                "HTTP/1.1 400 Bad Request\r\nContent-Length: 15\r\n\r\ninvalid_request",
+            ));
+
+            let result = get_json::<Echo>(&format!("http://127.0.0.1:{}/", port)).await;
+            assert!(
+                matches!(result, Err(HttpError::Status(400, body)) if body == "invalid_request")
+            );
+        });
+    }
+}
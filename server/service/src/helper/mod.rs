@@ -1,3 +1,5 @@
+pub mod cache_helper;
 pub mod db_helper;
+pub mod http_helper;
 pub mod mongo_helper;
 pub mod redis_helper;
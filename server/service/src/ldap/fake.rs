@@ -0,0 +1,289 @@
+//! 测试用的进程内 LDAP 服务器
+//!
+//! 支持简单绑定、按过滤器搜索与解绑，未绑定的连接搜索不到任何条目。
+use std::sync::Arc;
+
+use bytes::BytesMut;
+use ldap3::asn1::{parse_tag, parse_uint, write, StructureTag, TagClass, PL};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+pub const ADMIN_DN: &str = "cn=admin,dc=example,dc=org";
+pub const ADMIN_PASSWORD: &str = "admin";
+pub const ALICE_DN: &str = "uid=alice,ou=people,dc=example,dc=org";
+pub const ADMINS_GROUP: &str = "cn=admins,ou=groups,dc=example,dc=org";
+pub const STAFF_GROUP: &str = "cn=staff,ou=groups,dc=example,dc=org";
+
+pub struct FakeEntry {
+    pub dn: String,
+    pub password: String,
+    pub attributes: Vec<(String, Vec<String>)>,
+}
+
+impl FakeEntry {
+    pub fn new(dn: &str, password: &str, attributes: &[(&str, &[&str])]) -> Self {
+        Self {
+            dn: dn.to_string(),
+            password: password.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|(name, values)| {
+                    (
+                        name.to_string(),
+                        values.iter().map(ToString::to_string).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn values(&self, attribute: &str) -> Option<&[String]> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(attribute))
+            .map(|(_, values)| values.as_slice())
+    }
+}
+
+pub struct FakeDirectory {
+    pub entries: Vec<FakeEntry>,
+}
+
+impl FakeDirectory {
+    /// 服务账号 admin，用户 alice（属于 admins 与 staff 组）和 bob，以及两个 uid 重复的条目
+    pub fn sample() -> Self {
+        Self {
+            entries: vec![
+                FakeEntry::new(
+                    ADMIN_DN,
+                    ADMIN_PASSWORD,
+                    &[("objectClass", &["organizationalRole"])],
+                ),
+                FakeEntry::new(
+                    ALICE_DN,
+                    "wonderland",
+                    &[
+                        ("objectClass", &["person", "inetOrgPerson"]),
+                        ("uid", &["alice"]),
+                        ("cn", &["Alice Liddell"]),
+                        ("mail", &["alice@example.org"]),
+                        ("memberOf", &[ADMINS_GROUP, STAFF_GROUP]),
+                    ],
+                ),
+                FakeEntry::new(
+                    "uid=bob,ou=people,dc=example,dc=org",
+                    "builder",
+                    &[("objectClass", &["person"]), ("uid", &["bob"])],
+                ),
+                FakeEntry::new(
+                    "uid=twin,ou=people,dc=example,dc=org",
+                    "twin",
+                    &[("objectClass", &["person"]), ("uid", &["twin"])],
+                ),
+                FakeEntry::new(
+                    "uid=twin,ou=contractors,dc=example,dc=org",
+                    "twin",
+                    &[("objectClass", &["person"]), ("uid", &["twin"])],
+                ),
+            ],
+        }
+    }
+
+    /// 在随机端口上启动服务器，返回 `ldap://` 地址
+    pub async fn start(self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ldap://{}", listener.local_addr().unwrap());
+        let directory = Arc::new(self);
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(directory.clone().serve(stream));
+            }
+        });
+
+        url
+    }
+
+    async fn serve(self: Arc<Self>, mut stream: TcpStream) {
+        let mut buffer = BytesMut::new();
+        let mut bound = false;
+        while let Some(message) = read_message(&mut stream, &mut buffer).await {
+            let mut parts = children(message).into_iter();
+            let (Some(id), Some(operation)) = (parts.next(), parts.next()) else {
+                break;
+            };
+
+            let responses = match (operation.class, operation.id) {
+                (TagClass::Application, 0) => {
+                    let fields = children(operation);
+                    let dn = string(&fields[1]);
+                    let password = string(&fields[2]);
+                    bound = self
+                        .entries
+                        .iter()
+                        .any(|entry| entry.dn == dn && entry.password == password);
+                    vec![ldap_result(1, if bound { 0 } else { 49 })]
+                },
+                (TagClass::Application, 3) => self.search(operation, bound),
+                _ => break,
+            };
+
+            for response in responses {
+                let message = constructed(TagClass::Universal, 16, vec![id.clone(), response]);
+                let mut out = BytesMut::new();
+                write::encode_into(&mut out, message).unwrap();
+                if stream.write_all(&out).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn search(&self, operation: StructureTag, bound: bool) -> Vec<StructureTag> {
+        let fields = children(operation);
+        let base = string(&fields[0]);
+        let size_limit = number(&fields[3]) as usize;
+        let filter = &fields[6];
+
+        let matched: Vec<&FakeEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| bound && entry.dn.ends_with(&base) && matches(filter, entry))
+            .collect();
+
+        let mut responses: Vec<StructureTag> = matched
+            .iter()
+            .take(if size_limit == 0 {
+                usize::MAX
+            } else {
+                size_limit
+            })
+            .map(|entry| encode_entry(entry))
+            .collect();
+        let code = if size_limit > 0 && matched.len() > size_limit {
+            4
+        } else {
+            0
+        };
+        responses.push(ldap_result(5, code));
+        responses
+    }
+}
+
+fn matches(filter: &StructureTag, entry: &FakeEntry) -> bool {
+    match (&filter.payload, filter.id) {
+        (PL::C(items), 0) => items.iter().all(|item| matches(item, entry)),
+        (PL::C(items), 1) => items.iter().any(|item| matches(item, entry)),
+        (PL::C(items), 2) => !items.iter().any(|item| matches(item, entry)),
+        (PL::C(items), 3) => {
+            let value = string(&items[1]);
+            entry
+                .values(&string(&items[0]))
+                .is_some_and(|values| values.iter().any(|v| v.eq_ignore_ascii_case(&value)))
+        },
+        (PL::P(attribute), 7) => entry.values(&String::from_utf8_lossy(attribute)).is_some(),
+        _ => false,
+    }
+}
+
+fn encode_entry(entry: &FakeEntry) -> StructureTag {
+    let attributes = entry
+        .attributes
+        .iter()
+        .map(|(name, values)| {
+            constructed(
+                TagClass::Universal,
+                16,
+                vec![
+                    octet_string(name),
+                    constructed(
+                        TagClass::Universal,
+                        17,
+                        values.iter().map(|value| octet_string(value)).collect(),
+                    ),
+                ],
+            )
+        })
+        .collect();
+
+    constructed(
+        TagClass::Application,
+        4,
+        vec![
+            octet_string(&entry.dn),
+            constructed(TagClass::Universal, 16, attributes),
+        ],
+    )
+}
+
+fn ldap_result(id: u64, code: u8) -> StructureTag {
+    constructed(
+        TagClass::Application,
+        id,
+        vec![
+            StructureTag {
+                class: TagClass::Universal,
+                id: 10,
+                payload: PL::P(vec![code]),
+            },
+            octet_string(""),
+            octet_string(""),
+        ],
+    )
+}
+
+fn constructed(class: TagClass, id: u64, inner: Vec<StructureTag>) -> StructureTag {
+    StructureTag {
+        class,
+        id,
+        payload: PL::C(inner),
+    }
+}
+
+fn octet_string(value: &str) -> StructureTag {
+    StructureTag {
+        class: TagClass::Universal,
+        id: 4,
+        payload: PL::P(value.as_bytes().to_vec()),
+    }
+}
+
+fn children(tag: StructureTag) -> Vec<StructureTag> {
+    tag.expect_constructed().unwrap_or_default()
+}
+
+fn string(tag: &StructureTag) -> String {
+    match &tag.payload {
+        PL::P(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        PL::C(_) => String::new(),
+    }
+}
+
+fn number(tag: &StructureTag) -> u64 {
+    match &tag.payload {
+        PL::P(bytes) => parse_uint(bytes)
+            .map(|(_, value)| value)
+            .unwrap_or_default(),
+        PL::C(_) => 0,
+    }
+}
+
+/// 读取一条完整的 LDAPMessage，连接关闭时返回 `None`
+async fn read_message(stream: &mut TcpStream, buffer: &mut BytesMut) -> Option<StructureTag> {
+    loop {
+        match parse_tag(buffer) {
+            Ok((rest, tag)) => {
+                let consumed = buffer.len() - rest.len();
+                let _ = buffer.split_to(consumed);
+                return Some(tag);
+            },
+            Err(e) if e.is_incomplete() => {},
+            Err(_) => return None,
+        }
+        if stream.read_buf(buffer).await.ok()? == 0 {
+            return None;
+        }
+    }
+}
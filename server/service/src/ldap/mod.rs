@@ -0,0 +1,205 @@
+//! LDAP 客户端
+//!
+//! 基于 ldap3 封装认证所需的 LDAPv3 简单绑定与搜索操作，支持 `ldap://` 与 `ldaps://`。
+use std::collections::HashMap;
+
+use ldap3::{Ldap, LdapConnAsync, LdapResult, Scope, SearchEntry, SearchOptions};
+use thiserror::Error;
+
+#[cfg(test)]
+pub(crate) mod fake;
+
+/// 用户名或密码错误
+pub const INVALID_CREDENTIALS: u32 = 49;
+/// 搜索结果超过数量限制
+const SIZE_LIMIT_EXCEEDED: u32 = 4;
+
+#[derive(Error, Debug)]
+pub enum LdapError {
+    #[error("LDAP client error: {0}")]
+    Client(#[from] ldap3::LdapError),
+    #[error("Invalid LDAP filter: {0}")]
+    InvalidFilter(String),
+    #[error("LDAP operation failed with result code {code}: {message}")]
+    Operation { code: u32, message: String },
+    #[error("LDAP request timed out")]
+    Timeout,
+}
+
+/// 搜索结果条目，属性名不区分大小写
+#[derive(Debug, Clone)]
+pub struct LdapEntry {
+    pub dn: String,
+    attributes: HashMap<String, Vec<String>>,
+}
+
+impl LdapEntry {
+    pub fn values(&self, attribute: &str) -> &[String] {
+        self.attributes
+            .get(&attribute.to_ascii_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn first(&self, attribute: &str) -> Option<&str> {
+        self.values(attribute)
+            .first()
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+impl From<SearchEntry> for LdapEntry {
+    fn from(entry: SearchEntry) -> Self {
+        Self {
+            dn: entry.dn,
+            attributes: entry
+                .attrs
+                .into_iter()
+                .map(|(name, values)| (name.to_ascii_lowercase(), values))
+                .collect(),
+        }
+    }
+}
+
+/// 把 `template` 中的占位符替换为转义后的值，并校验得到的过滤器
+pub fn build_filter(template: &str, placeholder: &str, value: &str) -> Result<String, LdapError> {
+    let filter = template.replace(placeholder, &ldap3::ldap_escape(value));
+    ldap3::parse_filter(&filter).map_err(|_| LdapError::InvalidFilter(template.to_string()))?;
+    Ok(filter)
+}
+
+pub struct LdapConnection {
+    ldap: Ldap,
+}
+
+impl LdapConnection {
+    pub async fn connect(url: &str) -> Result<Self, LdapError> {
+        let (conn, ldap) = LdapConnAsync::new(url).await?;
+        ldap3::drive!(conn);
+        Ok(Self { ldap })
+    }
+
+    /// 简单绑定，密码为空时服务器会视为匿名绑定，调用方需自行拒绝空密码
+    pub async fn simple_bind(&mut self, dn: &str, password: &str) -> Result<(), LdapError> {
+        check_result(self.ldap.simple_bind(dn, password).await?)
+    }
+
+    /// 在 `base` 下的整棵子树中搜索，超过 `size_limit` 的条目被丢弃
+    pub async fn search(
+        &mut self,
+        base: &str,
+        filter: &str,
+        attributes: &[&str],
+        size_limit: i32,
+    ) -> Result<Vec<LdapEntry>, LdapError> {
+        let result = self
+            .ldap
+            .with_search_options(SearchOptions::new().sizelimit(size_limit))
+            .search(base, Scope::Subtree, filter, attributes)
+            .await?;
+
+        if result.1.rc != SIZE_LIMIT_EXCEEDED {
+            check_result(result.1)?;
+        }
+        Ok(result
+            .0
+            .into_iter()
+            .filter(|entry| !entry.is_ref() && !entry.is_intermediate())
+            .map(|entry| SearchEntry::construct(entry).into())
+            .collect())
+    }
+
+    pub async fn unbind(mut self) {
+        let _ = self.ldap.unbind().await;
+    }
+}
+
+/// 结果码非 0 时返回错误
+fn check_result(result: LdapResult) -> Result<(), LdapError> {
+    match result.rc {
+        0 => Ok(()),
+        code => Err(LdapError::Operation {
+            code,
+            message: result.text,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fake::FakeDirectory, *};
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_search_then_bind() {
+        runtime().block_on(async {
+            let url = FakeDirectory::sample().start().await;
+            let mut conn = LdapConnection::connect(&url).await.unwrap();
+            conn.simple_bind(fake::ADMIN_DN, fake::ADMIN_PASSWORD)
+                .await
+                .unwrap();
+
+            let filter = build_filter(
+                "(&(objectClass=person)(uid={username}))",
+                "{username}",
+                "alice",
+            )
+            .unwrap();
+            let entries = conn
+                .search("dc=example,dc=org", &filter, &["uid", "cn", "memberOf"], 2)
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 1);
+            let entry = &entries[0];
+            assert_eq!(entry.dn, fake::ALICE_DN);
+            assert_eq!(entry.first("CN"), Some("Alice Liddell"));
+            assert_eq!(entry.values("memberof").len(), 2);
+
+            conn.simple_bind(&entry.dn, "wonderland").await.unwrap();
+            conn.unbind().await;
+        });
+    }
+
+    #[test]
+    fn test_invalid_credentials() {
+        runtime().block_on(async {
+            let url = FakeDirectory::sample().start().await;
+            let mut conn = LdapConnection::connect(&url).await.unwrap();
+            match conn.simple_bind(fake::ALICE_DN, "wrong").await {
+                Err(LdapError::Operation { code, .. }) => assert_eq!(code, INVALID_CREDENTIALS),
+                other => panic!("unexpected result: {:?}", other.map(|_| ())),
+            }
+
+            // 未通过绑定的连接搜索不到任何条目
+            let entries = conn
+                .search("dc=example,dc=org", "(uid=alice)", &[], 2)
+                .await
+                .unwrap();
+            assert!(entries.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_build_filter() {
+        assert_eq!(
+            build_filter(
+                "(&(objectClass=person)(uid={username}))",
+                "{username}",
+                "a*)(uid=b"
+            )
+            .unwrap(),
+            "(&(objectClass=person)(uid=a\\2a\\29\\28uid=b))"
+        );
+        assert!(matches!(
+            build_filter("(uid={username}", "{username}", "alice"),
+            Err(LdapError::InvalidFilter(_))
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod admin;
 mod helper;
+mod ldap;
+pub mod mailer;
 pub use server_constant::definition::Audience;
 pub use server_global::{project_error, project_info};
 pub use server_model::admin::entities::sys_endpoint::Model as SysEndpoint;
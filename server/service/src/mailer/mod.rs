@@ -0,0 +1,147 @@
+//! 邮件发送
+//!
+//! 业务代码通过 [`Mailer`] 发送邮件，默认按配置使用 SMTP 或本地 outbox 目录，
+//! 也可以通过 [`set_mailer`] 替换为其他实现。
+use std::sync::{Arc, PoisonError, RwLock};
+
+use async_trait::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    Message,
+};
+use once_cell::sync::Lazy;
+use server_config::{MailConfig, MailTransport};
+use server_global::global;
+use thiserror::Error;
+use ulid::Ulid;
+
+pub use outbox::OutboxMailer;
+pub use smtp::SmtpMailer;
+
+mod outbox;
+mod smtp;
+
+#[derive(Error, Debug)]
+pub enum MailError {
+    #[error("Mail IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid mail address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+    #[error("Invalid mail message: {0}")]
+    Message(#[from] lettre::error::Error),
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+    #[error("Mail delivery timed out")]
+    Timeout,
+}
+
+/// 待发送的纯文本邮件
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl MailMessage {
+    /// 构建 RFC 5322 邮件，主题与正文按需使用 UTF-8 编码
+    pub fn build(&self, from: &str) -> Result<Message, MailError> {
+        let domain = from.rsplit('@').next().unwrap_or("localhost");
+        let message = Message::builder()
+            .from(from.parse::<Mailbox>()?)
+            .to(self.to.parse::<Mailbox>()?)
+            .subject(&self.subject)
+            .message_id(Some(format!("<{}@{}>", Ulid::new(), domain)))
+            .header(ContentType::TEXT_PLAIN)
+            .body(self.body.clone())?;
+        Ok(message)
+    }
+
+    /// 按 RFC 5322 格式化邮件
+    pub fn format(&self, from: &str) -> Result<String, MailError> {
+        let message = self.build(from)?;
+        Ok(String::from_utf8_lossy(&message.formatted()).into_owned())
+    }
+}
+
+/// 邮件发送器
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: &MailMessage) -> Result<(), MailError>;
+}
+
+static MAILER: Lazy<RwLock<Option<Arc<dyn Mailer>>>> = Lazy::new(|| RwLock::new(None));
+
+/// 替换全局邮件发送器，应在服务启动时调用
+pub fn set_mailer(mailer: Arc<dyn Mailer>) {
+    *MAILER.write().unwrap_or_else(PoisonError::into_inner) = Some(mailer);
+}
+
+/// 获取全局邮件发送器，未设置时按配置创建
+pub async fn mailer() -> Arc<dyn Mailer> {
+    if let Some(mailer) = MAILER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+    {
+        return mailer;
+    }
+
+    let config = global::get_config::<MailConfig>()
+        .await
+        .map(|config| config.as_ref().clone())
+        .unwrap_or_default();
+    let mailer: Arc<dyn Mailer> = match config.transport {
+        MailTransport::Smtp => Arc::new(SmtpMailer::new(config.from, config.smtp)),
+        MailTransport::Outbox => Arc::new(OutboxMailer::new(config.from, config.outbox_dir)),
+    };
+    MAILER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert(mailer)
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    use super::*;
+
+    #[test]
+    fn test_format_message() {
+        let message = MailMessage {
+            to: "user@example.com".to_string(),
+            subject: "重置密码".to_string(),
+            body: "请在 30 分钟内重置密码。\r\n".repeat(10),
+        };
+        let formatted = message.format("no-reply@example.com").unwrap();
+        let (headers, body) = formatted.split_once("\r\n\r\n").unwrap();
+
+        assert!(headers.contains("To: user@example.com"));
+        assert!(headers.contains(&format!(
+            "Subject: =?utf-8?b?{}?=",
+            STANDARD.encode("重置密码")
+        )));
+        assert!(headers.contains("@example.com>"));
+        assert!(headers.contains("Content-Transfer-Encoding: base64"));
+        assert!(body.lines().all(|line| line.len() <= 76));
+        assert_eq!(
+            STANDARD.decode(body.replace("\r\n", "")).unwrap(),
+            message.body.into_bytes()
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_address() {
+        let message = MailMessage {
+            to: "user@example.com>\r\nBcc: other@example.com".to_string(),
+            subject: "Hello".to_string(),
+            body: "World".to_string(),
+        };
+        assert!(matches!(
+            message.format("no-reply@example.com"),
+            Err(MailError::Address(_))
+        ));
+    }
+}
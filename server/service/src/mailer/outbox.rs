@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Local;
+use ulid::Ulid;
+
+use super::{MailError, MailMessage, Mailer};
+
+/// 将邮件以 `.eml` 文件写入本地目录，用于开发和离线测试
+pub struct OutboxMailer {
+    from: String,
+    dir: PathBuf,
+}
+
+impl OutboxMailer {
+    pub fn new(from: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            from: from.into(),
+            dir: dir.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for OutboxMailer {
+    async fn send(&self, message: &MailMessage) -> Result<(), MailError> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            Local::now().format("%Y%m%d%H%M%S"),
+            Ulid::new()
+        ));
+        tokio::fs::write(path, message.format(&self.from)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outbox_writes_message() {
+        let dir = std::env::temp_dir().join(format!("soybean-outbox-{}", Ulid::new()));
+        let mailer = OutboxMailer::new("no-reply@example.com", &dir);
+        let message = MailMessage {
+            to: "user@example.com".to_string(),
+            subject: "Hello".to_string(),
+            body: "World".to_string(),
+        };
+
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(mailer.send(&message))
+            .unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        let content = std::fs::read_to_string(files[0].as_ref().unwrap().path()).unwrap();
+        assert!(content.contains("To: user@example.com"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lettre::{
+    transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport,
+    Tokio1Executor,
+};
+use server_config::{SmtpConfig, SmtpSecurity};
+
+use super::{MailError, MailMessage, Mailer};
+
+/// 通过 SMTP 服务器发送邮件，支持明文、STARTTLS 与 TLS 连接
+pub struct SmtpMailer {
+    from: String,
+    config: SmtpConfig,
+}
+
+impl SmtpMailer {
+    pub fn new(from: impl Into<String>, config: SmtpConfig) -> Self {
+        Self {
+            from: from.into(),
+            config,
+        }
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>, MailError> {
+        let host = self.config.host.as_str();
+        let builder = match self.config.security {
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+        };
+        let mut builder = builder
+            .port(self.config.port)
+            .timeout(Some(Duration::from_secs(self.config.timeout)));
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(builder.build())
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: &MailMessage) -> Result<(), MailError> {
+        let message = message.build(&self.from)?;
+        let transport = self.transport()?;
+        tokio::time::timeout(
+            Duration::from_secs(self.config.timeout),
+            transport.send(message),
+        )
+        .await
+        .map_err(|_| MailError::Timeout)??;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// 模拟 SMTP 服务器，返回收到的全部命令
+    async fn fake_server(listener: TcpListener) -> Vec<String> {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        let mut received = Vec::new();
+        let mut in_data = false;
+
+        stream
+            .get_mut()
+            .write_all(b"220 fake ESMTP\r\n")
+            .await
+            .unwrap();
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            let line = line.trim_end().to_string();
+            let reply: &[u8] = if in_data {
+                if line != "." {
+                    received.push(line);
+                    continue;
+                }
+                in_data = false;
+                b"250 queued\r\n"
+            } else if line.starts_with("EHLO") {
+                b"250-fake\r\n250 AUTH LOGIN\r\n"
+            } else if line == "AUTH LOGIN" {
+                b"334 VXNlcm5hbWU6\r\n"
+            } else if line == STANDARD.encode("user") {
+                b"334 UGFzc3dvcmQ6\r\n"
+            } else if line == STANDARD.encode("secret") {
+                b"235 authenticated\r\n"
+            } else if line == "DATA" {
+                in_data = true;
+                b"354 go ahead\r\n"
+            } else if line == "QUIT" {
+                received.push(line);
+                stream.get_mut().write_all(b"221 bye\r\n").await.unwrap();
+                break;
+            } else {
+                b"250 ok\r\n"
+            };
+            if !in_data || line == "DATA" {
+                received.push(line);
+            }
+            stream.get_mut().write_all(reply).await.unwrap();
+        }
+        received
+    }
+
+    #[test]
+    fn test_smtp_delivery() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let server = tokio::spawn(fake_server(listener));
+
+            let mailer = SmtpMailer::new(
+                "no-reply@example.com",
+                SmtpConfig {
+                    host: "127.0.0.1".to_string(),
+                    port,
+                    username: Some("user".to_string()),
+                    password: Some("secret".to_string()),
+                    security: SmtpSecurity::None,
+                    timeout: 5,
+                },
+            );
+            mailer
+                .send(&MailMessage {
+                    to: "user@example.com".to_string(),
+                    subject: "Hello".to_string(),
+                    body: ".hidden".to_string(),
+                })
+                .await
+                .unwrap();
+
+            let received = server.await.unwrap();
+            assert!(received.contains(&"MAIL FROM:<no-reply@example.com>".to_string()));
+            assert!(received.contains(&"RCPT TO:<user@example.com>".to_string()));
+            assert!(received.contains(&"To: user@example.com".to_string()));
+            assert_eq!(received.last().map(String::as_str), Some("QUIT"));
+        });
+    }
+
+    #[test]
+    fn test_smtp_rejected_command() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            tokio::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                stream.write_all(b"554 no service\r\n").await.unwrap();
+            });
+
+            let mailer = SmtpMailer::new(
+                "no-reply@example.com",
+                SmtpConfig {
+                    host: "127.0.0.1".to_string(),
+                    port,
+                    security: SmtpSecurity::None,
+                    timeout: 5,
+                    ..Default::default()
+                },
+            );
+            let result = mailer
+                .send(&MailMessage {
+                    to: "user@example.com".to_string(),
+                    subject: "Hello".to_string(),
+                    body: "World".to_string(),
+                })
+                .await;
+            assert!(matches!(result, Err(MailError::Smtp(_))));
+        });
+    }
+}
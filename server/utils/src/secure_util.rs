@@ -12,6 +12,39 @@ lazy_static! {
 
 pub struct SecureUtil;
 
+/// 密码包含的字符类别
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordCharClasses {
+    pub uppercase: bool,
+    pub lowercase: bool,
+    pub digit: bool,
+    pub special: bool,
+}
+
+impl PasswordCharClasses {
+    pub fn of(password: &str) -> Self {
+        password.chars().fold(Self::default(), |mut classes, c| {
+            if c.is_uppercase() {
+                classes.uppercase = true;
+            } else if c.is_lowercase() {
+                classes.lowercase = true;
+            } else if c.is_ascii_digit() {
+                classes.digit = true;
+            } else if !c.is_whitespace() {
+                classes.special = true;
+            }
+            classes
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        [self.uppercase, self.lowercase, self.digit, self.special]
+            .iter()
+            .filter(|&&present| present)
+            .count()
+    }
+}
+
 impl SecureUtil {
     pub fn hash_password(password: &[u8]) -> Result<String, Box<dyn Error>> {
         let salt = SaltString::generate(&mut OsRng);
@@ -27,6 +60,28 @@ impl SecureUtil {
             Err(e) => Err(Box::new(e)),
         }
     }
+
+    /// 估算密码强度（0-4）
+    ///
+    /// 按包含的字符类别计分，长度不足 8 位减一分、达到 12 位加一分，
+    /// 由单一字符重复组成的密码强度为 0
+    pub fn password_strength(password: &str) -> u8 {
+        let length = password.chars().count();
+        let mut chars = password.chars();
+        let first = chars.next();
+        if length == 0 || chars.all(|c| Some(c) == first) {
+            return 0;
+        }
+
+        let mut score = PasswordCharClasses::of(password).count() as u8;
+        if length >= 12 {
+            score += 1;
+        }
+        if length < 8 {
+            score = score.saturating_sub(1);
+        }
+        score.min(4)
+    }
 }
 
 #[cfg(test)]
@@ -51,6 +106,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_password_char_classes() {
+        let classes = PasswordCharClasses::of("Abc123!");
+        assert!(classes.uppercase && classes.lowercase && classes.digit && classes.special);
+        assert_eq!(PasswordCharClasses::of("abc").count(), 1);
+        assert_eq!(PasswordCharClasses::of("").count(), 0);
+    }
+
+    #[test]
+    fn test_password_strength() {
+        assert_eq!(SecureUtil::password_strength(""), 0);
+        assert_eq!(SecureUtil::password_strength("aaaaaaaaaaaa"), 0);
+        assert_eq!(SecureUtil::password_strength("123456"), 0);
+        assert_eq!(SecureUtil::password_strength("password1"), 2);
+        assert_eq!(SecureUtil::password_strength("Password1!"), 4);
+        assert_eq!(SecureUtil::password_strength("correct horse battery"), 2);
+    }
+
     #[test]
     fn test_print_hashed_password() {
         let password = b"123456";